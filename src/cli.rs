@@ -0,0 +1,41 @@
+pub enum Command {
+    Serve,
+    CreateAdmin {
+        name: String,
+        email: String,
+        password: String,
+    },
+}
+
+pub const USAGE: &str =
+    "Usage: backend [create-admin --email <EMAIL> --password <PASSWORD> [--name <NAME>]]";
+
+impl Command {
+    pub fn from_args(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        match args.next().as_deref() {
+            None => Ok(Command::Serve),
+            Some("create-admin") => {
+                let mut name = "Admin".to_string();
+                let mut email = None;
+                let mut password = None;
+                while let Some(flag) = args.next() {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| format!("Missing value for {flag}"))?;
+                    match flag.as_str() {
+                        "--name" => name = value,
+                        "--email" => email = Some(value),
+                        "--password" => password = Some(value),
+                        _ => return Err(format!("Unknown argument: {flag}")),
+                    }
+                }
+                Ok(Command::CreateAdmin {
+                    name,
+                    email: email.ok_or("--email is required")?,
+                    password: password.ok_or("--password is required")?,
+                })
+            }
+            Some(other) => Err(format!("Unknown command: {other}")),
+        }
+    }
+}
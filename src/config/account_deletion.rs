@@ -0,0 +1,22 @@
+#[derive(Debug, Clone)]
+pub struct AccountDeletionConfig {
+    /// 申请删除后保留账户的天数，期间可以重新激活，之后由后台任务彻底删除
+    pub grace_period_days: i64,
+}
+
+impl Default for AccountDeletionConfig {
+    fn default() -> Self {
+        AccountDeletionConfig {
+            grace_period_days: std::env::var("ACCOUNT_DELETION_GRACE_DAYS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse::<i64>()
+                .expect("ACCOUNT_DELETION_GRACE_DAYS should be a i64 number"),
+        }
+    }
+}
+
+impl AccountDeletionConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
@@ -1,3 +1,17 @@
+/// 多租户数据的隔离方式
+///
+/// - `Column`：所有租户共用同一个数据库，按 `tenant_id` 字段区分。只有一个连接，
+///   跨租户统计和迁移都很简单，但隔离依赖每条查询都带上租户条件。
+/// - `Database`：每个租户使用独立的 SurrealDB 数据库（`<SURREAL_ROOT_DB>_<tenant>`），
+///   数据在存储层分开，漏写租户条件也不会越界；代价是每个租户一条长连接，
+///   租户必须在 `TENANTS` 中登记，迁移在租户首次访问时才执行，管理员统计、后台清理等只作用于根数据库。
+///   请求的租户只按子域名解析，因为认证本身就要查询租户数据库
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TenantIsolation {
+    Column,
+    Database,
+}
+
 #[derive(Debug, Clone)]
 pub struct DatabaseConfig {
     pub surreal_url: String,
@@ -5,6 +19,16 @@ pub struct DatabaseConfig {
     pub surreal_root_password: String,
     pub surreal_root_ns: String,
     pub surreal_root_db: String,
+    /// 瞬时错误（连接中断、超时）的最大重试次数，0 表示不重试
+    pub retry_attempts: u32,
+    /// 首次重试前的等待时间，之后每次翻倍
+    pub retry_base_delay_ms: u64,
+    /// 单次查询超过该毫秒数时输出慢查询警告，0 表示关闭
+    pub slow_query_threshold_ms: u64,
+    pub tenant_isolation: TenantIsolation,
+    /// 按数据库隔离时允许建库的租户，来自逗号分隔的 TENANTS；不在其中的子域名直接返回 404，
+    /// 不会建立连接或执行迁移，连接缓存的大小也因此有上限
+    pub tenants: Vec<String>,
 }
 
 impl Default for DatabaseConfig {
@@ -15,6 +39,32 @@ impl Default for DatabaseConfig {
             surreal_root_password: std::env::var("SURREAL_ROOT_PASSWORD").expect("SURREAL_ROOT_PASSWORD must be set"),
             surreal_root_ns: std::env::var("SURREAL_ROOT_NS").expect("SURREAL_ROOT_NS must be set"),
             surreal_root_db: std::env::var("SURREAL_ROOT_DB").expect("SURREAL_ROOT_DB must be set"),
+            retry_attempts: std::env::var("DB_RETRY_ATTEMPTS")
+                .unwrap_or_else(|_| "3".to_string())
+                .parse::<u32>()
+                .expect("DB_RETRY_ATTEMPTS should be a u32 number"),
+            retry_base_delay_ms: std::env::var("DB_RETRY_BASE_DELAY_MS")
+                .unwrap_or_else(|_| "50".to_string())
+                .parse::<u64>()
+                .expect("DB_RETRY_BASE_DELAY_MS should be a u64 number"),
+            slow_query_threshold_ms: std::env::var("SLOW_QUERY_THRESHOLD_MS")
+                .unwrap_or_else(|_| "200".to_string())
+                .parse::<u64>()
+                .expect("SLOW_QUERY_THRESHOLD_MS should be a u64 number"),
+            tenant_isolation: match std::env::var("TENANT_ISOLATION")
+                .unwrap_or_else(|_| "column".to_string())
+                .as_str()
+            {
+                "column" => TenantIsolation::Column,
+                "database" => TenantIsolation::Database,
+                _ => panic!("TENANT_ISOLATION should be column or database"),
+            },
+            tenants: std::env::var("TENANTS")
+                .unwrap_or_default()
+                .split(',')
+                .map(|tenant| tenant.trim().to_lowercase())
+                .filter(|tenant| !tenant.is_empty())
+                .collect(),
         }
     }
 }
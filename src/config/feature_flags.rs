@@ -0,0 +1,47 @@
+use std::collections::HashMap;
+
+#[derive(Debug, Clone)]
+pub struct FeatureFlagConfig {
+    /// 数据库中没有记录时使用的默认值，格式为 name=true,other=false
+    pub defaults: HashMap<String, bool>,
+    /// 从数据库刷新内存缓存的间隔秒数
+    pub refresh_interval: u64,
+}
+
+impl Default for FeatureFlagConfig {
+    fn default() -> Self {
+        FeatureFlagConfig {
+            defaults: std::env::var("FEATURE_FLAGS")
+                .map(|flags| parse_flags(&flags))
+                .unwrap_or_default(),
+            refresh_interval: std::env::var("FEATURE_FLAGS_REFRESH_INTERVAL")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse::<u64>()
+                .expect("FEATURE_FLAGS_REFRESH_INTERVAL should be a u64 number"),
+        }
+    }
+}
+
+impl FeatureFlagConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+fn parse_flags(flags: &str) -> HashMap<String, bool> {
+    flags
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (name, enabled) = entry
+                .split_once('=')
+                .expect("FEATURE_FLAGS entries should be name=true or name=false");
+            let enabled = enabled
+                .trim()
+                .parse::<bool>()
+                .expect("FEATURE_FLAGS values should be true or false");
+            (name.trim().to_string(), enabled)
+        })
+        .collect()
+}
@@ -1,12 +1,29 @@
+use axum::http::HeaderName;
+use axum::http::header::{ACCEPT, ACCEPT_LANGUAGE, AUTHORIZATION, CONTENT_TYPE, IF_NONE_MATCH};
+
+use crate::models::device::DEVICE_FINGERPRINT_HEADER;
+
+/// 应用自身读取的请求头（语言协商、ETag 条件请求、设备绑定指纹），始终允许跨域携带
+const APP_REQUEST_HEADERS: [HeaderName; 3] = [
+    ACCEPT_LANGUAGE,
+    IF_NONE_MATCH,
+    HeaderName::from_static(DEVICE_FINGERPRINT_HEADER),
+];
+
 #[derive(Debug, Clone)]
 pub struct FrontendConfig {
     pub frontend_url: String,
+    /// CORS 允许的请求头，已合并应用自身需要的请求头
+    pub cors_allow_headers: Vec<HeaderName>,
 }
 
 impl Default for FrontendConfig {
     fn default() -> Self {
         FrontendConfig {
-            frontend_url: std::env::var("FRONTEND_URL").expect("FRONTEND_URL must be set")
+            frontend_url: std::env::var("FRONTEND_URL").expect("FRONTEND_URL must be set"),
+            cors_allow_headers: cors_allow_headers(
+                std::env::var("CORS_ALLOW_HEADERS").ok().as_deref(),
+            ),
         }
     }
 }
@@ -15,4 +32,27 @@ impl FrontendConfig {
     pub fn new() -> Self {
         Self::default()
     }
-}
\ No newline at end of file
+}
+
+/// 解析逗号分隔的请求头列表，未设置时使用 Authorization、Accept、Content-Type
+fn cors_allow_headers(raw: Option<&str>) -> Vec<HeaderName> {
+    let mut headers: Vec<HeaderName> = match raw {
+        Some(raw) => raw
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(|name| {
+                HeaderName::from_bytes(name.as_bytes()).unwrap_or_else(|_| {
+                    panic!("CORS_ALLOW_HEADERS contains an invalid header name: {name}")
+                })
+            })
+            .collect(),
+        None => vec![AUTHORIZATION, ACCEPT, CONTENT_TYPE],
+    };
+    for header in APP_REQUEST_HEADERS {
+        if !headers.contains(&header) {
+            headers.push(header);
+        }
+    }
+    headers
+}
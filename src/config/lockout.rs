@@ -0,0 +1,26 @@
+#[derive(Debug, Clone)]
+pub struct LockoutConfig {
+    pub max_failed_attempts: u32,
+    pub lockout_minutes: i64,
+}
+
+impl Default for LockoutConfig {
+    fn default() -> Self {
+        LockoutConfig {
+            max_failed_attempts: std::env::var("MAX_FAILED_LOGIN_ATTEMPTS")
+                .unwrap_or_else(|_| "5".to_string())
+                .parse::<u32>()
+                .expect("MAX_FAILED_LOGIN_ATTEMPTS should be a u32 number"),
+            lockout_minutes: std::env::var("ACCOUNT_LOCKOUT_MINUTES")
+                .unwrap_or_else(|_| "15".to_string())
+                .parse::<i64>()
+                .expect("ACCOUNT_LOCKOUT_MINUTES should be a i64 number"),
+        }
+    }
+}
+
+impl LockoutConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
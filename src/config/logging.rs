@@ -0,0 +1,76 @@
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::EnvFilter;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    /// 多行带颜色的输出，适合本地开发
+    Pretty,
+    /// 每行一个 JSON 对象，事件字段展开到顶层，便于 ELK、Loki 等日志系统采集
+    Json,
+    Compact,
+}
+
+impl LogFormat {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "pretty" => Some(LogFormat::Pretty),
+            "json" => Some(LogFormat::Json),
+            "compact" => Some(LogFormat::Compact),
+            _ => None,
+        }
+    }
+}
+
+/// 日志在读取其他配置之前初始化，因此不放在 `Config` 中，而是单独读取
+#[derive(Debug, Clone)]
+pub struct LoggingConfig {
+    pub format: LogFormat,
+    /// 本 crate 的日志级别
+    pub app_level: LevelFilter,
+    /// 依赖库的日志级别
+    pub dependency_level: LevelFilter,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        // debug 构建默认 pretty，release 构建默认 json
+        let default_format = if cfg!(debug_assertions) {
+            "pretty"
+        } else {
+            "json"
+        };
+        LoggingConfig {
+            format: LogFormat::from_str(
+                &std::env::var("LOG_FORMAT").unwrap_or_else(|_| default_format.to_string()),
+            )
+            .expect("LOG_FORMAT should be pretty, json or compact"),
+            app_level: std::env::var("LOG_LEVEL")
+                .unwrap_or_else(|_| "info".to_string())
+                .parse::<LevelFilter>()
+                .expect("LOG_LEVEL should be off, error, warn, info, debug or trace"),
+            dependency_level: std::env::var("LOG_DEPENDENCY_LEVEL")
+                .unwrap_or_else(|_| "warn".to_string())
+                .parse::<LevelFilter>()
+                .expect("LOG_DEPENDENCY_LEVEL should be off, error, warn, info, debug or trace"),
+        }
+    }
+}
+
+impl LoggingConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// 设置了 RUST_LOG 时以它为准，否则按 crate 和依赖库分别设置级别
+    pub fn env_filter(&self) -> EnvFilter {
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| {
+            // TraceLayer 的请求日志由应用自己配置，跟随本 crate 的级别
+            EnvFilter::new(format!(
+                "{},{}={},tower_http::trace={}",
+                self.dependency_level,
+                env!("CARGO_CRATE_NAME"),
+                self.app_level,
+                self.app_level
+            ))
+        })
+    }
+}
@@ -0,0 +1,29 @@
+use crate::models::maintenance::MaintenanceMode;
+
+#[derive(Debug, Clone)]
+pub struct MaintenanceConfig {
+    /// 数据库中没有维护记录时使用的模式
+    pub default_mode: MaintenanceMode,
+    pub retry_after_seconds: u64,
+}
+
+impl Default for MaintenanceConfig {
+    fn default() -> Self {
+        MaintenanceConfig {
+            default_mode: MaintenanceMode::from_str(
+                &std::env::var("MAINTENANCE_MODE").unwrap_or_else(|_| "off".to_string()),
+            )
+            .expect("MAINTENANCE_MODE should be off, read_only or offline"),
+            retry_after_seconds: std::env::var("MAINTENANCE_RETRY_AFTER")
+                .unwrap_or_else(|_| "300".to_string())
+                .parse::<u64>()
+                .expect("MAINTENANCE_RETRY_AFTER should be a u64 number"),
+        }
+    }
+}
+
+impl MaintenanceConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
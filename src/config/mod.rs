@@ -1,11 +1,28 @@
+use crate::config::account_deletion::AccountDeletionConfig;
 use crate::config::database::DatabaseConfig;
+use crate::config::feature_flags::FeatureFlagConfig;
 use crate::config::frontend::FrontendConfig;
+use crate::config::lockout::LockoutConfig;
+use crate::config::maintenance::MaintenanceConfig;
+use crate::config::network::NetworkConfig;
+use crate::config::password::PasswordPolicy;
+use crate::config::rate_limit::UserRateLimitConfig;
+use crate::config::seed::SeedConfig;
 use crate::config::server::ServerConfig;
 use crate::config::token::TokenConfig;
 
 pub mod server;
+pub mod account_deletion;
 pub mod database;
+pub mod feature_flags;
 pub mod frontend;
+pub mod lockout;
+pub mod logging;
+pub mod maintenance;
+pub mod network;
+pub mod password;
+pub mod rate_limit;
+pub mod seed;
 pub mod token;
 
 #[derive(Debug, Clone)]
@@ -13,7 +30,15 @@ pub struct Config {
     pub server_config: ServerConfig,
     pub db_config: DatabaseConfig,
     pub frontend_config: FrontendConfig,
-    pub token_config: TokenConfig
+    pub token_config: TokenConfig,
+    pub seed_config: SeedConfig,
+    pub password_policy: PasswordPolicy,
+    pub lockout_config: LockoutConfig,
+    pub feature_flag_config: FeatureFlagConfig,
+    pub maintenance_config: MaintenanceConfig,
+    pub user_rate_limit_config: UserRateLimitConfig,
+    pub account_deletion_config: AccountDeletionConfig,
+    pub network_config: NetworkConfig,
 }
 
 impl Default for Config {
@@ -23,6 +48,14 @@ impl Default for Config {
             db_config: DatabaseConfig::new(),
             frontend_config: FrontendConfig::new(),
             token_config: TokenConfig::new(),
+            seed_config: SeedConfig::new(),
+            password_policy: PasswordPolicy::new(),
+            lockout_config: LockoutConfig::new(),
+            feature_flag_config: FeatureFlagConfig::new(),
+            maintenance_config: MaintenanceConfig::new(),
+            user_rate_limit_config: UserRateLimitConfig::new(),
+            account_deletion_config: AccountDeletionConfig::new(),
+            network_config: NetworkConfig::new(),
         }
     }
 }
@@ -0,0 +1,56 @@
+use std::net::IpAddr;
+
+use ipnet::IpNet;
+
+#[derive(Debug, Clone)]
+pub struct NetworkConfig {
+    /// 反向代理或负载均衡的地址段，只有直连对端在其中时才信任 X-Forwarded-For
+    pub trusted_proxies: Vec<IpNet>,
+    /// 非空时只有这些地址段可以访问管理员路由
+    pub admin_allowlist: Vec<IpNet>,
+    /// 始终拒绝访问管理员路由的地址段，优先于白名单
+    pub admin_denylist: Vec<IpNet>,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        NetworkConfig {
+            trusted_proxies: networks("TRUSTED_PROXIES"),
+            admin_allowlist: networks("ADMIN_IP_ALLOWLIST"),
+            admin_denylist: networks("ADMIN_IP_DENYLIST"),
+        }
+    }
+}
+
+impl NetworkConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_trusted_proxy(&self, ip: IpAddr) -> bool {
+        self.trusted_proxies.iter().any(|net| net.contains(&ip))
+    }
+
+    pub fn is_admin_ip_allowed(&self, ip: IpAddr) -> bool {
+        if self.admin_denylist.iter().any(|net| net.contains(&ip)) {
+            return false;
+        }
+        self.admin_allowlist.is_empty() || self.admin_allowlist.iter().any(|net| net.contains(&ip))
+    }
+}
+
+/// 解析逗号分隔的 CIDR 列表，单个地址视为只包含自身的网段
+fn networks(var: &str) -> Vec<IpNet> {
+    std::env::var(var)
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            entry
+                .parse::<IpNet>()
+                .or_else(|_| entry.parse::<IpAddr>().map(IpNet::from))
+                .unwrap_or_else(|_| panic!("{var} contains an invalid CIDR or IP address: {entry}"))
+        })
+        .collect()
+}
@@ -0,0 +1,142 @@
+use serde::Serialize;
+use std::borrow::Cow;
+use utoipa::ToSchema;
+use validator::{ValidationError, ValidationErrors};
+
+/// bcrypt 只使用前 72 字节，超出部分会被静默截断；多字节字符下按字符计数的上限不足以保证这一点
+pub const BCRYPT_MAX_BYTES: usize = 72;
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub max_length: usize,
+    pub require_uppercase: bool,
+    pub require_lowercase: bool,
+    pub require_digit: bool,
+    pub require_symbol: bool,
+    pub check_breached: bool,
+    /// 禁止重复使用最近的 N 个密码，0 表示不限制
+    pub history_depth: usize,
+    #[serde(skip)]
+    pub breach_check_timeout_ms: u64,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        PasswordPolicy {
+            min_length: std::env::var("PASSWORD_MIN_LENGTH")
+                .unwrap_or_else(|_| "8".to_string())
+                .parse::<usize>()
+                .expect("PASSWORD_MIN_LENGTH should be a usize number"),
+            max_length: std::env::var("PASSWORD_MAX_LENGTH")
+                .unwrap_or_else(|_| "64".to_string())
+                .parse::<usize>()
+                .expect("PASSWORD_MAX_LENGTH should be a usize number"),
+            require_uppercase: std::env::var("PASSWORD_REQUIRE_UPPERCASE")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse::<bool>()
+                .expect("PASSWORD_REQUIRE_UPPERCASE should be true or false"),
+            require_lowercase: std::env::var("PASSWORD_REQUIRE_LOWERCASE")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse::<bool>()
+                .expect("PASSWORD_REQUIRE_LOWERCASE should be true or false"),
+            require_digit: std::env::var("PASSWORD_REQUIRE_DIGIT")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse::<bool>()
+                .expect("PASSWORD_REQUIRE_DIGIT should be true or false"),
+            require_symbol: std::env::var("PASSWORD_REQUIRE_SYMBOL")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse::<bool>()
+                .expect("PASSWORD_REQUIRE_SYMBOL should be true or false"),
+            check_breached: std::env::var("PASSWORD_CHECK_BREACHED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse::<bool>()
+                .expect("PASSWORD_CHECK_BREACHED should be true or false"),
+            history_depth: std::env::var("PASSWORD_HISTORY_DEPTH")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse::<usize>()
+                .expect("PASSWORD_HISTORY_DEPTH should be a usize number"),
+            breach_check_timeout_ms: std::env::var("PASSWORD_BREACH_CHECK_TIMEOUT_MS")
+                .unwrap_or_else(|_| "2000".to_string())
+                .parse::<u64>()
+                .expect("PASSWORD_BREACH_CHECK_TIMEOUT_MS should be a u64 number"),
+        }
+    }
+}
+
+impl PasswordPolicy {
+    pub fn new() -> Self {
+        let policy = Self::default();
+        assert!(
+            policy.min_length >= 1 && policy.min_length <= policy.max_length,
+            "PASSWORD_MIN_LENGTH should be between 1 and PASSWORD_MAX_LENGTH"
+        );
+        assert!(
+            policy.max_length <= BCRYPT_MAX_BYTES,
+            "PASSWORD_MAX_LENGTH should not exceed 72 (bcrypt input limit)"
+        );
+        policy
+    }
+
+    /// 收集所有未满足的规则，每条规则对应一个带 code 和 message 的 ValidationError，
+    /// 追加到调用方的 errors 中以便和其他字段的错误一起返回
+    pub fn collect_errors(
+        &self,
+        field: &'static str,
+        password: &str,
+        errors: &mut ValidationErrors,
+    ) {
+        let length = password.chars().count();
+
+        if length < self.min_length {
+            let mut error = ValidationError::new("length").with_message(Cow::Owned(format!(
+                "Password must be at least {} characters",
+                self.min_length
+            )));
+            error.add_param(Cow::Borrowed("min"), &self.min_length);
+            errors.add(field, error);
+        }
+        if length > self.max_length {
+            let mut error = ValidationError::new("length").with_message(Cow::Owned(format!(
+                "Password must not be more than {} characters",
+                self.max_length
+            )));
+            error.add_param(Cow::Borrowed("max"), &self.max_length);
+            errors.add(field, error);
+        } else if password.len() > BCRYPT_MAX_BYTES {
+            let mut error = ValidationError::new("bytes").with_message(Cow::Owned(format!(
+                "Password must not be more than {BCRYPT_MAX_BYTES} bytes"
+            )));
+            error.add_param(Cow::Borrowed("max"), &BCRYPT_MAX_BYTES);
+            errors.add(field, error);
+        }
+        if self.require_uppercase && !password.chars().any(|c| c.is_uppercase()) {
+            errors.add(
+                field,
+                ValidationError::new("uppercase")
+                    .with_message(Cow::Borrowed("Password must contain an uppercase letter")),
+            );
+        }
+        if self.require_lowercase && !password.chars().any(|c| c.is_lowercase()) {
+            errors.add(
+                field,
+                ValidationError::new("lowercase")
+                    .with_message(Cow::Borrowed("Password must contain a lowercase letter")),
+            );
+        }
+        if self.require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+            errors.add(
+                field,
+                ValidationError::new("digit")
+                    .with_message(Cow::Borrowed("Password must contain a digit")),
+            );
+        }
+        if self.require_symbol && !password.chars().any(|c| !c.is_alphanumeric()) {
+            errors.add(
+                field,
+                ValidationError::new("symbol")
+                    .with_message(Cow::Borrowed("Password must contain a symbol")),
+            );
+        }
+    }
+}
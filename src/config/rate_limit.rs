@@ -0,0 +1,34 @@
+#[derive(Debug, Clone)]
+pub struct UserRateLimitConfig {
+    /// 全局限流按 IP 计算，开启后认证路由额外按 claims.sub 限流
+    pub enabled: bool,
+    /// 每个用户每分钟补充的请求数
+    pub requests_per_minute: u32,
+    /// 令牌桶容量，即允许的最大突发请求数
+    pub burst_size: u32,
+}
+
+impl Default for UserRateLimitConfig {
+    fn default() -> Self {
+        UserRateLimitConfig {
+            enabled: std::env::var("USER_RATE_LIMIT_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse::<bool>()
+                .expect("USER_RATE_LIMIT_ENABLED should be true or false"),
+            requests_per_minute: std::env::var("USER_RATE_LIMIT_PER_MINUTE")
+                .unwrap_or_else(|_| "120".to_string())
+                .parse::<u32>()
+                .expect("USER_RATE_LIMIT_PER_MINUTE should be a u32 number"),
+            burst_size: std::env::var("USER_RATE_LIMIT_BURST")
+                .unwrap_or_else(|_| "20".to_string())
+                .parse::<u32>()
+                .expect("USER_RATE_LIMIT_BURST should be a u32 number"),
+        }
+    }
+}
+
+impl UserRateLimitConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
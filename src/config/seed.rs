@@ -0,0 +1,22 @@
+#[derive(Debug, Clone)]
+pub struct SeedConfig {
+    pub admin_email: Option<String>,
+    pub admin_password: Option<String>,
+    pub admin_name: String,
+}
+
+impl Default for SeedConfig {
+    fn default() -> Self {
+        SeedConfig {
+            admin_email: std::env::var("SEED_ADMIN_EMAIL").ok(),
+            admin_password: std::env::var("SEED_ADMIN_PASSWORD").ok(),
+            admin_name: std::env::var("SEED_ADMIN_NAME").unwrap_or_else(|_| "Admin".to_string()),
+        }
+    }
+}
+
+impl SeedConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
@@ -1,6 +1,17 @@
 #[derive(Debug, Clone)]
 pub struct ServerConfig {
     pub server_port: u16,
+    pub stats_stream_interval: u64,
+    pub enable_compression: bool,
+    pub log_bodies: bool,
+    pub default_page_size: u32,
+    pub max_page_size: u32,
+    pub tenant_base_domain: Option<String>,
+    pub wrap_responses: bool,
+    /// 收到停机信号后等待进行中请求完成的最长秒数，超时后强制退出
+    pub shutdown_drain_timeout: u64,
+    /// 仅用于本地开发：错误响应的 details 中附带底层错误和查询标签，生产环境必须关闭
+    pub verbose_errors: bool,
 }
 
 impl Default for ServerConfig {
@@ -10,6 +21,41 @@ impl Default for ServerConfig {
                 .expect("SERVER_PORT must be set").
                 parse::<u16>()
                 .expect("SERVER_PORT should be a u16 number"),
+            stats_stream_interval: std::env::var("STATS_STREAM_INTERVAL")
+                .unwrap_or_else(|_| "5".to_string())
+                .parse::<u64>()
+                .expect("STATS_STREAM_INTERVAL should be a u64 number"),
+            enable_compression: std::env::var("ENABLE_COMPRESSION")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse::<bool>()
+                .expect("ENABLE_COMPRESSION should be true or false"),
+            log_bodies: std::env::var("LOG_REQUEST_BODIES")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse::<bool>()
+                .expect("LOG_REQUEST_BODIES should be true or false"),
+            default_page_size: std::env::var("DEFAULT_PAGE_SIZE")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse::<u32>()
+                .expect("DEFAULT_PAGE_SIZE should be a u32 number"),
+            max_page_size: std::env::var("MAX_PAGE_SIZE")
+                .unwrap_or_else(|_| "100".to_string())
+                .parse::<u32>()
+                .expect("MAX_PAGE_SIZE should be a u32 number"),
+            tenant_base_domain: std::env::var("TENANT_BASE_DOMAIN")
+                .ok()
+                .map(|domain| domain.trim_start_matches('.').to_lowercase()),
+            wrap_responses: std::env::var("WRAP_SUCCESS_RESPONSES")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse::<bool>()
+                .expect("WRAP_SUCCESS_RESPONSES should be true or false"),
+            shutdown_drain_timeout: std::env::var("SHUTDOWN_DRAIN_TIMEOUT")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse::<u64>()
+                .expect("SHUTDOWN_DRAIN_TIMEOUT should be a u64 number"),
+            verbose_errors: std::env::var("VERBOSE_ERRORS")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse::<bool>()
+                .expect("VERBOSE_ERRORS should be true or false"),
         }
     }
 }
@@ -1,12 +1,66 @@
+use std::collections::HashMap;
+
+use crate::models::token_claims::ExtraClaimType;
+
+/// 达到会话上限时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionLimitStrategy {
+    EvictOldest,
+    Reject,
+}
+
+/// 访问令牌格式：自包含的 JWT，或只能通过会话表解析的随机字符串
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessTokenFormat {
+    Jwt,
+    Opaque,
+}
+
 #[derive(Debug, Clone)]
 pub struct TokenConfig {
     pub jwt_access_secret: String,
     pub jwt_refresh_secret: String,
+    pub jwt_access_kid: String,
+    pub jwt_refresh_kid: String,
+    pub jwt_previous_access_keys: Vec<(String, String)>,
+    pub jwt_previous_refresh_keys: Vec<(String, String)>,
     pub email_verification_secret: String,
     pub password_reset_secret: String,
     pub access_token_expires_in: i64,
     pub refresh_token_expires_in: i64,
+    pub email_verification_expires_in: i64,
+    pub password_reset_expires_in: i64,
     pub token_cleanup_interval: i64,
+    pub trusted_session_days: i64,
+    /// 只作用于没有 expires_at 的旧会话记录（0009 迁移之前创建的）：最后活跃超过该天数后被清理。
+    /// 有 expires_at 的会话在刷新令牌过期后即被清理，不受该配置影响
+    pub session_retention_days: Option<i64>,
+    /// 会话超过该秒数没有活动即视为失效，即使刷新令牌尚未过期；未配置时不限制。
+    /// 启用后每个认证请求都会查询会话表并刷新 last_active_at
+    pub session_idle_timeout: Option<i64>,
+    pub max_sessions_per_user: u32,
+    pub session_limit_strategy: SessionLimitStrategy,
+    /// 同一用户从相同设备指纹和 IP 登录时复用已有的活跃会话，而不是新建会话
+    pub one_session_per_device: bool,
+    /// 每个用户同一时间只保留一个活跃会话：新登录会先撤销该用户的所有其他会话
+    pub single_active_session: bool,
+    /// 把访问令牌绑定到签发时的设备（User-Agent 加 X-Device-Fingerprint 请求头），
+    /// 其他设备出示该令牌会被拒绝；客户端必须每次请求都带上同样的指纹头
+    pub device_binding: bool,
+    /// 校验过期时间时容忍的时钟偏差秒数，令牌签名校验和 `is_expired` 共用
+    pub clock_skew_leeway: u64,
+    /// token_sessions 表总行数超过该值时在系统统计中标记告警，通常意味着清理任务没有正常运行
+    pub session_table_alert_threshold: u64,
+    /// 登录和刷新时同时以 HttpOnly Cookie 下发令牌。Cookie 会被浏览器自动携带，
+    /// 重新引入 CSRF 风险：保持 SameSite=Strict（或 Lax 并配合 CSRF token），
+    /// 并确保 FRONTEND_URL 只配置可信来源
+    pub cookie_delivery: bool,
+    pub cookie_secure: bool,
+    pub cookie_same_site: String,
+    /// 允许写入访问令牌的自定义声明及其类型，未配置时不做限制；租户和设备绑定声明始终允许
+    pub extra_claims_schema: Option<HashMap<String, ExtraClaimType>>,
+    /// opaque 模式下撤销会话立即生效且不暴露声明，代价是每次请求都要查询会话表
+    pub access_token_format: AccessTokenFormat,
 }
 
 impl Default for TokenConfig {
@@ -15,6 +69,12 @@ impl Default for TokenConfig {
             jwt_access_secret: std::env::var("JWT_ACCESS_SECRET")
                 .expect("JWT_ACCESS_SECRET must be set"),
             jwt_refresh_secret: std::env::var("JWT_REFRESH_SECRET").expect("JWT_REFRESH_SECRET"),
+            jwt_access_kid: std::env::var("JWT_ACCESS_KID")
+                .unwrap_or_else(|_| "access-v1".to_string()),
+            jwt_refresh_kid: std::env::var("JWT_REFRESH_KID")
+                .unwrap_or_else(|_| "refresh-v1".to_string()),
+            jwt_previous_access_keys: parse_previous_keys("JWT_PREVIOUS_ACCESS_KEYS"),
+            jwt_previous_refresh_keys: parse_previous_keys("JWT_PREVIOUS_REFRESH_KEYS"),
             email_verification_secret: std::env::var("EMAIL_VERIFICATION_SECRET")
                 .expect("EMAIL_VERIFICATION_SECRET"),
             password_reset_secret: std::env::var("PASSWORD_RESET_SECRET")
@@ -27,10 +87,90 @@ impl Default for TokenConfig {
                 .expect("REFRESH_TOKEN_EXPIRES_IN")
                 .parse::<i64>()
                 .expect("REFRESH_TOKEN_EXPIRES_IN should be a i64 number"),
+            email_verification_expires_in: std::env::var("EMAIL_VERIFICATION_EXPIRES_IN")
+                .unwrap_or_else(|_| "86400".to_string())
+                .parse::<i64>()
+                .expect("EMAIL_VERIFICATION_EXPIRES_IN should be a i64 number"),
+            password_reset_expires_in: std::env::var("PASSWORD_RESET_EXPIRES_IN")
+                .unwrap_or_else(|_| "3600".to_string())
+                .parse::<i64>()
+                .expect("PASSWORD_RESET_EXPIRES_IN should be a i64 number"),
             token_cleanup_interval: std::env::var("TOKEN_CLEANUP_INTERVAL")
                 .expect("TOKEN_CLEANUP_INTERVAL")
                 .parse::<i64>()
                 .expect("TOKEN_CLEANUP_INTERVAL should be a i64 number"),
+            trusted_session_days: std::env::var("TRUSTED_SESSION_DAYS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse::<i64>()
+                .expect("TRUSTED_SESSION_DAYS should be a i64 number"),
+            session_retention_days: std::env::var("SESSION_RETENTION_DAYS")
+                .ok()
+                .map(|days| {
+                    days.parse::<i64>()
+                        .expect("SESSION_RETENTION_DAYS should be a i64 number")
+                }),
+            session_idle_timeout: std::env::var("SESSION_IDLE_TIMEOUT")
+                .ok()
+                .map(|seconds| {
+                    seconds.parse::<i64>()
+                        .expect("SESSION_IDLE_TIMEOUT should be a i64 number")
+                }),
+            max_sessions_per_user: std::env::var("MAX_SESSIONS_PER_USER")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse::<u32>()
+                .expect("MAX_SESSIONS_PER_USER should be a u32 number"),
+            session_limit_strategy: match std::env::var("SESSION_LIMIT_STRATEGY")
+                .unwrap_or_else(|_| "evict".to_string())
+                .as_str()
+            {
+                "evict" => SessionLimitStrategy::EvictOldest,
+                "reject" => SessionLimitStrategy::Reject,
+                _ => panic!("SESSION_LIMIT_STRATEGY should be evict or reject"),
+            },
+            one_session_per_device: std::env::var("ONE_SESSION_PER_DEVICE")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse::<bool>()
+                .expect("ONE_SESSION_PER_DEVICE should be true or false"),
+            single_active_session: std::env::var("SINGLE_ACTIVE_SESSION")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse::<bool>()
+                .expect("SINGLE_ACTIVE_SESSION should be true or false"),
+            device_binding: std::env::var("TOKEN_DEVICE_BINDING")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse::<bool>()
+                .expect("TOKEN_DEVICE_BINDING should be true or false"),
+            clock_skew_leeway: std::env::var("TOKEN_CLOCK_SKEW_LEEWAY")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse::<u64>()
+                .expect("TOKEN_CLOCK_SKEW_LEEWAY should be a u64 number"),
+            session_table_alert_threshold: std::env::var("SESSION_TABLE_ALERT_THRESHOLD")
+                .unwrap_or_else(|_| "100000".to_string())
+                .parse::<u64>()
+                .expect("SESSION_TABLE_ALERT_THRESHOLD should be a u64 number"),
+            cookie_delivery: std::env::var("TOKEN_COOKIE_DELIVERY")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse::<bool>()
+                .expect("TOKEN_COOKIE_DELIVERY should be true or false"),
+            cookie_secure: std::env::var("COOKIE_SECURE")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse::<bool>()
+                .expect("COOKIE_SECURE should be true or false"),
+            cookie_same_site: match std::env::var("COOKIE_SAME_SITE")
+                .unwrap_or_else(|_| "Strict".to_string())
+                .as_str()
+            {
+                same_site @ ("Strict" | "Lax" | "None") => same_site.to_string(),
+                _ => panic!("COOKIE_SAME_SITE should be Strict, Lax or None"),
+            },
+            extra_claims_schema: parse_extra_claims_schema("EXTRA_CLAIMS_SCHEMA"),
+            access_token_format: match std::env::var("ACCESS_TOKEN_FORMAT")
+                .unwrap_or_else(|_| "jwt".to_string())
+                .as_str()
+            {
+                "jwt" => AccessTokenFormat::Jwt,
+                "opaque" => AccessTokenFormat::Opaque,
+                _ => panic!("ACCESS_TOKEN_FORMAT should be jwt or opaque"),
+            },
         }
     }
 }
@@ -39,4 +179,87 @@ impl TokenConfig {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// 按 kid 查找访问令牌的验证密钥，轮换期间旧密钥仍然有效
+    pub fn access_secret_for(&self, kid: &str) -> Option<&str> {
+        find_secret(
+            kid,
+            &self.jwt_access_kid,
+            &self.jwt_access_secret,
+            &self.jwt_previous_access_keys,
+        )
+    }
+
+    pub fn refresh_secret_for(&self, kid: &str) -> Option<&str> {
+        find_secret(
+            kid,
+            &self.jwt_refresh_kid,
+            &self.jwt_refresh_secret,
+            &self.jwt_previous_refresh_keys,
+        )
+    }
+
+    /// 没有 expires_at 的旧会话最后活跃后保留多久才会被清理，未配置时为刷新令牌有效期再加一天宽限
+    pub fn session_retention(&self) -> chrono::Duration {
+        match self.session_retention_days {
+            Some(days) => chrono::Duration::days(days),
+            None => {
+                chrono::Duration::seconds(self.refresh_token_expires_in) + chrono::Duration::days(1)
+            }
+        }
+    }
+}
+
+// 格式为 "kid1:secret1,kid2:secret2"，secret 本身可以包含冒号
+fn parse_previous_keys(name: &str) -> Vec<(String, String)> {
+    std::env::var(name)
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (kid, secret) = entry
+                .split_once(':')
+                .unwrap_or_else(|| panic!("{} entries should be in kid:secret format", name));
+            (kid.to_string(), secret.to_string())
+        })
+        .collect()
+}
+
+// 格式为 "plan:string,beta:bool"，类型为 string、number、bool、array、object 或 any
+fn parse_extra_claims_schema(name: &str) -> Option<HashMap<String, ExtraClaimType>> {
+    let value = std::env::var(name).ok()?;
+    let schema = value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (key, claim_type) = entry
+                .split_once(':')
+                .unwrap_or_else(|| panic!("{} entries should be in key:type format", name));
+            let claim_type = ExtraClaimType::parse(claim_type.trim()).unwrap_or_else(|| {
+                panic!(
+                    "{} types should be string, number, bool, array, object or any",
+                    name
+                )
+            });
+            (key.trim().to_string(), claim_type)
+        })
+        .collect();
+    Some(schema)
+}
+
+fn find_secret<'a>(
+    kid: &str,
+    current_kid: &str,
+    current_secret: &'a str,
+    previous_keys: &'a [(String, String)],
+) -> Option<&'a str> {
+    if kid == current_kid {
+        return Some(current_secret);
+    }
+    previous_keys
+        .iter()
+        .find(|(previous_kid, _)| previous_kid == kid)
+        .map(|(_, secret)| secret.as_str())
 }
@@ -0,0 +1,53 @@
+use std::sync::Arc;
+
+use crate::{
+    database::{tables::FEATURE_FLAGS, timing::TimedQuery},
+    errors::{core::Result, db::DatabaseError},
+    models::feature_flag::FeatureFlag,
+    state::AppState,
+};
+
+/// 功能开关对全平台生效，按数据库隔离租户时也始终读写根数据库
+#[derive(Debug, Clone)]
+pub struct FeatureFlagRepository;
+
+impl FeatureFlagRepository {
+    pub fn new() -> Self {
+        Self
+    }
+    pub async fn list(&self, app_state: Arc<AppState>) -> Result<Vec<FeatureFlag>> {
+        let flags: Vec<FeatureFlag> = app_state
+            .root_db
+            .query(format!(
+                "SELECT name, enabled, updated_at FROM {FEATURE_FLAGS}"
+            ))
+            .timed(&app_state, "SELECT feature flags")
+            .await?
+            .take(0)
+            .map_err(|e| DatabaseError::query_failed(e, Some("Take query result".to_string())))?;
+        Ok(flags)
+    }
+    pub async fn upsert(
+        &self,
+        app_state: Arc<AppState>,
+        name: String,
+        enabled: bool,
+    ) -> Result<FeatureFlag> {
+        let flags: Vec<FeatureFlag> = app_state
+            .root_db
+            .query(format!(
+                "UPSERT type::thing('{FEATURE_FLAGS}', $name) \
+                 SET name = $name, enabled = $enabled, updated_at = time::now() \
+                 RETURN name, enabled, updated_at"
+            ))
+            .bind(("name", name))
+            .bind(("enabled", enabled))
+            .timed(&app_state, "UPSERT feature flag")
+            .await?
+            .take(0)
+            .map_err(|e| DatabaseError::query_failed(e, Some("Take query result".to_string())))?;
+        flags.into_iter().next().ok_or_else(|| {
+            DatabaseError::NotFound("Failed to upsert feature flag".to_string()).into()
+        })
+    }
+}
@@ -0,0 +1,56 @@
+use std::sync::Arc;
+
+use crate::{
+    database::{tables::MAINTENANCE, timing::TimedQuery},
+    errors::{core::Result, db::DatabaseError},
+    models::maintenance::{MaintenanceMode, MaintenanceStatus},
+    state::AppState,
+};
+
+/// 维护状态对全平台生效，按数据库隔离租户时也始终读写根数据库
+#[derive(Debug, Clone)]
+pub struct MaintenanceRepository;
+
+impl MaintenanceRepository {
+    pub fn new() -> Self {
+        Self
+    }
+    pub async fn find(&self, app_state: Arc<AppState>) -> Result<Option<MaintenanceStatus>> {
+        let status: Vec<MaintenanceStatus> = app_state
+            .root_db
+            .query(format!(
+                "SELECT mode, message, retry_after_seconds, updated_at FROM {MAINTENANCE}:current"
+            ))
+            .timed(&app_state, "SELECT maintenance status")
+            .await?
+            .take(0)
+            .map_err(|e| DatabaseError::query_failed(e, Some("Take query result".to_string())))?;
+        Ok(status.into_iter().next())
+    }
+    pub async fn upsert(
+        &self,
+        app_state: Arc<AppState>,
+        mode: MaintenanceMode,
+        message: Option<String>,
+        retry_after_seconds: u64,
+    ) -> Result<MaintenanceStatus> {
+        let status: Vec<MaintenanceStatus> = app_state
+            .root_db
+            .query(format!(
+                "UPSERT {MAINTENANCE}:current \
+                 SET mode = $mode, message = $message, retry_after_seconds = $retry_after_seconds, \
+                 updated_at = time::now() \
+                 RETURN mode, message, retry_after_seconds, updated_at"
+            ))
+            .bind(("mode", mode))
+            .bind(("message", message))
+            .bind(("retry_after_seconds", retry_after_seconds))
+            .timed(&app_state, "UPSERT maintenance status")
+            .await?
+            .take(0)
+            .map_err(|e| DatabaseError::query_failed(e, Some("Take query result".to_string())))?;
+        status.into_iter().next().ok_or_else(|| {
+            DatabaseError::NotFound("Failed to update maintenance status".to_string()).into()
+        })
+    }
+}
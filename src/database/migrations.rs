@@ -0,0 +1,254 @@
+use crate::{
+    database::{tables, transaction::with_transaction},
+    errors::{core::Result, db::DatabaseError},
+    state::AppState,
+};
+use std::sync::Arc;
+use tracing::info;
+
+pub struct Migration {
+    pub name: &'static str,
+    pub statements: &'static str,
+}
+
+/// 按顺序执行，已经应用过的迁移不能修改，只能追加新的迁移
+/// 早期迁移使用 IF NOT EXISTS，以兼容引入迁移记录之前已经初始化过的数据库
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        name: "0001_create_users",
+        statements: "
+        DEFINE TABLE IF NOT EXISTS users SCHEMAFULL;
+        DEFINE FIELD IF NOT EXISTS id ON users TYPE string;
+        DEFINE FIELD IF NOT EXISTS name ON users TYPE string;
+        DEFINE FIELD IF NOT EXISTS email ON users TYPE string;
+        DEFINE FIELD IF NOT EXISTS password ON users TYPE string;
+        DEFINE FIELD IF NOT EXISTS role ON users TYPE string;
+        DEFINE FIELD IF NOT EXISTS verified ON users TYPE bool;
+        DEFINE FIELD IF NOT EXISTS created_at ON users TYPE datetime;
+        DEFINE FIELD IF NOT EXISTS updated_at ON users TYPE datetime;
+        DEFINE INDEX IF NOT EXISTS email_idx ON users COLUMNS email UNIQUE;
+    ",
+    },
+    Migration {
+        name: "0002_create_token_sessions",
+        statements: "
+        DEFINE TABLE IF NOT EXISTS token_sessions SCHEMAFULL;
+        DEFINE FIELD IF NOT EXISTS id ON token_sessions TYPE string;
+        DEFINE FIELD IF NOT EXISTS user_id ON token_sessions TYPE string;
+        DEFINE FIELD IF NOT EXISTS access_token_jti ON token_sessions TYPE string;
+        DEFINE FIELD IF NOT EXISTS refresh_token_jti ON token_sessions TYPE string;
+        DEFINE FIELD IF NOT EXISTS created_at ON token_sessions TYPE datetime;
+        DEFINE FIELD IF NOT EXISTS last_active_at ON token_sessions TYPE datetime;
+        DEFINE FIELD IF NOT EXISTS is_active ON token_sessions TYPE bool;
+        DEFINE FIELD IF NOT EXISTS device_info ON token_sessions TYPE option<string>;
+        DEFINE FIELD IF NOT EXISTS ip_address ON token_sessions TYPE option<string>;
+        DEFINE FIELD IF NOT EXISTS location ON token_sessions TYPE option<string>;
+        DEFINE INDEX IF NOT EXISTS access_jti_idx ON token_sessions COLUMNS access_token_jti;
+        DEFINE INDEX IF NOT EXISTS refresh_jti_idx ON token_sessions COLUMNS refresh_token_jti;
+    ",
+    },
+    Migration {
+        name: "0003_structured_device_info",
+        statements: "
+        DEFINE FIELD OVERWRITE device_info ON token_sessions FLEXIBLE TYPE option<object | string>;
+    ",
+    },
+    Migration {
+        name: "0004_trusted_sessions",
+        statements: "
+        DEFINE FIELD trusted ON token_sessions TYPE bool DEFAULT false;
+        DEFINE FIELD trusted_until ON token_sessions TYPE option<datetime>;
+    ",
+    },
+    Migration {
+        name: "0005_user_last_login",
+        statements: "
+        DEFINE FIELD last_login_at ON users TYPE option<datetime>;
+    ",
+    },
+    Migration {
+        name: "0006_user_lockout",
+        statements: "
+        DEFINE FIELD failed_login_attempts ON users TYPE int DEFAULT 0;
+        DEFINE FIELD locked_until ON users TYPE option<datetime>;
+    ",
+    },
+    Migration {
+        name: "0007_user_pending_email",
+        statements: "
+        DEFINE FIELD pending_email ON users TYPE option<string>;
+    ",
+    },
+    Migration {
+        name: "0008_user_password_history",
+        statements: "
+        DEFINE FIELD password_history ON users TYPE array<string> DEFAULT [];
+    ",
+    },
+    Migration {
+        name: "0009_session_expires_at",
+        statements: "
+        DEFINE FIELD expires_at ON token_sessions TYPE option<datetime>;
+    ",
+    },
+    Migration {
+        name: "0010_tenant_id",
+        statements: "
+        DEFINE FIELD tenant_id ON users TYPE option<string>;
+        DEFINE FIELD tenant_id ON token_sessions TYPE option<string>;
+        REMOVE INDEX email_idx ON users;
+        DEFINE INDEX tenant_email_idx ON users COLUMNS tenant_id, email UNIQUE;
+    ",
+    },
+    Migration {
+        name: "0011_create_notes",
+        statements: "
+        DEFINE TABLE notes SCHEMAFULL;
+        DEFINE FIELD id ON notes TYPE string;
+        DEFINE FIELD user_id ON notes TYPE string;
+        DEFINE FIELD title ON notes TYPE string;
+        DEFINE FIELD content ON notes TYPE string;
+        DEFINE FIELD created_at ON notes TYPE datetime;
+        DEFINE FIELD updated_at ON notes TYPE datetime;
+        DEFINE INDEX note_user_idx ON notes COLUMNS user_id;
+    ",
+    },
+    Migration {
+        name: "0012_user_notification_preferences",
+        statements: "
+        DEFINE FIELD notification_preferences ON users FLEXIBLE TYPE option<object>;
+    ",
+    },
+    Migration {
+        name: "0013_session_device_fingerprint",
+        statements: "
+        DEFINE FIELD device_fingerprint ON token_sessions TYPE option<string>;
+        DEFINE INDEX session_fingerprint_idx ON token_sessions COLUMNS user_id, device_fingerprint;
+    ",
+    },
+    Migration {
+        name: "0014_create_feature_flags",
+        statements: "
+        DEFINE TABLE feature_flags SCHEMAFULL;
+        DEFINE FIELD name ON feature_flags TYPE string;
+        DEFINE FIELD enabled ON feature_flags TYPE bool;
+        DEFINE FIELD updated_at ON feature_flags TYPE datetime;
+    ",
+    },
+    Migration {
+        name: "0015_create_maintenance",
+        statements: "
+        DEFINE TABLE maintenance SCHEMAFULL;
+        DEFINE FIELD mode ON maintenance TYPE string;
+        DEFINE FIELD message ON maintenance TYPE option<string>;
+        DEFINE FIELD retry_after_seconds ON maintenance TYPE int;
+        DEFINE FIELD updated_at ON maintenance TYPE datetime;
+    ",
+    },
+    Migration {
+        name: "0016_user_recovery_codes",
+        statements: "
+        DEFINE FIELD recovery_codes ON users TYPE array<string> DEFAULT [];
+    ",
+    },
+    Migration {
+        name: "0017_session_opaque_access_tokens",
+        statements: "
+        DEFINE FIELD access_token_hash ON token_sessions TYPE option<string>;
+        DEFINE FIELD access_claims ON token_sessions FLEXIBLE TYPE option<object>;
+        DEFINE INDEX access_token_hash_idx ON token_sessions COLUMNS access_token_hash;
+    ",
+    },
+    Migration {
+        name: "0018_user_deleted_at",
+        statements: "
+        DEFINE FIELD deleted_at ON users TYPE option<datetime>;
+        DEFINE INDEX user_deleted_at_idx ON users COLUMNS deleted_at;
+    ",
+    },
+    Migration {
+        name: "0019_session_device_binding",
+        statements: "
+        DEFINE FIELD device_binding ON token_sessions TYPE option<string>;
+    ",
+    },
+    Migration {
+        name: "0020_lowercase_roles",
+        statements: "
+        UPDATE users SET role = string::lowercase(role);
+        UPDATE token_sessions SET access_claims.role = string::lowercase(access_claims.role)
+            WHERE access_claims.role != NONE;
+    ",
+    },
+    Migration {
+        name: "0021_create_oauth_connections",
+        statements: "
+        DEFINE TABLE oauth_connections SCHEMAFULL;
+        DEFINE FIELD id ON oauth_connections TYPE string;
+        DEFINE FIELD user_id ON oauth_connections TYPE string;
+        DEFINE FIELD provider ON oauth_connections TYPE string;
+        DEFINE FIELD subject ON oauth_connections TYPE string;
+        DEFINE FIELD email ON oauth_connections TYPE option<string>;
+        DEFINE FIELD created_at ON oauth_connections TYPE datetime;
+        DEFINE INDEX oauth_connection_user_idx ON oauth_connections COLUMNS user_id;
+        DEFINE INDEX oauth_connection_identity_idx ON oauth_connections COLUMNS user_id, provider, subject UNIQUE;
+    ",
+    },
+    Migration {
+        name: "0022_note_tenant_id",
+        statements: "
+        DEFINE FIELD tenant_id ON notes TYPE option<string>;
+        DEFINE INDEX note_tenant_idx ON notes COLUMNS tenant_id;
+        UPDATE notes SET tenant_id = type::thing('users', user_id).tenant_id;
+    ",
+    },
+];
+
+pub async fn run_migrations(app_state: Arc<AppState>) -> Result<()> {
+    let define_failed = |e| DatabaseError::query_failed(e, Some("DEFINE migrations".to_string()));
+    app_state
+        .db()
+        .query(format!(
+            "
+        DEFINE TABLE IF NOT EXISTS {table} SCHEMAFULL;
+        DEFINE FIELD IF NOT EXISTS name ON {table} TYPE string;
+        DEFINE FIELD IF NOT EXISTS applied_at ON {table} TYPE datetime;
+        DEFINE INDEX IF NOT EXISTS migration_name_idx ON {table} COLUMNS name UNIQUE;
+    ",
+            table = tables::MIGRATIONS
+        ))
+        .await
+        .map_err(define_failed)?
+        .check()
+        .map_err(define_failed)?;
+
+    let applied: Vec<String> = app_state
+        .db()
+        .query(format!(
+            "SELECT VALUE name FROM {table}",
+            table = tables::MIGRATIONS
+        ))
+        .await
+        .map_err(|e| DatabaseError::query_failed(e, Some("SELECT applied migrations".to_string())))?
+        .take(0)
+        .map_err(|e| DatabaseError::query_failed(e, Some("Take query result".to_string())))?;
+
+    for migration in MIGRATIONS {
+        if applied.iter().any(|name| name == migration.name) {
+            continue;
+        }
+        with_transaction(app_state.clone(), migration.name, |query| {
+            query
+                .query(migration.statements)
+                .query(format!(
+                    "CREATE {table} CONTENT {{ name: $name, applied_at: time::now() }}",
+                    table = tables::MIGRATIONS
+                ))
+                .bind(("name", migration.name))
+        })
+        .await?;
+        info!("✅ Applied database migration {}", migration.name);
+    }
+
+    Ok(())
+}
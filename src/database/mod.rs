@@ -1,3 +1,16 @@
+pub mod feature_flag;
 pub mod init;
+pub mod maintenance;
+pub mod migrations;
+pub mod note;
+pub mod oauth_connection;
+pub mod repository;
+pub mod retry;
+pub mod seed;
+pub mod tables;
+pub mod tenant;
+pub mod timing;
 pub mod token;
-pub mod user;
+pub mod transaction;
+pub mod unique;
+pub mod user;
\ No newline at end of file
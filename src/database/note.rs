@@ -0,0 +1,141 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    database::{repository::Repository, tables::NOTES, timing::TimedQuery},
+    errors::{core::Result, db::DatabaseError},
+    models::note::Note,
+    state::AppState,
+};
+
+#[derive(Debug, Clone)]
+pub struct NoteRepository;
+
+impl NoteRepository {
+    pub fn new() -> Self {
+        Self
+    }
+    pub async fn find_by_user(
+        &self,
+        app_state: Arc<AppState>,
+        user_id: String,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<Note>> {
+        let notes: Vec<Note> = app_state
+            .db()
+            .query(format!(
+                "SELECT * FROM {NOTES} WHERE user_id = $user_id \
+                 ORDER BY created_at DESC LIMIT $limit START $offset"
+            ))
+            .bind(("user_id", user_id))
+            .bind(("limit", limit))
+            .bind(("offset", offset))
+            .timed(&app_state, "SELECT notes by user")
+            .await?
+            .take(0)
+            .map_err(|e| DatabaseError::query_failed(e, Some("Take query result".to_string())))?;
+        Ok(notes)
+    }
+    /// 同一租户内所有用户的笔记，供管理员查看
+    pub async fn find_by_tenant(
+        &self,
+        app_state: Arc<AppState>,
+        tenant_id: Option<String>,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<Note>> {
+        let notes: Vec<Note> = app_state
+            .db()
+            .query(format!(
+                "SELECT * FROM {NOTES} WHERE tenant_id = $tenant_id \
+                 ORDER BY created_at DESC LIMIT $limit START $offset"
+            ))
+            .bind(("tenant_id", tenant_id))
+            .bind(("limit", limit))
+            .bind(("offset", offset))
+            .timed(&app_state, "SELECT notes by tenant")
+            .await?
+            .take(0)
+            .map_err(|e| DatabaseError::query_failed(e, Some("Take query result".to_string())))?;
+        Ok(notes)
+    }
+    pub async fn count_by_tenant(
+        &self,
+        app_state: Arc<AppState>,
+        tenant_id: Option<String>,
+    ) -> Result<u64> {
+        let count: Vec<serde_json::Value> = app_state
+            .db()
+            .query(format!(
+                "SELECT count() FROM {NOTES} WHERE tenant_id = $tenant_id GROUP ALL"
+            ))
+            .bind(("tenant_id", tenant_id))
+            .timed(&app_state, "COUNT notes by tenant")
+            .await?
+            .take(0)
+            .map_err(|e| DatabaseError::query_failed(e, Some("Take query result".to_string())))?;
+        Ok(count
+            .first()
+            .and_then(|result| result.get("count"))
+            .and_then(|count_val| count_val.as_u64())
+            .unwrap_or(0))
+    }
+    pub async fn count_by_user(&self, app_state: Arc<AppState>, user_id: String) -> Result<u64> {
+        let count: Vec<serde_json::Value> = app_state
+            .db()
+            .query(format!(
+                "SELECT count() FROM {NOTES} WHERE user_id = $user_id GROUP ALL"
+            ))
+            .bind(("user_id", user_id))
+            .timed(&app_state, "COUNT notes by user")
+            .await?
+            .take(0)
+            .map_err(|e| DatabaseError::query_failed(e, Some("Take query result".to_string())))?;
+        Ok(count
+            .first()
+            .and_then(|result| result.get("count"))
+            .and_then(|count_val| count_val.as_u64())
+            .unwrap_or(0))
+    }
+}
+
+#[async_trait]
+impl Repository<Note> for NoteRepository {
+    async fn create(&self, app_state: Arc<AppState>, note: Note) -> Result<Note> {
+        let created: Option<Note> = app_state
+            .db()
+            .create((NOTES, &note.id))
+            .content(note)
+            .timed(&app_state, "CREATE note")
+            .await?;
+        created.ok_or(DatabaseError::NotFound("Failed to create note".to_string()).into())
+    }
+    async fn find_by_id(&self, app_state: Arc<AppState>, note_id: String) -> Result<Option<Note>> {
+        let note: Option<Note> = app_state
+            .db()
+            .select((NOTES, note_id.as_str()))
+            .timed(&app_state, "SELECT note by id")
+            .await?;
+        Ok(note)
+    }
+    async fn update(&self, app_state: Arc<AppState>, note: Note) -> Result<Note> {
+        let note_id = note.id.clone();
+        let updated: Option<Note> = app_state
+            .db()
+            .update((NOTES, note_id.as_str()))
+            .content(note)
+            .timed(&app_state, "UPDATE note")
+            .await?;
+        updated.ok_or(DatabaseError::NotFound("Note not found for update".to_string()).into())
+    }
+    async fn delete(&self, app_state: Arc<AppState>, note_id: String) -> Result<()> {
+        let _: Option<Note> = app_state
+            .db()
+            .delete((NOTES, note_id.as_str()))
+            .timed(&app_state, "DELETE note")
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+use std::sync::Arc;
+
+use crate::{
+    database::{tables::OAUTH_CONNECTIONS, timing::TimedQuery},
+    errors::{core::Result, db::DatabaseError},
+    models::oauth_connection::OAuthConnection,
+    state::AppState,
+};
+
+#[derive(Debug, Clone)]
+pub struct OAuthConnectionRepository;
+
+impl OAuthConnectionRepository {
+    pub fn new() -> Self {
+        Self
+    }
+    pub async fn find_by_user(
+        &self,
+        app_state: Arc<AppState>,
+        user_id: String,
+    ) -> Result<Vec<OAuthConnection>> {
+        let connections: Vec<OAuthConnection> = app_state
+            .db()
+            .query(format!(
+                "SELECT * FROM {OAUTH_CONNECTIONS} WHERE user_id = $user_id \
+                 ORDER BY created_at ASC"
+            ))
+            .bind(("user_id", user_id))
+            .timed(&app_state, "SELECT oauth connections by user")
+            .await?
+            .take(0)
+            .map_err(|e| DatabaseError::query_failed(e, Some("Take query result".to_string())))?;
+        Ok(connections)
+    }
+    /// 删除用户在某个提供方下的全部绑定，返回删除的条数
+    pub async fn delete_by_provider(
+        &self,
+        app_state: Arc<AppState>,
+        user_id: String,
+        provider: String,
+    ) -> Result<usize> {
+        let deleted: Vec<OAuthConnection> = app_state
+            .db()
+            .query(format!(
+                "DELETE {OAUTH_CONNECTIONS} WHERE user_id = $user_id AND provider = $provider \
+                 RETURN BEFORE"
+            ))
+            .bind(("user_id", user_id))
+            .bind(("provider", provider))
+            .timed(&app_state, "DELETE oauth connections by provider")
+            .await?
+            .take(0)
+            .map_err(|e| DatabaseError::query_failed(e, Some("Take query result".to_string())))?;
+        Ok(deleted.len())
+    }
+}
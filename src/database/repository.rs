@@ -0,0 +1,15 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{errors::core::Result, state::AppState};
+
+/// 以记录 id 为主键的通用 CRUD 接口，新增资源时实现该 trait 即可复用同一套用法；
+/// 列表和计数需要按用户或租户限定范围，由各仓储自行提供
+#[async_trait]
+pub trait Repository<T: Send + 'static>: Send + Sync {
+    async fn create(&self, app_state: Arc<AppState>, entity: T) -> Result<T>;
+    async fn find_by_id(&self, app_state: Arc<AppState>, id: String) -> Result<Option<T>>;
+    async fn update(&self, app_state: Arc<AppState>, entity: T) -> Result<T>;
+    async fn delete(&self, app_state: Arc<AppState>, id: String) -> Result<()>;
+}
@@ -0,0 +1,57 @@
+use std::time::{Duration, Instant};
+
+use tracing::warn;
+
+use crate::{
+    database::timing::record_query_duration,
+    errors::{core::Result, db::DatabaseError},
+    state::AppState,
+};
+
+/// 遇到瞬时错误时按指数退避重试，其余错误立即返回
+/// 只能用于幂等读取或明确可以安全重放的写入，否则重试可能导致重复写入
+pub async fn with_retry<T, F, Fut>(
+    app_state: &AppState,
+    operation: &str,
+    mut attempt: F,
+) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = surrealdb::Result<T>>,
+{
+    let config = &app_state.env.db_config;
+    let mut retries = 0;
+    loop {
+        let started = Instant::now();
+        let result = attempt().await;
+        record_query_duration(config.slow_query_threshold_ms, operation, started.elapsed());
+        let error = match result {
+            Ok(value) => return Ok(value),
+            Err(e) => DatabaseError::from(e),
+        };
+        if !error.is_transient() || retries >= config.retry_attempts {
+            return Err(match error {
+                DatabaseError::QueryError {
+                    source,
+                    query: None,
+                } => DatabaseError::query_failed(source, Some(operation.to_string())),
+                error => error,
+            }
+            .into());
+        }
+        let delay = Duration::from_millis(
+            config
+                .retry_base_delay_ms
+                .saturating_mul(2u64.saturating_pow(retries)),
+        );
+        retries += 1;
+        warn!(
+            operation,
+            retry = retries,
+            delay_ms = delay.as_millis() as u64,
+            error = ?error,
+            "Transient database error, retrying"
+        );
+        tokio::time::sleep(delay).await;
+    }
+}
@@ -0,0 +1,30 @@
+use crate::{errors::core::Result, state::AppState};
+use std::sync::Arc;
+use tracing::info;
+
+/// 每次启动都可以安全执行：已有管理员时不会重复创建，也不会覆盖其密码
+pub async fn seed_default_admin(app_state: Arc<AppState>) -> Result<()> {
+    let seed_config = &app_state.env.seed_config;
+    let (Some(email), Some(password)) = (
+        seed_config.admin_email.clone(),
+        seed_config.admin_password.clone(),
+    ) else {
+        info!("SEED_ADMIN_EMAIL/SEED_ADMIN_PASSWORD not set, skipping admin seeding.");
+        return Ok(());
+    };
+
+    match app_state
+        .user_service
+        .ensure_admin(
+            app_state.clone(),
+            seed_config.admin_name.clone(),
+            email,
+            password,
+        )
+        .await?
+    {
+        Some(admin) => info!("✅ Seeded default admin {}.", admin.email),
+        None => info!("An admin user already exists, skipping admin seeding."),
+    }
+    Ok(())
+}
@@ -0,0 +1,10 @@
+//! 表名常量，仓库中的 SurrealQL 和记录 id 统一引用这里，避免表名拼写分散在各处
+
+pub const USERS: &str = "users";
+pub const TOKEN_SESSIONS: &str = "token_sessions";
+pub const NOTES: &str = "notes";
+pub const OAUTH_CONNECTIONS: &str = "oauth_connections";
+pub const FEATURE_FLAGS: &str = "feature_flags";
+pub const MAINTENANCE: &str = "maintenance";
+/// 迁移记录表
+pub const MIGRATIONS: &str = "migrations";
@@ -0,0 +1,86 @@
+use std::{collections::HashMap, sync::Arc};
+
+use surrealdb::{Surreal, engine::remote::ws::Client};
+use tokio::sync::{Mutex, OnceCell};
+
+use crate::{
+    database::{init::connect, migrations::run_migrations},
+    errors::{
+        api::ApiError,
+        core::{Error, Result},
+    },
+    state::AppState,
+};
+
+tokio::task_local! {
+    /// 当前请求所属租户的数据库连接，由 tenant_database_middleware 在请求范围内设置
+    pub static CURRENT_TENANT_DB: Surreal<Client>;
+}
+
+// 租户连接只初始化一次，初始化失败时保持为空以便重试
+type TenantConnection = Arc<OnceCell<Surreal<Client>>>;
+
+/// 按数据库隔离租户时，每个已登记（TENANTS）的租户一条长连接，首次访问时建立并执行迁移
+#[derive(Debug, Clone, Default)]
+pub struct TenantDatabases {
+    connections: Arc<Mutex<HashMap<String, TenantConnection>>>,
+}
+
+impl TenantDatabases {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn connection(
+        &self,
+        app_state: Arc<AppState>,
+        tenant_id: &str,
+    ) -> Result<Surreal<Client>> {
+        // Host 头不可信，未登记的租户在建立连接之前就拒绝
+        if !app_state
+            .env
+            .db_config
+            .tenants
+            .iter()
+            .any(|tenant| tenant == tenant_id)
+        {
+            return Err(ApiError::NotFound.into());
+        }
+        // 全局锁只用于取出该租户的初始化单元；建立连接和迁移在锁外进行，
+        // 同一租户并发的首次请求等待同一次初始化，不会阻塞其他租户，失败后下次请求重试
+        let cell = self
+            .connections
+            .lock()
+            .await
+            .entry(tenant_id.to_string())
+            .or_default()
+            .clone();
+        let db = cell
+            .get_or_try_init(|| async {
+                let database =
+                    tenant_database_name(&app_state.env.db_config.surreal_root_db, tenant_id)?;
+                let db = connect(&app_state.env.db_config, &database).await?;
+                CURRENT_TENANT_DB
+                    .scope(db.clone(), run_migrations(app_state.clone()))
+                    .await?;
+                tracing::info!(tenant_id, database, "Tenant database initialized");
+                Ok::<_, Error>(db)
+            })
+            .await?;
+        Ok(db.clone())
+    }
+}
+
+fn tenant_database_name(root_db: &str, tenant_id: &str) -> Result<String> {
+    let valid = !tenant_id.is_empty()
+        && tenant_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(ApiError::InvalidRequest {
+            message: "Invalid tenant identifier".to_string(),
+        }
+        .into());
+    }
+    Ok(format!("{root_db}_{tenant_id}"))
+}
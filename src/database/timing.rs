@@ -0,0 +1,47 @@
+use std::time::{Duration, Instant};
+
+use tracing::{debug, warn};
+
+use crate::{
+    errors::{core::Result, db::DatabaseError},
+    state::AppState,
+};
+
+/// 执行查询并记录耗时，失败时把 operation 作为错误上下文
+pub trait TimedQuery<T>: IntoFuture<Output = surrealdb::Result<T>> + Sized {
+    fn timed(
+        self,
+        app_state: &AppState,
+        operation: &'static str,
+    ) -> impl Future<Output = Result<T>> + Send;
+}
+
+impl<F, T> TimedQuery<T> for F
+where
+    F: IntoFuture<Output = surrealdb::Result<T>> + Send,
+    F::IntoFuture: Send,
+{
+    fn timed(
+        self,
+        app_state: &AppState,
+        operation: &'static str,
+    ) -> impl Future<Output = Result<T>> + Send {
+        let slow_query_threshold = app_state.env.db_config.slow_query_threshold_ms;
+        async move {
+            let started = Instant::now();
+            let result = self.await;
+            record_query_duration(slow_query_threshold, operation, started.elapsed());
+            result.map_err(|e| DatabaseError::query_failed(e, Some(operation.to_string())).into())
+        }
+    }
+}
+
+/// 耗时作为事件挂在当前 span 下，超过阈值时按慢查询告警，阈值为 0 表示关闭慢查询日志
+pub fn record_query_duration(slow_query_threshold_ms: u64, operation: &str, elapsed: Duration) {
+    let duration_ms = elapsed.as_millis() as u64;
+    if slow_query_threshold_ms > 0 && duration_ms >= slow_query_threshold_ms {
+        warn!(operation, duration_ms, "Slow database query");
+    } else {
+        debug!(operation, duration_ms, "Database query completed");
+    }
+}
@@ -1,9 +1,19 @@
 use crate::{
+    database::{
+        retry::with_retry,
+        tables::{TOKEN_SESSIONS, USERS},
+        timing::TimedQuery,
+        unique::at_most_one,
+    },
     errors::{core::Result, db::DatabaseError},
-    models::token_session::TokenSession,
+    models::{
+        device::DeviceInfo,
+        role::Role,
+        token_session::{SessionIpFilter, SessionTokenUpdate, TokenSession},
+    },
     state::AppState,
 };
-use std::sync::Arc;
+use std::{future::IntoFuture, sync::Arc};
 
 #[derive(Debug, Clone)]
 pub struct TokenRepository;
@@ -18,13 +28,11 @@ impl TokenRepository {
         session: TokenSession,
     ) -> Result<TokenSession> {
         let created: Option<TokenSession> = app_state
-            .db
-            .create(("token_sessions", &session.id))
+            .db()
+            .create((TOKEN_SESSIONS, &session.id))
             .content(session)
-            .await
-            .map_err(|e| {
-                DatabaseError::query_failed(e, Some("CREATE token_sessions".to_string()))
-            })?;
+            .timed(&app_state, "CREATE token_sessions")
+            .await?;
         created.ok_or(DatabaseError::NotFound("Failed to create token session".to_string()).into())
     }
     pub async fn find_by_access_token_jti(
@@ -32,47 +40,78 @@ impl TokenRepository {
         app_state: Arc<AppState>,
         jti: String,
     ) -> Result<Option<TokenSession>> {
-        let sessions: Vec<TokenSession> = app_state
-            .db
-            .query("SELECT * FROM token_sessions WHERE access_token_jti = $jti")
-            .bind(("jti", jti))
-            .await
-            .map_err(|e| {
-                DatabaseError::query_failed(e, Some("SELECT by access_token_jti".to_string()))
-            })?
-            .take(0)
-            .map_err(|e| DatabaseError::query_failed(e, Some("Take query result".to_string())))?;
-        Ok(sessions.into_iter().next())
+        let (db, jti) = (&app_state.db(), &jti);
+        let sessions: Vec<TokenSession> = with_retry(
+            &app_state,
+            "SELECT by access_token_jti",
+            move || async move {
+                db.query(format!(
+                    "SELECT * FROM {TOKEN_SESSIONS} WHERE access_token_jti = $jti LIMIT 2"
+                ))
+                .bind(("jti", jti.clone()))
+                .await?
+                .take(0)
+            },
+        )
+        .await?;
+        at_most_one(sessions, "token_sessions.access_token_jti")
     }
     pub async fn find_by_refresh_token_jti(
         &self,
         app_state: Arc<AppState>,
         jti: String,
     ) -> Result<Option<TokenSession>> {
-        let sessions: Vec<TokenSession> = app_state
-            .db
-            .query("SELECT * FROM token_sessions WHERE refresh_token_jti = $jti")
-            .bind(("jti", jti))
-            .await
-            .map_err(|e| {
-                DatabaseError::query_failed(e, Some("SELECT by refresh_token_jti".to_string()))
-            })?
-            .take(0)
-            .map_err(|e| DatabaseError::query_failed(e, Some("Take query result".to_string())))?;
-        Ok(sessions.into_iter().next())
+        let (db, jti) = (&app_state.db(), &jti);
+        let sessions: Vec<TokenSession> = with_retry(
+            &app_state,
+            "SELECT by refresh_token_jti",
+            move || async move {
+                db.query(format!(
+                    "SELECT * FROM {TOKEN_SESSIONS} WHERE refresh_token_jti = $jti LIMIT 2"
+                ))
+                .bind(("jti", jti.clone()))
+                .await?
+                .take(0)
+            },
+        )
+        .await?;
+        at_most_one(sessions, "token_sessions.refresh_token_jti")
     }
-    pub async fn revoke_session(&self, app_state: Arc<AppState>, session_id: String) -> Result<()> {
-        let _: Option<TokenSession> = app_state
-            .db
-            .update(("token_sessions", session_id.as_str()))
+    pub async fn find_by_access_token_hash(
+        &self,
+        app_state: Arc<AppState>,
+        token_hash: String,
+    ) -> Result<Option<TokenSession>> {
+        let (db, token_hash) = (&app_state.db(), &token_hash);
+        let sessions: Vec<TokenSession> = with_retry(
+            &app_state,
+            "SELECT by access_token_hash",
+            move || async move {
+                db.query(format!(
+                    "SELECT * FROM {TOKEN_SESSIONS} WHERE access_token_hash = $token_hash LIMIT 2"
+                ))
+                .bind(("token_hash", token_hash.clone()))
+                .await?
+                .take(0)
+            },
+        )
+        .await?;
+        at_most_one(sessions, "token_sessions.access_token_hash")
+    }
+    pub async fn revoke_session(
+        &self,
+        app_state: Arc<AppState>,
+        session_id: String,
+    ) -> Result<Option<TokenSession>> {
+        let revoked: Option<TokenSession> = app_state
+            .db()
+            .update((TOKEN_SESSIONS, session_id.as_str()))
             .merge(serde_json::json!({
                 "is_active": false
             }))
-            .await
-            .map_err(|e| {
-                DatabaseError::query_failed(e, Some("UPDATE session to revoke".to_string()))
-            })?;
-        Ok(())
+            .timed(&app_state, "UPDATE session to revoke")
+            .await?;
+        Ok(revoked)
     }
     pub async fn revoke_all_user_sessions(
         &self,
@@ -80,76 +119,196 @@ impl TokenRepository {
         user_id: String,
     ) -> Result<()> {
         let _: Vec<TokenSession> = app_state
-            .db
-            .query("UPDATE token_sessions SET is_active = false WHERE user_id = $user_id")
+            .db()
+            .query(format!(
+                "UPDATE {TOKEN_SESSIONS} SET is_active = false WHERE user_id = $user_id"
+            ))
             .bind(("user_id", user_id))
-            .await
-            .map_err(|e| {
-                DatabaseError::query_failed(e, Some("UPDATE all user sessions".to_string()))
-            })?
+            .timed(&app_state, "UPDATE all user sessions")
+            .await?
             .take(0)
             .map_err(|e| DatabaseError::query_failed(e, Some("Take query result".to_string())))?;
         Ok(())
     }
-    pub async fn update_last_active(
+    pub async fn set_trusted(
         &self,
         app_state: Arc<AppState>,
         session_id: String,
-    ) -> Result<()> {
-        let _: Option<TokenSession> = app_state
-            .db
-            .update(("token_sessions", session_id.as_str()))
+        trusted: bool,
+        trusted_until: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<Option<TokenSession>> {
+        let session: Option<TokenSession> = app_state
+            .db()
+            .update((TOKEN_SESSIONS, session_id.as_str()))
             .merge(serde_json::json!({
+                "trusted": trusted,
+                "trusted_until": trusted_until
+            }))
+            .timed(&app_state, "UPDATE session trusted status")
+            .await?;
+        Ok(session)
+    }
+    pub async fn rotate_session_tokens(
+        &self,
+        app_state: Arc<AppState>,
+        session_id: String,
+        update: SessionTokenUpdate,
+    ) -> Result<Option<TokenSession>> {
+        let session: Option<TokenSession> = app_state
+            .db()
+            .update((TOKEN_SESSIONS, session_id.as_str()))
+            .merge(serde_json::json!({
+                "access_token_jti": update.access_token_jti,
+                "refresh_token_jti": update.refresh_token_jti,
+                "expires_at": update.expires_at,
+                "access_token_hash": update.access_token_hash,
+                "access_claims": update.access_claims,
                 "last_active_at": chrono::Utc::now()
             }))
-            .await
-            .map_err(|e| {
-                DatabaseError::query_failed(e, Some("UPDATE last_active_at".to_string()))
-            })?;
-        Ok(())
+            .timed(&app_state, "UPDATE session token jtis")
+            .await?;
+        Ok(session)
     }
-    pub async fn find_by_id(
+    /// 同一设备重新登录复用会话时写入本次登录的设备绑定值和设备信息，刷新令牌时据此恢复声明
+    pub async fn update_session_device(
         &self,
         app_state: Arc<AppState>,
         session_id: String,
+        device_binding: Option<String>,
+        device_info: Option<DeviceInfo>,
     ) -> Result<Option<TokenSession>> {
         let session: Option<TokenSession> = app_state
-            .db
-            .select(("token_sessions", session_id.as_str()))
-            .await
-            .map_err(|e| {
-                DatabaseError::query_failed(e, Some("SELECT session by id".to_string()))
-            })?;
+            .db()
+            .update((TOKEN_SESSIONS, session_id.as_str()))
+            .merge(serde_json::json!({
+                "device_binding": device_binding,
+                "device_info": device_info
+            }))
+            .timed(&app_state, "UPDATE session device")
+            .await?;
+        Ok(session)
+    }
+    /// 替换为一个不对应任何令牌的 jti，使当前刷新令牌失效而会话保持活跃
+    pub async fn invalidate_refresh_token(
+        &self,
+        app_state: Arc<AppState>,
+        session_id: String,
+    ) -> Result<Option<TokenSession>> {
+        let session: Option<TokenSession> = app_state
+            .db()
+            .update((TOKEN_SESSIONS, session_id.as_str()))
+            .merge(serde_json::json!({
+                "refresh_token_jti": uuid::Uuid::new_v4().to_string()
+            }))
+            .timed(&app_state, "UPDATE session to invalidate refresh token")
+            .await?;
         Ok(session)
     }
+    pub async fn update_last_active(
+        &self,
+        app_state: Arc<AppState>,
+        session_id: String,
+    ) -> Result<()> {
+        // 只覆盖时间戳，重放是安全的
+        let last_active_at = chrono::Utc::now();
+        let db = app_state.db();
+        let _: Option<TokenSession> = with_retry(&app_state, "UPDATE last_active_at", || {
+            db.update((TOKEN_SESSIONS, session_id.as_str()))
+                .merge(serde_json::json!({
+                    "last_active_at": last_active_at
+                }))
+                .into_future()
+        })
+        .await?;
+        Ok(())
+    }
+    pub async fn find_by_id(
+        &self,
+        app_state: Arc<AppState>,
+        session_id: String,
+    ) -> Result<Option<TokenSession>> {
+        let db = app_state.db();
+        with_retry(&app_state, "SELECT session by id", || {
+            db.select((TOKEN_SESSIONS, session_id.as_str()))
+                .into_future()
+        })
+        .await
+    }
     pub async fn get_active_sessions_by_user(
         &self,
         app_state: Arc<AppState>,
         user_id: String,
     ) -> Result<Vec<TokenSession>> {
         let sessions: Vec<TokenSession> = app_state
-            .db
-            .query("SELECT * FROM token_sessions WHERE user_id = $user_id AND is_active = true")
+            .db()
+            .query(format!(
+                "SELECT * FROM {TOKEN_SESSIONS} WHERE user_id = $user_id AND is_active = true"
+            ))
             .bind(("user_id", user_id))
-            .await
-            .map_err(|e| {
-                DatabaseError::query_failed(e, Some("SELCT active sessions by user".to_string()))
-            })?
+            .timed(&app_state, "SELECT active sessions by user")
+            .await?
             .take(0)
             .map_err(|e| DatabaseError::query_failed(e, Some("Take query result".to_string())))?;
         Ok(sessions)
     }
-    pub async fn cleanup_expired_sessions(&self, app_state: Arc<AppState>) -> Result<usize> {
-        let now = chrono::Utc::now();
-        let cutoff_time = now - chrono::Duration::days(30);
+    /// 按最近活跃时间倒序分页
+    pub async fn find_active_sessions_by_user_page(
+        &self,
+        app_state: Arc<AppState>,
+        user_id: String,
+        limit: u32,
+        start: u32,
+    ) -> Result<Vec<TokenSession>> {
+        let sessions: Vec<TokenSession> = app_state
+            .db()
+            .query(format!(
+                "SELECT * FROM {TOKEN_SESSIONS} WHERE user_id = $user_id AND is_active = true \
+                 ORDER BY last_active_at DESC LIMIT $limit START $start"
+            ))
+            .bind(("user_id", user_id))
+            .bind(("limit", limit))
+            .bind(("start", start))
+            .timed(&app_state, "SELECT active sessions page by user")
+            .await?
+            .take(0)
+            .map_err(|e| DatabaseError::query_failed(e, Some("Take query result".to_string())))?;
+        Ok(sessions)
+    }
+    /// 包含已撤销的会话，用于数据导出
+    pub async fn find_all_by_user(
+        &self,
+        app_state: Arc<AppState>,
+        user_id: String,
+    ) -> Result<Vec<TokenSession>> {
+        let sessions: Vec<TokenSession> = app_state
+            .db()
+            .query(format!(
+                "SELECT * FROM {TOKEN_SESSIONS} WHERE user_id = $user_id ORDER BY created_at DESC"
+            ))
+            .bind(("user_id", user_id))
+            .timed(&app_state, "SELECT all sessions by user")
+            .await?
+            .take(0)
+            .map_err(|e| DatabaseError::query_failed(e, Some("Take query result".to_string())))?;
+        Ok(sessions)
+    }
+    pub async fn cleanup_expired_sessions(
+        &self,
+        app_state: Arc<AppState>,
+        retention: chrono::Duration,
+    ) -> Result<usize> {
+        // 只删除已撤销或刷新令牌已过期的会话；没有 expires_at 的旧会话按最后活跃时间判断
+        let cutoff_time = chrono::Utc::now() - retention;
         let deleted: Vec<TokenSession> = app_state
-            .db
-            .query("DELETE token_sessions WHERE created_at < $cutoff_time RETURN BEFORE")
+            .db()
+            .query(format!(
+                "DELETE {TOKEN_SESSIONS} WHERE is_active = false \
+                 OR (expires_at != NONE AND expires_at < time::now()) \
+                 OR (expires_at = NONE AND last_active_at < $cutoff_time) RETURN BEFORE"
+            ))
             .bind(("cutoff_time", cutoff_time))
-            .await
-            .map_err(|e| {
-                DatabaseError::query_failed(e, Some("DELETE expired sessions".to_string()))
-            })?
+            .timed(&app_state, "DELETE expired sessions")
+            .await?
             .take(0)
             .map_err(|e| DatabaseError::query_failed(e, Some("Take query result".to_string())))?;
         Ok(deleted.len())
@@ -165,4 +324,296 @@ impl TokenRepository {
             Ok(false)
         }
     }
+    pub async fn count_all_sessions(&self, app_state: Arc<AppState>) -> Result<u64> {
+        let count: Vec<serde_json::Value> = app_state
+            .db()
+            .query(format!("SELECT count() FROM {TOKEN_SESSIONS} GROUP ALL"))
+            .timed(&app_state, "COUNT all sessions")
+            .await?
+            .take(0)
+            .map_err(|e| DatabaseError::query_failed(e, Some("Take query result".to_string())))?;
+        Ok(count
+            .first()
+            .and_then(|result| result.get("count"))
+            .and_then(|count_val| count_val.as_u64())
+            .unwrap_or(0))
+    }
+    pub async fn count_sessions_in_tenant(
+        &self,
+        app_state: Arc<AppState>,
+        tenant_id: Option<String>,
+    ) -> Result<u64> {
+        let count: Vec<serde_json::Value> = app_state
+            .db()
+            .query(format!(
+                "SELECT count() FROM {TOKEN_SESSIONS} WHERE tenant_id = $tenant_id GROUP ALL"
+            ))
+            .bind(("tenant_id", tenant_id))
+            .timed(&app_state, "COUNT sessions in tenant")
+            .await?
+            .take(0)
+            .map_err(|e| DatabaseError::query_failed(e, Some("Take query result".to_string())))?;
+        Ok(count
+            .first()
+            .and_then(|result| result.get("count"))
+            .and_then(|count_val| count_val.as_u64())
+            .unwrap_or(0))
+    }
+    pub async fn count_active_sessions(
+        &self,
+        app_state: Arc<AppState>,
+        tenant_id: Option<String>,
+    ) -> Result<u64> {
+        let count: Vec<serde_json::Value> = app_state
+            .db()
+            .query(format!(
+                "SELECT count() FROM {TOKEN_SESSIONS} \
+                 WHERE is_active = true AND tenant_id = $tenant_id GROUP ALL"
+            ))
+            .bind(("tenant_id", tenant_id))
+            .timed(&app_state, "COUNT active sessions")
+            .await?
+            .take(0)
+            .map_err(|e| DatabaseError::query_failed(e, Some("Take query result".to_string())))?;
+        Ok(count
+            .first()
+            .and_then(|result| result.get("count"))
+            .and_then(|count_val| count_val.as_u64())
+            .unwrap_or(0))
+    }
+    pub async fn count_active_sessions_by_user(
+        &self,
+        app_state: Arc<AppState>,
+        user_id: String,
+    ) -> Result<u64> {
+        let count: Vec<serde_json::Value> = app_state
+            .db()
+            .query(format!(
+                "SELECT count() FROM {TOKEN_SESSIONS} \
+                 WHERE user_id = $user_id AND is_active = true GROUP ALL"
+            ))
+            .bind(("user_id", user_id))
+            .timed(&app_state, "COUNT active sessions by user")
+            .await?
+            .take(0)
+            .map_err(|e| DatabaseError::query_failed(e, Some("Take query result".to_string())))?;
+        Ok(count
+            .first()
+            .and_then(|result| result.get("count"))
+            .and_then(|count_val| count_val.as_u64())
+            .unwrap_or(0))
+    }
+    /// 同一用户、同一设备指纹且 IP 完全相同的最近活跃会话
+    pub async fn find_active_session_by_device(
+        &self,
+        app_state: Arc<AppState>,
+        user_id: String,
+        device_fingerprint: String,
+        ip_address: Option<String>,
+    ) -> Result<Option<TokenSession>> {
+        let mut sessions: Vec<TokenSession> = app_state
+            .db()
+            .query(format!(
+                "SELECT * FROM {TOKEN_SESSIONS} WHERE user_id = $user_id AND is_active = true \
+                 AND device_fingerprint = $device_fingerprint AND ip_address = $ip_address \
+                 ORDER BY last_active_at DESC LIMIT 1"
+            ))
+            .bind(("user_id", user_id))
+            .bind(("device_fingerprint", device_fingerprint))
+            .bind(("ip_address", ip_address))
+            .timed(&app_state, "SELECT active session by device")
+            .await?
+            .take(0)
+            .map_err(|e| DatabaseError::query_failed(e, Some("Take query result".to_string())))?;
+        Ok(sessions.pop())
+    }
+    /// 返回 (该用户的会话总数, 使用该设备指纹的会话数)，包括已撤销但尚未清理的会话
+    pub async fn count_sessions_by_fingerprint(
+        &self,
+        app_state: Arc<AppState>,
+        user_id: String,
+        device_fingerprint: String,
+    ) -> Result<(u64, u64)> {
+        let mut response = app_state
+            .db()
+            .query(format!(
+                "SELECT count() FROM {TOKEN_SESSIONS} WHERE user_id = $user_id GROUP ALL"
+            ))
+            .query(format!(
+                "SELECT count() FROM {TOKEN_SESSIONS} \
+                 WHERE user_id = $user_id AND device_fingerprint = $device_fingerprint GROUP ALL"
+            ))
+            .bind(("user_id", user_id))
+            .bind(("device_fingerprint", device_fingerprint))
+            .timed(&app_state, "COUNT sessions by fingerprint")
+            .await?;
+        let mut counts = [0u64; 2];
+        for (index, count) in counts.iter_mut().enumerate() {
+            let rows: Vec<serde_json::Value> = response.take(index).map_err(|e| {
+                DatabaseError::query_failed(e, Some("Take query result".to_string()))
+            })?;
+            *count = rows
+                .first()
+                .and_then(|result| result.get("count"))
+                .and_then(|count_val| count_val.as_u64())
+                .unwrap_or(0);
+        }
+        Ok((counts[0], counts[1]))
+    }
+    pub async fn find_least_recently_active_sessions(
+        &self,
+        app_state: Arc<AppState>,
+        user_id: String,
+        limit: u64,
+    ) -> Result<Vec<TokenSession>> {
+        let sessions: Vec<TokenSession> = app_state
+            .db()
+            .query(format!(
+                "SELECT * FROM {TOKEN_SESSIONS} WHERE user_id = $user_id AND is_active = true \
+                 ORDER BY last_active_at ASC LIMIT $limit"
+            ))
+            .bind(("user_id", user_id))
+            .bind(("limit", limit))
+            .timed(&app_state, "SELECT oldest user sessions")
+            .await?
+            .take(0)
+            .map_err(|e| DatabaseError::query_failed(e, Some("Take query result".to_string())))?;
+        Ok(sessions)
+    }
+    pub async fn revoke_sessions_created_before(
+        &self,
+        app_state: Arc<AppState>,
+        tenant_id: Option<String>,
+        cutoff_time: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<TokenSession>> {
+        let revoked: Vec<TokenSession> = app_state
+            .db()
+            .query(format!(
+                "UPDATE {TOKEN_SESSIONS} SET is_active = false \
+                 WHERE is_active = true AND tenant_id = $tenant_id \
+                 AND created_at < $cutoff_time RETURN AFTER"
+            ))
+            .bind(("tenant_id", tenant_id))
+            .bind(("cutoff_time", cutoff_time))
+            .timed(&app_state, "UPDATE sessions created before")
+            .await?
+            .take(0)
+            .map_err(|e| DatabaseError::query_failed(e, Some("Take query result".to_string())))?;
+        Ok(revoked)
+    }
+    pub async fn revoke_sessions_by_ip(
+        &self,
+        app_state: Arc<AppState>,
+        tenant_id: Option<String>,
+        ip_address: String,
+    ) -> Result<Vec<TokenSession>> {
+        let revoked: Vec<TokenSession> = app_state
+            .db()
+            .query(format!(
+                "UPDATE {TOKEN_SESSIONS} SET is_active = false \
+                 WHERE is_active = true AND tenant_id = $tenant_id \
+                 AND ip_address = $ip_address RETURN AFTER"
+            ))
+            .bind(("tenant_id", tenant_id))
+            .bind(("ip_address", ip_address))
+            .timed(&app_state, "UPDATE sessions by ip")
+            .await?
+            .take(0)
+            .map_err(|e| DatabaseError::query_failed(e, Some("Take query result".to_string())))?;
+        Ok(revoked)
+    }
+    /// 在当前租户内跨所有用户查找来自某个 IP 或网段的会话，包括已撤销的会话，返回当前页和总数；
+    /// 网段无法用字符串比较表达，先取出该租户所有带 IP 的会话再在内存中按网段过滤和分页
+    pub async fn find_sessions_by_ip(
+        &self,
+        app_state: Arc<AppState>,
+        tenant_id: Option<String>,
+        filter: SessionIpFilter,
+        limit: u32,
+        start: u32,
+    ) -> Result<(Vec<TokenSession>, u64)> {
+        let SessionIpFilter::Exact(ip) = &filter else {
+            let sessions: Vec<TokenSession> = app_state
+                .db()
+                .query(format!(
+                    "SELECT * FROM {TOKEN_SESSIONS} \
+                     WHERE tenant_id = $tenant_id AND ip_address != NONE \
+                     ORDER BY created_at DESC"
+                ))
+                .bind(("tenant_id", tenant_id))
+                .timed(&app_state, "SELECT sessions by subnet")
+                .await?
+                .take(0)
+                .map_err(|e| {
+                    DatabaseError::query_failed(e, Some("Take query result".to_string()))
+                })?;
+            let matched: Vec<TokenSession> = sessions
+                .into_iter()
+                .filter(|session| {
+                    session
+                        .ip_address
+                        .as_deref()
+                        .is_some_and(|ip| filter.matches(ip))
+                })
+                .collect();
+            let total = matched.len() as u64;
+            let page = matched
+                .into_iter()
+                .skip(start as usize)
+                .take(limit as usize)
+                .collect();
+            return Ok((page, total));
+        };
+        let mut response = app_state
+            .db()
+            .query(format!(
+                "SELECT * FROM {TOKEN_SESSIONS} WHERE tenant_id = $tenant_id AND ip_address = $ip \
+                 ORDER BY created_at DESC LIMIT $limit START $start"
+            ))
+            .query(format!(
+                "SELECT count() FROM {TOKEN_SESSIONS} \
+                 WHERE tenant_id = $tenant_id AND ip_address = $ip GROUP ALL"
+            ))
+            .bind(("tenant_id", tenant_id))
+            .bind(("ip", ip.clone()))
+            .bind(("limit", limit))
+            .bind(("start", start))
+            .timed(&app_state, "SELECT sessions by ip")
+            .await?;
+        let sessions: Vec<TokenSession> = response
+            .take(0)
+            .map_err(|e| DatabaseError::query_failed(e, Some("Take query result".to_string())))?;
+        let count: Vec<serde_json::Value> = response
+            .take(1)
+            .map_err(|e| DatabaseError::query_failed(e, Some("Take query result".to_string())))?;
+        let total = count
+            .first()
+            .and_then(|result| result.get("count"))
+            .and_then(|count_val| count_val.as_u64())
+            .unwrap_or(0);
+        Ok((sessions, total))
+    }
+    pub async fn revoke_sessions_by_user_role(
+        &self,
+        app_state: Arc<AppState>,
+        tenant_id: Option<String>,
+        role: Role,
+    ) -> Result<Vec<TokenSession>> {
+        let revoked: Vec<TokenSession> = app_state
+            .db()
+            .query(format!(
+                "UPDATE {TOKEN_SESSIONS} SET is_active = false \
+                 WHERE is_active = true AND tenant_id = $tenant_id \
+                 AND user_id IN (SELECT VALUE record::id(id) FROM {USERS} \
+                 WHERE role = $role AND tenant_id = $tenant_id) \
+                 RETURN AFTER"
+            ))
+            .bind(("tenant_id", tenant_id))
+            .bind(("role", role))
+            .timed(&app_state, "UPDATE sessions by user role")
+            .await?
+            .take(0)
+            .map_err(|e| DatabaseError::query_failed(e, Some("Take query result".to_string())))?;
+        Ok(revoked)
+    }
 }
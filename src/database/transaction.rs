@@ -0,0 +1,43 @@
+use std::{sync::Arc, time::Instant};
+
+use surrealdb::{Response, engine::remote::ws::Client, method::Query};
+
+use crate::{
+    database::timing::record_query_duration,
+    errors::{core::Result, db::DatabaseError},
+    state::AppState,
+};
+
+/// 把闭包追加的语句包在 BEGIN/COMMIT 之间一次性发送，任意语句失败则整体回滚
+/// 返回结果的下标只计算闭包内的语句，BEGIN/COMMIT 本身不产生结果
+pub async fn with_transaction<F>(
+    app_state: Arc<AppState>,
+    operation: &str,
+    statements: F,
+) -> Result<Response>
+where
+    F: for<'r> FnOnce(Query<'r, Client>) -> Query<'r, Client>,
+{
+    let started = Instant::now();
+    let response = statements(app_state.db().query("BEGIN TRANSACTION"))
+        .query("COMMIT TRANSACTION")
+        .await;
+    record_query_duration(
+        app_state.env.db_config.slow_query_threshold_ms,
+        operation,
+        started.elapsed(),
+    );
+    let mut response = response.map_err(|e| DatabaseError::transaction_failed(e, operation))?;
+    let mut errors: Vec<(usize, surrealdb::Error)> = response.take_errors().into_iter().collect();
+    if errors.is_empty() {
+        return Ok(response);
+    }
+    // 回滚后其余语句都会报 "not executed"，真正的原因在触发回滚的那条语句上
+    errors.sort_by_key(|(index, _)| *index);
+    let position = errors
+        .iter()
+        .position(|(_, error)| !error.to_string().contains("not executed"))
+        .unwrap_or(0);
+    let (_, error) = errors.swap_remove(position);
+    Err(DatabaseError::transaction_failed(error, operation).into())
+}
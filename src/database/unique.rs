@@ -0,0 +1,20 @@
+use tracing::error;
+
+use crate::errors::{core::Result, db::DatabaseError};
+
+/// 唯一查询返回多行说明唯一索引缺失或数据已损坏，不能随便取第一行
+pub fn at_most_one<T>(rows: Vec<T>, lookup: &str) -> Result<Option<T>> {
+    if rows.len() > 1 {
+        error!(
+            lookup,
+            rows = rows.len(),
+            "Unique lookup returned multiple rows, data integrity violated"
+        );
+        return Err(DatabaseError::ConstraintViolation(format!(
+            "Duplicate records found for {}",
+            lookup
+        ))
+        .into());
+    }
+    Ok(rows.into_iter().next())
+}
@@ -1,11 +1,31 @@
-use std::sync::Arc;
+use std::{future::IntoFuture, sync::Arc};
+
+use chrono::{DateTime, Utc};
 
 use crate::{
-    errors::{core::Result, db::DatabaseError},
-    models::user::User,
+    database::{
+        retry::with_retry,
+        tables::{NOTES, OAUTH_CONNECTIONS, TOKEN_SESSIONS, USERS},
+        timing::TimedQuery,
+        transaction::with_transaction,
+        unique::at_most_one,
+    },
+    dtos::pagination::Cursor,
+    errors::{
+        core::{Error, Result},
+        db::DatabaseError,
+    },
+    models::{
+        notification::NotificationPreferences,
+        role::Role,
+        user::{User, UserListFilter},
+    },
     state::AppState,
 };
 
+// 事务内查重失败时抛出的错误信息，用于和其他事务错误区分
+const EMAIL_TAKEN: &str = "users.email already exists";
+
 #[derive(Debug, Clone)]
 pub struct UserRepository;
 
@@ -13,41 +33,78 @@ impl UserRepository {
     pub fn new() -> Self {
         Self
     }
-    pub async fn create(&self, app_state: Arc<AppState>, user: User) -> Result<User> {
-        let created: Option<User> = app_state
-            .db
-            .create(("users", &user.id))
-            .content(user)
-            .await
-            .map_err(|e| DatabaseError::query_failed(e, Some("CREATE user".to_string())))?;
-        created.ok_or(DatabaseError::NotFound("Failed to create user".to_string()).into())
+    /// 邮箱查重与写入放在同一个事务中，并发注册同一邮箱时只有一个能成功，冲突时返回 `None`
+    pub async fn create(&self, app_state: Arc<AppState>, user: User) -> Result<Option<User>> {
+        let response = with_transaction(app_state, "CREATE user", |query| {
+            query
+                .query(format!(
+                    "IF (SELECT count() FROM {USERS} WHERE email = $user.email AND tenant_id = $user.tenant_id GROUP ALL)[0].count > 0 \
+                     {{ THROW $email_taken }}"
+                ))
+                .query(format!("CREATE type::thing('{USERS}', $user.id) CONTENT $user"))
+                .bind(("user", user))
+                .bind(("email_taken", EMAIL_TAKEN))
+        })
+        .await;
+        let mut response = match response {
+            Ok(response) => response,
+            Err(Error::Db(DatabaseError::TransactionError { source, .. }))
+                if source.to_string().contains(EMAIL_TAKEN) =>
+            {
+                return Ok(None);
+            }
+            Err(e) => return Err(e),
+        };
+        let created: Vec<User> = response
+            .take(1)
+            .map_err(|e| DatabaseError::query_failed(e, Some("Take query result".to_string())))?;
+        match created.into_iter().next() {
+            Some(user) => Ok(Some(user)),
+            None => Err(DatabaseError::NotFound("Failed to create user".to_string()).into()),
+        }
+    }
+    /// 在同一个事务中批量写入，任意一条失败则全部回滚
+    #[cfg(debug_assertions)]
+    pub async fn create_many(&self, app_state: Arc<AppState>, users: Vec<User>) -> Result<()> {
+        with_transaction(app_state, "CREATE users in bulk", |query| {
+            query
+                .query(format!(
+                    "FOR $user IN $users {{ CREATE type::thing('{USERS}', $user.id) CONTENT $user; }}"
+                ))
+                .bind(("users", users))
+        })
+        .await?;
+        Ok(())
     }
     pub async fn find_by_email(
         &self,
         app_state: Arc<AppState>,
         email: String,
+        tenant_id: Option<String>,
     ) -> Result<Option<User>> {
-        let users: Vec<User> = app_state
-            .db
-            .query("SELECT * FROM users WHERE email = $email LIMIT 1")
-            .bind(("email", email))
-            .await
-            .map_err(|e| DatabaseError::query_failed(e, Some("SELECT user by email".to_string())))?
+        let (db, email, tenant_id) = (&app_state.db(), &email, &tenant_id);
+        let users: Vec<User> = with_retry(&app_state, "SELECT user by email", move || async move {
+            db.query(format!(
+                "SELECT * FROM {USERS} WHERE email = $email AND tenant_id = $tenant_id LIMIT 2"
+            ))
+            .bind(("email", email.clone()))
+            .bind(("tenant_id", tenant_id.clone()))
+            .await?
             .take(0)
-            .map_err(|e| DatabaseError::query_failed(e, Some("Take query result".to_string())))?;
-        Ok(users.into_iter().next())
+        })
+        .await?;
+        at_most_one(users, "users.email")
     }
     pub async fn find_by_id(
         &self,
         app_state: Arc<AppState>,
         user_id: String,
     ) -> Result<Option<User>> {
-        let user: Option<User> = app_state
-            .db
-            .select(("users", user_id.as_str()))
-            .await
-            .map_err(|e| DatabaseError::query_failed(e, Some("SELECT user by id".to_string())))?;
-        Ok(user)
+        let db = app_state.db();
+        with_retry(&app_state, "SELECT user by id", || {
+            db.select((USERS, user_id.as_str())).into_future()
+        })
+        .await
     }
     pub async fn update_verification_status(
         &self,
@@ -56,37 +113,91 @@ impl UserRepository {
         verified: bool,
     ) -> Result<User> {
         let updated: Option<User> = app_state
-            .db
-            .update(("users", user_id.as_str()))
+            .db()
+            .update((USERS, user_id.as_str()))
             .merge(serde_json::json!({
                 "verified": verified,
                 "updated_at": chrono::Utc::now()
             }))
-            .await
-            .map_err(|e| {
-                DatabaseError::query_failed(e, Some("UPDATE user verification".to_string()))
-            })?;
+            .timed(&app_state, "UPDATE user verification")
+            .await?;
         updated.ok_or(
             DatabaseError::NotFound("User not found for verification update".to_string()).into(),
         )
     }
+    pub async fn update_role(
+        &self,
+        app_state: Arc<AppState>,
+        user_id: String,
+        role: Role,
+    ) -> Result<User> {
+        let updated: Option<User> = app_state
+            .db()
+            .update((USERS, user_id.as_str()))
+            .merge(serde_json::json!({
+                "role": role,
+                "updated_at": chrono::Utc::now()
+            }))
+            .timed(&app_state, "UPDATE user role")
+            .await?;
+        updated.ok_or(DatabaseError::NotFound("User not found for role update".to_string()).into())
+    }
+    pub async fn update_last_login(
+        &self,
+        app_state: Arc<AppState>,
+        user_id: String,
+    ) -> Result<User> {
+        let updated: Option<User> = app_state
+            .db()
+            .update((USERS, user_id.as_str()))
+            .merge(serde_json::json!({
+                "last_login_at": chrono::Utc::now(),
+                "failed_login_attempts": 0,
+                "locked_until": None::<chrono::DateTime<chrono::Utc>>
+            }))
+            .timed(&app_state, "UPDATE user last_login_at")
+            .await?;
+        updated.ok_or(
+            DatabaseError::NotFound("User not found for last login update".to_string()).into(),
+        )
+    }
+    pub async fn update_failed_logins(
+        &self,
+        app_state: Arc<AppState>,
+        user_id: String,
+        failed_login_attempts: u32,
+        locked_until: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<User> {
+        let updated: Option<User> = app_state
+            .db()
+            .update((USERS, user_id.as_str()))
+            .merge(serde_json::json!({
+                "failed_login_attempts": failed_login_attempts,
+                "locked_until": locked_until
+            }))
+            .timed(&app_state, "UPDATE user failed logins")
+            .await?;
+        updated.ok_or(
+            DatabaseError::NotFound("User not found for failed login update".to_string()).into(),
+        )
+    }
     pub async fn update_password(
         &self,
         app_state: Arc<AppState>,
         user_id: String,
         new_password_hash: String,
+        password_history: Vec<String>,
     ) -> Result<User> {
         let updated: Option<User> = app_state
-            .db
-            .update(("users", user_id.as_str()))
+            .db()
+            .update((USERS, user_id.as_str()))
             .merge(serde_json::json!({
                 "password": new_password_hash,
+                "password_history": password_history,
                 "updated_at": chrono::Utc::now(),
             }))
-            .await
-            .map_err(|e| {
-                DatabaseError::query_failed(e, Some("UPDATE user password".to_string()))
-            })?;
+            .timed(&app_state, "UPDATE user password")
+            .await?;
         updated
             .ok_or(DatabaseError::NotFound("User not found for password update".to_string()).into())
     }
@@ -104,33 +215,168 @@ impl UserRepository {
             update_data["name"] = serde_json::Value::String(name);
         }
         if let Some(email) = email {
-            update_data["email"] = serde_json::Value::String(email);
-            update_data["verified"] = serde_json::Value::Bool(false);
+            update_data["pending_email"] = serde_json::Value::String(email);
         }
         let updated: Option<User> = app_state
-            .db
-            .update(("users", user_id.as_str()))
+            .db()
+            .update((USERS, user_id.as_str()))
             .merge(update_data)
-            .await
-            .map_err(|e| DatabaseError::query_failed(e, Some("UPDATE user profile".to_string())))?;
+            .timed(&app_state, "UPDATE user profile")
+            .await?;
         updated
             .ok_or(DatabaseError::NotFound("User not found for profile update".to_string()).into())
     }
+    pub async fn update_notification_preferences(
+        &self,
+        app_state: Arc<AppState>,
+        user_id: String,
+        preferences: NotificationPreferences,
+    ) -> Result<User> {
+        let updated: Option<User> = app_state
+            .db()
+            .update((USERS, user_id.as_str()))
+            .merge(serde_json::json!({
+                "notification_preferences": preferences,
+                "updated_at": chrono::Utc::now()
+            }))
+            .timed(&app_state, "UPDATE user notification preferences")
+            .await?;
+        updated.ok_or(
+            DatabaseError::NotFound(
+                "User not found for notification preferences update".to_string(),
+            )
+            .into(),
+        )
+    }
+    pub async fn update_recovery_codes(
+        &self,
+        app_state: Arc<AppState>,
+        user_id: String,
+        recovery_codes: Vec<String>,
+    ) -> Result<User> {
+        let updated: Option<User> = app_state
+            .db()
+            .update((USERS, user_id.as_str()))
+            .merge(serde_json::json!({
+                "recovery_codes": recovery_codes,
+                "updated_at": chrono::Utc::now()
+            }))
+            .timed(&app_state, "UPDATE user recovery codes")
+            .await?;
+        updated.ok_or(
+            DatabaseError::NotFound("User not found for recovery codes update".to_string()).into(),
+        )
+    }
+    /// 仅当恢复码仍然存在时才移除，并发使用同一个恢复码时只有一个请求能拿到结果
+    pub async fn consume_recovery_code(
+        &self,
+        app_state: Arc<AppState>,
+        user_id: String,
+        recovery_code: String,
+    ) -> Result<Option<User>> {
+        let users: Vec<User> = app_state
+            .db()
+            .query(format!(
+                "UPDATE type::thing('{USERS}', $user_id) \
+                 SET recovery_codes -= $recovery_code, updated_at = time::now() \
+                 WHERE recovery_codes CONTAINS $recovery_code RETURN AFTER"
+            ))
+            .bind(("user_id", user_id))
+            .bind(("recovery_code", recovery_code))
+            .timed(&app_state, "UPDATE user consume recovery code")
+            .await?
+            .take(0)
+            .map_err(|e| DatabaseError::query_failed(e, Some("Take query result".to_string())))?;
+        Ok(users.into_iter().next())
+    }
+    pub async fn confirm_email_change(
+        &self,
+        app_state: Arc<AppState>,
+        user_id: String,
+        email: String,
+    ) -> Result<Option<User>> {
+        let users: Vec<User> = app_state
+            .db()
+            .query(format!(
+                "UPDATE type::thing('{USERS}', $user_id) \
+                 SET email = pending_email, pending_email = NONE, verified = true, updated_at = time::now() \
+                 WHERE pending_email = $email RETURN AFTER"
+            ))
+            .bind(("user_id", user_id))
+            .bind(("email", email))
+            .timed(&app_state, "UPDATE user confirm email change")
+            .await?
+            .take(0)
+            .map_err(|e| DatabaseError::query_failed(e, Some("Take query result".to_string())))?;
+        Ok(users.into_iter().next())
+    }
+    /// 设置或清除删除申请时间
+    pub async fn set_deleted_at(
+        &self,
+        app_state: Arc<AppState>,
+        user_id: String,
+        deleted_at: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<User> {
+        let updated: Option<User> = app_state
+            .db()
+            .update((USERS, user_id.as_str()))
+            .merge(serde_json::json!({
+                "deleted_at": deleted_at,
+                "updated_at": chrono::Utc::now()
+            }))
+            .timed(&app_state, "UPDATE user deleted_at")
+            .await?;
+        updated
+            .ok_or(DatabaseError::NotFound("User not found for deletion update".to_string()).into())
+    }
+    /// 在 cutoff 之前申请删除、宽限期已过的用户 id
+    pub async fn find_ids_deleted_before(
+        &self,
+        app_state: Arc<AppState>,
+        cutoff: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<String>> {
+        let ids: Vec<String> = app_state
+            .db()
+            .query(format!(
+                "SELECT VALUE record::id(id) FROM {USERS} WHERE deleted_at != NONE AND deleted_at < $cutoff"
+            ))
+            .bind(("cutoff", cutoff))
+            .timed(&app_state, "SELECT users deleted before")
+            .await?
+            .take(0)
+            .map_err(|e| DatabaseError::query_failed(e, Some("Take query result".to_string())))?;
+        Ok(ids)
+    }
+    /// 用户记录与其会话、笔记、第三方登录绑定在同一个事务中删除，避免留下孤立数据
     pub async fn delete(&self, app_state: Arc<AppState>, user_id: String) -> Result<()> {
-        let _: Option<User> = app_state
-            .db
-            .delete(("users", user_id.as_str()))
-            .await
-            .map_err(|e| DatabaseError::query_failed(e, Some("DELETE user".to_string())))?;
+        with_transaction(app_state, "DELETE user", |query| {
+            query
+                .query(format!("DELETE {TOKEN_SESSIONS} WHERE user_id = $user_id"))
+                .query(format!("DELETE {NOTES} WHERE user_id = $user_id"))
+                .query(format!(
+                    "DELETE {OAUTH_CONNECTIONS} WHERE user_id = $user_id"
+                ))
+                .query(format!("DELETE type::thing('{USERS}', $user_id)"))
+                .bind(("user_id", user_id))
+        })
+        .await?;
         Ok(())
     }
-    pub async fn email_exists(&self, app_state: Arc<AppState>, email: String) -> Result<bool> {
+    pub async fn email_exists(
+        &self,
+        app_state: Arc<AppState>,
+        email: String,
+        tenant_id: Option<String>,
+    ) -> Result<bool> {
         let count: Vec<serde_json::Value> = app_state
-            .db
-            .query("SELECT count() FROM users WHERE email = $email GROUP ALL")
+            .db()
+            .query(format!(
+                "SELECT count() FROM {USERS} WHERE email = $email AND tenant_id = $tenant_id GROUP ALL"
+            ))
             .bind(("email", email))
-            .await
-            .map_err(|e| DatabaseError::query_failed(e, Some("COUNT users by email".to_string())))?
+            .bind(("tenant_id", tenant_id))
+            .timed(&app_state, "COUNT users by email")
+            .await?
             .take(0)
             .map_err(|e| DatabaseError::query_failed(e, Some("Take query result".to_string())))?;
         if let Some(result) = count.first() {
@@ -142,4 +388,168 @@ impl UserRepository {
         }
         Ok(false)
     }
+    pub async fn list(
+        &self,
+        app_state: Arc<AppState>,
+        filter: UserListFilter,
+        start: u32,
+        limit: u32,
+        cursor: Option<Cursor>,
+    ) -> Result<(Vec<User>, u64)> {
+        // 租户条件始终生效，未启用多租户时匹配 tenant_id 为空的用户
+        let mut conditions = vec!["tenant_id = $tenant_id"];
+        if filter.search.is_some() {
+            conditions.push(
+                "(string::contains(string::lowercase(name), $search) OR string::contains(string::lowercase(email), $search))",
+            );
+        }
+        if filter.role.is_some() {
+            conditions.push("role = $role");
+        }
+        if filter.verified.is_some() {
+            conditions.push("verified = $verified");
+        }
+        let where_clause = format!(" WHERE {}", conditions.join(" AND "));
+
+        let search = filter.search.map(|search| search.to_lowercase());
+        // 有游标时使用 keyset 分页，避免大表上 START 偏移的全表扫描
+        let page_query = match cursor {
+            Some(_) => {
+                let mut page_conditions = conditions.clone();
+                page_conditions.push(
+                    "(created_at < $cursor_time OR (created_at = $cursor_time AND record::id(id) < $cursor_id))",
+                );
+                format!(
+                    "SELECT * FROM {USERS} WHERE {} ORDER BY created_at DESC, id DESC LIMIT $limit",
+                    page_conditions.join(" AND ")
+                )
+            }
+            None => format!(
+                "SELECT * FROM {USERS}{} ORDER BY created_at DESC, id DESC LIMIT $limit START $start",
+                where_clause
+            ),
+        };
+        let (cursor_time, cursor_id) = cursor
+            .map(|cursor| (Some(cursor.created_at), Some(cursor.id)))
+            .unwrap_or_default();
+        let users: Vec<User> = app_state
+            .db()
+            .query(page_query)
+            .bind(("search", search.clone()))
+            .bind(("role", filter.role.clone()))
+            .bind(("verified", filter.verified))
+            .bind(("tenant_id", filter.tenant_id.clone()))
+            .bind(("limit", limit))
+            .bind(("start", start))
+            .bind(("cursor_time", cursor_time))
+            .bind(("cursor_id", cursor_id))
+            .timed(&app_state, "SELECT users page")
+            .await?
+            .take(0)
+            .map_err(|e| DatabaseError::query_failed(e, Some("Take query result".to_string())))?;
+
+        let response = app_state
+            .db()
+            .query(format!(
+                "SELECT count() FROM {USERS}{} GROUP ALL",
+                where_clause
+            ))
+            .bind(("search", search))
+            .bind(("role", filter.role))
+            .bind(("verified", filter.verified))
+            .bind(("tenant_id", filter.tenant_id))
+            .timed(&app_state, "COUNT users page")
+            .await?;
+        let total = Self::take_count(response)?;
+
+        Ok((users, total))
+    }
+    pub async fn count_all(
+        &self,
+        app_state: Arc<AppState>,
+        tenant_id: Option<String>,
+    ) -> Result<u64> {
+        let response = app_state
+            .db()
+            .query(format!(
+                "SELECT count() FROM {USERS} WHERE tenant_id = $tenant_id GROUP ALL"
+            ))
+            .bind(("tenant_id", tenant_id))
+            .timed(&app_state, "COUNT all users")
+            .await?;
+        Self::take_count(response)
+    }
+    pub async fn count_verified(
+        &self,
+        app_state: Arc<AppState>,
+        tenant_id: Option<String>,
+    ) -> Result<u64> {
+        let response = app_state
+            .db()
+            .query(format!(
+                "SELECT count() FROM {USERS} \
+                 WHERE verified = true AND tenant_id = $tenant_id GROUP ALL"
+            ))
+            .bind(("tenant_id", tenant_id))
+            .timed(&app_state, "COUNT verified users")
+            .await?;
+        Self::take_count(response)
+    }
+    pub async fn count_by_role(&self, app_state: Arc<AppState>, role: Role) -> Result<u64> {
+        let response = app_state
+            .db()
+            .query(format!(
+                "SELECT count() FROM {USERS} WHERE role = $role GROUP ALL"
+            ))
+            .bind(("role", role))
+            .timed(&app_state, "COUNT users by role")
+            .await?;
+        Self::take_count(response)
+    }
+    pub async fn count_by_role_in_tenant(
+        &self,
+        app_state: Arc<AppState>,
+        tenant_id: Option<String>,
+        role: Role,
+    ) -> Result<u64> {
+        let response = app_state
+            .db()
+            .query(format!(
+                "SELECT count() FROM {USERS} \
+                 WHERE role = $role AND tenant_id = $tenant_id GROUP ALL"
+            ))
+            .bind(("role", role))
+            .bind(("tenant_id", tenant_id))
+            .timed(&app_state, "COUNT users by role in tenant")
+            .await?;
+        Self::take_count(response)
+    }
+    pub async fn count_created_since(
+        &self,
+        app_state: Arc<AppState>,
+        tenant_id: Option<String>,
+        since: DateTime<Utc>,
+    ) -> Result<u64> {
+        let response = app_state
+            .db()
+            .query(format!(
+                "SELECT count() FROM {USERS} \
+                 WHERE created_at > $since AND tenant_id = $tenant_id GROUP ALL"
+            ))
+            .bind(("since", since))
+            .bind(("tenant_id", tenant_id))
+            .timed(&app_state, "COUNT users created since")
+            .await?;
+        Self::take_count(response)
+    }
+    fn take_count(mut response: surrealdb::Response) -> Result<u64> {
+        let count: Vec<serde_json::Value> = response
+            .take(0)
+            .map_err(|e| DatabaseError::query_failed(e, Some("Take query result".to_string())))?;
+        Ok(count
+            .first()
+            .and_then(|result| result.get("count"))
+            .and_then(|count_val| count_val.as_u64())
+            .unwrap_or(0))
+    }
 }
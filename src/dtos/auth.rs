@@ -1,7 +1,8 @@
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use validator::Validate;
 
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct LoginRequest {
     #[validate(email(message = "Invalid email format"))]
     pub email: String,
@@ -10,43 +11,98 @@ pub struct LoginRequest {
     pub password: String,
 
     pub device_info: Option<String>,
+
+    /// 同时以 HttpOnly Cookie 返回令牌，服务端开启 TOKEN_COOKIE_DELIVERY 时总是下发
+    #[serde(default)]
+    pub use_cookies: bool,
 }
 
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct RegisterRequest {
     #[validate(email(message = "Invalid email format"))]
     pub email: String,
 
-    #[validate(length(min = 8, message = "Password must be at least 8 characters"))]
+    #[validate(length(min = 1, message = "Password cannot be empty"))]
     pub password: String,
 
     #[validate(length(min = 1, message = "Name cannot be empty"))]
     pub name: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct RefreshTokenRequest {
-    pub refresh_token: String,
+    /// 使用 Cookie 下发令牌时可以省略，改从 refresh_token Cookie 读取
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+    /// 同时以 HttpOnly Cookie 返回新令牌
+    #[serde(default)]
+    pub use_cookies: bool,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct LoginResponse {
     pub access_token: String,
     pub refresh_token: String,
     pub token_type: String,
     pub expires_in: i64,
+    pub scopes: Vec<String>,
     pub user: UserInfo,
+    /// 为 true 时返回的令牌只是挑战令牌，需要完成二次验证才能换取完整令牌；
+    /// 目前尚未支持二次验证，始终为 false
+    pub requires_2fa: bool,
+    /// 邮箱尚未验证；令牌仍具备完整权限，客户端应引导用户完成验证
+    pub requires_email_verification: bool,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct RefreshTokenResponse {
     pub access_token: String,
     pub refresh_token: String,
     pub token_type: String,
     pub expires_in: i64,
+    pub scopes: Vec<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct IntrospectRequest {
+    pub token: String,
+    /// "access_token" 或 "refresh_token"，只影响尝试顺序
+    pub token_type_hint: Option<String>,
+}
+
+/// RFC 7662 内省响应，令牌无效时只返回 active: false
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct IntrospectResponse {
+    pub active: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exp: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iat: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jti: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iss: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aud: Option<String>,
+}
+
+/// 当前访问令牌的过期信息，直接由请求中的声明计算
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TokenInfoResponse {
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    /// 距离过期的秒数，已过期时为 0
+    pub expires_in_seconds: i64,
+    pub scopes: Vec<String>,
+    pub token_type: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
 pub struct UserInfo {
     pub id: String,
     pub email: String,
@@ -55,35 +111,90 @@ pub struct UserInfo {
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct LogoutRequest {
     pub refresh_token: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct RevokeRefreshTokenRequest {
+    #[validate(length(min = 1, message = "Refresh token cannot be empty"))]
+    pub refresh_token: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
 pub struct LogoutResponse {
     pub message: String,
 }
 
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct ChangePasswordRequest {
     #[validate(length(min = 1, message = "Current password cannot be empty"))]
     pub current_password: String,
 
-    #[validate(length(min = 8, message = "New password must be at least 8 characters"))]
+    #[validate(length(min = 1, message = "New password cannot be empty"))]
     pub new_password: String,
 }
 
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ChangeEmailRequest {
+    #[validate(email(message = "Invalid email format"))]
+    pub new_email: String,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ConfirmEmailChangeRequest {
+    #[validate(length(min = 1, message = "Token cannot be empty"))]
+    pub token: String,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct ForgotPasswordRequest {
     #[validate(email(message = "Invalid email format"))]
     pub email: String,
 }
 
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct ResetPasswordRequest {
     pub token: String,
 
-    #[validate(length(min = 8, message = "Password must be at least 8 characters"))]
+    #[validate(length(min = 1, message = "New password cannot be empty"))]
     pub new_password: String,
 }
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct RegenerateRecoveryCodesRequest {
+    #[validate(length(min = 1, message = "Password cannot be empty"))]
+    pub password: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RecoveryCodesResponse {
+    /// 只在生成时返回一次，之前的恢复码全部失效
+    pub recovery_codes: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct RecoverAccountRequest {
+    #[validate(email(message = "Invalid email format"))]
+    pub email: String,
+
+    #[validate(length(min = 1, message = "Recovery code cannot be empty"))]
+    pub recovery_code: String,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ReactivateAccountRequest {
+    #[validate(email(message = "Invalid email format"))]
+    pub email: String,
+
+    #[validate(length(min = 1, message = "Password cannot be empty"))]
+    pub password: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RecoverAccountResponse {
+    /// 用于 /auth/reset-password 的密码重置令牌
+    pub reset_token: String,
+    pub remaining_recovery_codes: usize,
+}
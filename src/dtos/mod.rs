@@ -1,2 +1,5 @@
 pub mod auth;
+pub mod note;
+pub mod pagination;
+pub mod response;
 pub mod user;
@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::models::note::Note;
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateNoteRequest {
+    #[validate(length(min = 1, max = 200, message = "Title must be 1-200 characters"))]
+    pub title: String,
+
+    #[validate(length(max = 10000, message = "Content cannot exceed 10000 characters"))]
+    #[serde(default)]
+    pub content: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct NoteResponse {
+    pub id: String,
+    pub title: String,
+    pub content: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<Note> for NoteResponse {
+    fn from(note: Note) -> Self {
+        Self {
+            id: note.id,
+            title: note.title,
+            content: note.content,
+            created_at: note.created_at,
+            updated_at: note.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateNoteRequest {
+    #[validate(length(min = 1, max = 200, message = "Title must be 1-200 characters"))]
+    pub title: Option<String>,
+
+    #[validate(length(max = 10000, message = "Content cannot exceed 10000 characters"))]
+    pub content: Option<String>,
+}
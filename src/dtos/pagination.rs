@@ -0,0 +1,152 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{FromRequestParts, Query},
+    http::request::Parts,
+};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    config::server::ServerConfig,
+    errors::{api::ApiError, core::Error},
+    state::AppState,
+};
+
+/// 原始分页参数，缺省值和上限由 ServerConfig 决定；提供 cursor 时忽略 page
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PaginationQuery {
+    pub page: Option<u32>,
+    pub limit: Option<u32>,
+    pub cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Pagination {
+    pub page: u32,
+    pub limit: u32,
+    #[serde(skip)]
+    pub cursor: Option<Cursor>,
+}
+
+/// 按 created_at 倒序的 keyset 游标，id 用于区分同一时间创建的记录
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cursor {
+    pub created_at: DateTime<Utc>,
+    pub id: String,
+}
+
+impl Cursor {
+    pub fn new(created_at: DateTime<Utc>, id: String) -> Self {
+        Self { created_at, id }
+    }
+
+    /// 编码为不透明的 URL 安全字符串
+    pub fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(format!("{}|{}", self.created_at.to_rfc3339(), self.id))
+    }
+
+    pub fn decode(cursor: &str) -> Option<Self> {
+        let bytes = URL_SAFE_NO_PAD.decode(cursor).ok()?;
+        let raw = String::from_utf8(bytes).ok()?;
+        let (created_at, id) = raw.split_once('|')?;
+        let created_at = DateTime::parse_from_rfc3339(created_at)
+            .ok()?
+            .with_timezone(&Utc);
+        Some(Self::new(created_at, id.to_string()))
+    }
+}
+
+impl Pagination {
+    pub fn from_query(query: PaginationQuery, config: &ServerConfig) -> Result<Self, ApiError> {
+        let cursor = query
+            .cursor
+            .as_deref()
+            .map(|cursor| {
+                Cursor::decode(cursor).ok_or_else(|| ApiError::invalid_request("Invalid cursor"))
+            })
+            .transpose()?;
+        Ok(Self {
+            page: query.page.unwrap_or(1).max(1),
+            limit: query
+                .limit
+                .unwrap_or(config.default_page_size)
+                .clamp(1, config.max_page_size),
+            cursor,
+        })
+    }
+
+    /// SurrealQL 的 START 偏移量
+    pub fn start(&self) -> u32 {
+        (self.page - 1).saturating_mul(self.limit)
+    }
+
+    pub fn pages(&self, total: u64) -> u64 {
+        total.div_ceil(self.limit as u64)
+    }
+}
+
+impl FromRequestParts<Arc<AppState>> for Pagination {
+    type Rejection = Error;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        let Query(query) = Query::<PaginationQuery>::from_request_parts(parts, state)
+            .await
+            .map_err(|e| ApiError::invalid_request(e.body_text()))?;
+        Ok(Self::from_query(query, &state.env.server_config)?)
+    }
+}
+
+/// 列表接口统一的分页元信息
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PaginationMeta {
+    pub page: u32,
+    pub limit: u32,
+    pub total: u64,
+    pub pages: u64,
+    pub has_next: bool,
+    pub has_prev: bool,
+    /// 仅在本页取满时返回，用于 keyset 分页获取下一页
+    pub next_cursor: Option<String>,
+}
+
+/// 列表接口统一的响应结构 `{ data, pagination }`
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PaginatedResponse<T> {
+    pub data: Vec<T>,
+    pub pagination: PaginationMeta,
+}
+
+impl<T> PaginatedResponse<T> {
+    pub fn new(data: Vec<T>, pagination: &Pagination, total: u64) -> Self {
+        let pages = pagination.pages(total);
+        // 游标分页没有页码概念：取满一页视为还有下一页，携带游标视为有上一页
+        let (has_next, has_prev) = match pagination.cursor {
+            Some(_) => (data.len() == pagination.limit as usize, true),
+            None => ((pagination.page as u64) < pages, pagination.page > 1),
+        };
+        Self {
+            pagination: PaginationMeta {
+                page: pagination.page,
+                limit: pagination.limit,
+                total,
+                pages,
+                has_next,
+                has_prev,
+                next_cursor: None,
+            },
+            data,
+        }
+    }
+
+    pub fn with_next_cursor(mut self, next_cursor: Option<String>) -> Self {
+        self.pagination.next_cursor = next_cursor;
+        self
+    }
+}
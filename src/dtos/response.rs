@@ -0,0 +1,39 @@
+use axum::{
+    Json,
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// 成功响应的统一结构 `{ data, meta }`，与 ErrorResponse 一样携带请求 id 和时间戳
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ApiResponse<T> {
+    pub data: T,
+    pub meta: ResponseMeta,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ResponseMeta {
+    pub request_id: String,
+    pub timestamp: String,
+}
+
+impl<T> ApiResponse<T> {
+    pub fn new(data: T) -> Self {
+        Self {
+            data,
+            meta: ResponseMeta {
+                request_id: Uuid::new_v4().to_string(),
+                timestamp: Utc::now().to_rfc3339(),
+            },
+        }
+    }
+}
+
+impl<T: Serialize> IntoResponse for ApiResponse<T> {
+    fn into_response(self) -> Response {
+        Json(self).into_response()
+    }
+}
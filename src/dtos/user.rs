@@ -1,16 +1,47 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, de::Error as _};
+use utoipa::ToSchema;
 use validator::Validate;
 
-#[derive(Debug, Deserialize, Validate)]
+use crate::models::{
+    device::DeviceInfo, notification::NotificationPreferences, oauth_connection::OAuthConnection,
+    token_session::TokenSession,
+};
+
+/// PATCH 语义：省略的字段保持不变；姓名和邮箱不能清空，显式传 null 会被拒绝
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct UpdateProfileRequest {
+    #[serde(default, deserialize_with = "non_null")]
     #[validate(length(min = 1, message = "Name cannot be empty"))]
     pub name: Option<String>,
 
+    /// 新邮箱需通过确认邮件验证后才生效
+    #[serde(default, deserialize_with = "non_null")]
     #[validate(email(message = "Invalid email format"))]
     pub email: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+/// PUT 语义：替换整个资料，所有字段都必须提供
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ReplaceProfileRequest {
+    #[validate(length(min = 1, message = "Name cannot be empty"))]
+    pub name: String,
+
+    /// 新邮箱需通过确认邮件验证后才生效
+    #[validate(email(message = "Invalid email format"))]
+    pub email: String,
+}
+
+// 配合 #[serde(default)] 使用：字段缺失时为 None，字段存在但为 null 时报错
+fn non_null<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(Some)
+        .ok_or_else(|| D::Error::custom("field cannot be null, omit it to leave it unchanged"))
+}
+
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ProfileResponse {
     pub id: String,
     pub name: String,
@@ -19,15 +50,162 @@ pub struct ProfileResponse {
     pub role: String,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub active_sessions: usize,
+    /// 已申请修改但尚未确认的新邮箱
+    pub pending_email: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct SessionInfo {
     pub id: String,
-    pub device_info: Option<String>,
+    pub device: Option<String>,
+    pub device_info: Option<DeviceInfo>,
     pub ip_address: Option<String>,
     pub location: Option<String>,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub last_active_at: chrono::DateTime<chrono::Utc>,
     pub is_current: bool,
+    pub trusted: bool,
+    pub trusted_until: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct DeleteAccountRequest {
+    #[validate(length(min = 1, message = "Password cannot be empty"))]
+    pub password: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DeleteAccountResponse {
+    pub message: String,
+    /// 超过这个时间后账户会被彻底删除，在此之前可以通过 /auth/reactivate 恢复
+    pub purge_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ConnectionInfo {
+    pub provider: String,
+    pub email: Option<String>,
+    pub connected_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<OAuthConnection> for ConnectionInfo {
+    fn from(connection: OAuthConnection) -> Self {
+        Self {
+            provider: connection.provider,
+            email: connection.email,
+            connected_at: connection.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct TrustSessionRequest {
+    pub trusted: bool,
+}
+
+/// 数据导出中的用户资料，不包含密码哈希和历史密码
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ExportedProfile {
+    pub id: String,
+    pub name: String,
+    pub email: String,
+    pub role: String,
+    pub verified: bool,
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_login_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub pending_email: Option<String>,
+    pub tenant_id: Option<String>,
+    pub notification_preferences: NotificationPreferences,
+}
+
+/// 数据导出中的会话，不包含令牌 jti
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ExportedSession {
+    pub id: String,
+    pub device_info: Option<DeviceInfo>,
+    pub ip_address: Option<String>,
+    pub location: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub last_active_at: chrono::DateTime<chrono::Utc>,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub is_active: bool,
+    pub trusted: bool,
+    pub trusted_until: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl From<TokenSession> for ExportedSession {
+    fn from(session: TokenSession) -> Self {
+        Self {
+            id: session.id,
+            device_info: session.device_info,
+            ip_address: session.ip_address,
+            location: session.location,
+            created_at: session.created_at,
+            last_active_at: session.last_active_at,
+            expires_at: session.expires_at,
+            is_active: session.is_active,
+            trusted: session.trusted,
+            trusted_until: session.trusted_until,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UserDataExport {
+    pub exported_at: chrono::DateTime<chrono::Utc>,
+    pub profile: ExportedProfile,
+    /// 包含已撤销和已过期的会话
+    pub sessions: Vec<ExportedSession>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(body: &str) -> serde_json::Result<UpdateProfileRequest> {
+        serde_json::from_str(body)
+    }
+
+    #[test]
+    fn omitted_fields_are_left_unset() {
+        let request = parse("{}").unwrap();
+        assert_eq!((request.name, request.email), (None, None));
+
+        let request = parse(r#"{"name":"Ada"}"#).unwrap();
+        assert_eq!(
+            (request.name.as_deref(), request.email),
+            (Some("Ada"), None)
+        );
+
+        let request = parse(r#"{"email":"ada@example.com"}"#).unwrap();
+        assert_eq!(
+            (request.name, request.email.as_deref()),
+            (None, Some("ada@example.com"))
+        );
+
+        let request = parse(r#"{"name":"Ada","email":"ada@example.com"}"#).unwrap();
+        assert_eq!(
+            (request.name.as_deref(), request.email.as_deref()),
+            (Some("Ada"), Some("ada@example.com"))
+        );
+    }
+
+    #[test]
+    fn explicit_null_is_rejected() {
+        assert!(parse(r#"{"name":null}"#).is_err());
+        assert!(parse(r#"{"name":"Ada","email":null}"#).is_err());
+    }
+
+    #[test]
+    fn provided_fields_are_still_validated() {
+        assert!(parse(r#"{"name":""}"#).unwrap().validate().is_err());
+        assert!(
+            parse(r#"{"email":"not-an-email"}"#)
+                .unwrap()
+                .validate()
+                .is_err()
+        );
+        assert!(parse("{}").unwrap().validate().is_ok());
+    }
 }
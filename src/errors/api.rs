@@ -6,6 +6,8 @@ pub enum ApiError {
     InvalidRequest { message: String },
     #[error("Requested resource not found")]
     NotFound,
+    #[error("Method not allowed for this resource")]
+    MethodNotAllowed,
     #[error("API rate limit exceeded")]
     RateLimitExceeded,
     #[error("Unsupported media type")]
@@ -25,6 +27,7 @@ impl ApiError {
         match self {
             ApiError::InvalidRequest { .. } => "API_INVALID_REQUEST",
             ApiError::NotFound => "API_NOT_FOUND",
+            ApiError::MethodNotAllowed => "API_METHOD_NOT_ALLOWED",
             ApiError::RateLimitExceeded => "API_RATE_LIMIT_EXCEEDED",
             ApiError::UnsupportedMediaType => "API_UNSUPPORTED_MEDIA_TYPE",
             ApiError::PayloadTooLarge => "API_PAYLOAD_TOO_LARGE",
@@ -18,6 +18,18 @@ pub enum AuthError {
     EmptyPassword,
     #[error("Password must not be more than {max_length} characters")]
     PasswordTooLong { max_length: usize },
+    #[error("This password has appeared in a data breach, please choose another one")]
+    CompromisedPassword,
+    #[error("New password must not match a recently used password")]
+    PasswordReused,
+    #[error("Maximum number of active sessions reached")]
+    SessionLimitReached,
+    #[error("Account is temporarily locked due to too many failed login attempts")]
+    AccountLocked,
+    #[error("Account is scheduled for deletion, reactivate it to sign in again")]
+    AccountPendingDeletion,
+    #[error("Cannot unlink the last sign-in method, set a password first")]
+    LastLoginMethod,
     #[error("Error while hashing password")]
     HashingError,
     #[error("Invalid password hash format")]
@@ -43,6 +55,12 @@ impl AuthError {
             AuthError::UserNoLongerExists => "AUTH_USER_NOT_EXISTS",
             AuthError::EmptyPassword => "AUTH_EMPTY_PASSWORD",
             AuthError::PasswordTooLong { .. } => "AUTH_PASSWORD_TOO_LONG",
+            AuthError::CompromisedPassword => "AUTH_COMPROMISED_PASSWORD",
+            AuthError::PasswordReused => "AUTH_PASSWORD_REUSED",
+            AuthError::SessionLimitReached => "AUTH_SESSION_LIMIT_REACHED",
+            AuthError::AccountLocked => "AUTH_ACCOUNT_LOCKED",
+            AuthError::AccountPendingDeletion => "AUTH_ACCOUNT_PENDING_DELETION",
+            AuthError::LastLoginMethod => "AUTH_LAST_LOGIN_METHOD",
             AuthError::HashingError => "AUTH_HASHING_ERROR",
             AuthError::InvalidHashFormat => "AUTH_INVALID_HASH_FORMAT",
             AuthError::PermissionDenied => "AUTH_PERMISSION_DENIED",
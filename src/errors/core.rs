@@ -1,4 +1,5 @@
 use axum::response::{IntoResponse, Response};
+use std::sync::OnceLock;
 use thiserror::Error;
 use tracing::{error, warn};
 use uuid::Uuid;
@@ -76,7 +77,14 @@ impl Error {
                 );
             }
             Error::Auth(err) => match err {
-                AuthError::InvalidCredentials | AuthError::TokenExpired => {
+                AuthError::InvalidCredentials
+                | AuthError::TokenExpired
+                | AuthError::CompromisedPassword
+                | AuthError::PasswordReused
+                | AuthError::SessionLimitReached
+                | AuthError::AccountLocked
+                | AuthError::AccountPendingDeletion
+                | AuthError::LastLoginMethod => {
                     warn!(
                         error = %err,
                         trace_id = %trace_id,
@@ -128,13 +136,53 @@ impl IntoResponse for Error {
     }
 }
 
+static VERBOSE_ERRORS: OnceLock<bool> = OnceLock::new();
+
+/// 启动时根据配置设置一次，未设置时视为关闭
+pub fn set_verbose_errors(enabled: bool) {
+    let _ = VERBOSE_ERRORS.set(enabled);
+}
+
+fn verbose_errors() -> bool {
+    VERBOSE_ERRORS.get().copied().unwrap_or(false)
+}
+
+/// 被隐藏成通用消息的错误在详细模式下附带的调试信息
+fn verbose_details(error: &Error) -> Option<serde_json::Value> {
+    match error {
+        Error::Db(err) => {
+            let source = std::error::Error::source(err).map(|source| format!("{:#}", source));
+            let mut details = serde_json::json!({
+                "error": err.to_string(),
+                "source": source,
+            });
+            match err {
+                DatabaseError::QueryError { query, .. } => {
+                    details["query"] = serde_json::json!(query)
+                }
+                DatabaseError::ConnectionError { context, .. } => {
+                    details["context"] = serde_json::json!(context)
+                }
+                DatabaseError::TransactionError { operation, .. } => {
+                    details["operation"] = serde_json::json!(operation)
+                }
+                _ => {}
+            }
+            Some(details)
+        }
+        Error::Internal { message, .. } => Some(serde_json::json!({ "error": message })),
+        _ => None,
+    }
+}
+
 impl From<Error> for HttpError {
     fn from(error: Error) -> Self {
         let trace_id = error.trace_id();
 
         error.log_error();
 
-        match error {
+        let verbose_details = verbose_errors().then(|| verbose_details(&error)).flatten();
+        let http_error = match error {
             Error::Jwt(err) => match err {
                 JwtError::TokenExpired => HttpError::with_trace_id(
                     "JWT token has expired",
@@ -194,13 +242,23 @@ impl From<Error> for HttpError {
                         trace_id,
                     )
                 }
-                AuthError::PermissionDenied => HttpError::with_trace_id(
+                AuthError::PermissionDenied | AuthError::AccountPendingDeletion => {
+                    HttpError::with_trace_id(
+                        err.to_string(),
+                        axum::http::StatusCode::FORBIDDEN,
+                        err.error_code(),
+                        trace_id,
+                    )
+                }
+                AuthError::AccountLocked => HttpError::with_trace_id(
                     err.to_string(),
-                    axum::http::StatusCode::FORBIDDEN,
+                    axum::http::StatusCode::LOCKED,
                     err.error_code(),
                     trace_id,
                 ),
-                AuthError::EmailAlreadyExists => HttpError::with_trace_id(
+                AuthError::EmailAlreadyExists
+                | AuthError::SessionLimitReached
+                | AuthError::LastLoginMethod => HttpError::with_trace_id(
                     err.to_string(),
                     axum::http::StatusCode::CONFLICT,
                     err.error_code(),
@@ -221,6 +279,12 @@ impl From<Error> for HttpError {
                     err.error_code(),
                     trace_id,
                 ),
+                ApiError::MethodNotAllowed => HttpError::with_trace_id(
+                    err.to_string(),
+                    axum::http::StatusCode::METHOD_NOT_ALLOWED,
+                    err.error_code(),
+                    trace_id,
+                ),
                 ApiError::RateLimitExceeded => HttpError::with_trace_id(
                     err.to_string(),
                     axum::http::StatusCode::TOO_MANY_REQUESTS,
@@ -233,6 +297,12 @@ impl From<Error> for HttpError {
                     err.error_code(),
                     trace_id,
                 ),
+                ApiError::UnsupportedMediaType => HttpError::with_trace_id(
+                    err.to_string(),
+                    axum::http::StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                    err.error_code(),
+                    trace_id,
+                ),
                 _ => HttpError::with_trace_id(
                     err.to_string(),
                     axum::http::StatusCode::BAD_REQUEST,
@@ -244,25 +314,29 @@ impl From<Error> for HttpError {
                 let mut error_details = serde_json::Map::new();
 
                 for (field, errors) in validation_errors.field_errors() {
-                    let field_errors: Vec<String> = errors
+                    let field_errors: Vec<serde_json::Value> = errors
                         .iter()
                         .map(|error| {
-                            error
+                            let message = error
                                 .message
                                 .as_ref()
                                 .map(|m| m.to_string())
-                                .unwrap_or_else(|| format!("Invalid value for field: {}", field))
+                                .unwrap_or_else(|| format!("Invalid value for field: {}", field));
+                            // validator 会自动附带原始输入 value，可能包含密码，不能回显
+                            let params: serde_json::Map<String, serde_json::Value> = error
+                                .params
+                                .iter()
+                                .filter(|(key, _)| key.as_ref() != "value")
+                                .map(|(key, value)| (key.to_string(), value.clone()))
+                                .collect();
+                            serde_json::json!({
+                                "code": error.code,
+                                "message": message,
+                                "params": params,
+                            })
                         })
                         .collect();
-                    error_details.insert(
-                        field.to_string(),
-                        serde_json::Value::Array(
-                            field_errors
-                                .into_iter()
-                                .map(serde_json::Value::String)
-                                .collect(),
-                        ),
-                    );
+                    error_details.insert(field.to_string(), serde_json::Value::Array(field_errors));
                 }
                 HttpError::with_trace_id(
                     "Validation failed",
@@ -276,6 +350,11 @@ impl From<Error> for HttpError {
             Error::Internal { .. } => {
                 HttpError::server_error_with_trace_id("Internal server error", trace_id)
             }
+        };
+
+        match verbose_details {
+            Some(details) if http_error.details.is_none() => http_error.with_details(details),
+            _ => http_error,
         }
     }
 }
@@ -50,21 +50,100 @@ impl DatabaseError {
             operation: operation.into(),
         }
     }
+
+    /// 连接类错误通常是瞬时的，重试可能成功；约束冲突、记录不存在等重试也不会改变结果
+    pub fn is_transient(&self) -> bool {
+        matches!(self, Self::ConnectionError { .. })
+    }
 }
 
 impl From<surrealdb::Error> for DatabaseError {
     fn from(error: surrealdb::Error) -> Self {
+        use surrealdb::error::{Api, Db};
         match error {
-            surrealdb::Error::Db(db_error) => {
-                if db_error.to_string().contains("not found") {
-                    DatabaseError::NotFound(db_error.to_string())
-                } else if db_error.to_string().contains("constraint") {
-                    DatabaseError::ConstraintViolation(db_error.to_string())
-                } else {
-                    DatabaseError::query_failed(anyhow::Error::new(db_error), None)
-                }
+            surrealdb::Error::Api(Api::Ws(_) | Api::Http(_) | Api::ConnectionUninitialised)
+            | surrealdb::Error::Db(Db::QueryTimedout | Db::TxRetryable) => {
+                DatabaseError::connection_failed(error, "Transient database error")
+            }
+            surrealdb::Error::Db(
+                Db::IndexExists { .. } | Db::RecordExists { .. } | Db::TxKeyAlreadyExists,
+            ) => DatabaseError::ConstraintViolation(error.to_string()),
+            surrealdb::Error::Db(Db::IdNotFound { .. }) => {
+                DatabaseError::NotFound(error.to_string())
+            }
+            // 远程连接只能拿到服务端渲染好的错误信息，按对应类型错误的固定格式识别
+            surrealdb::Error::Api(Api::Query(ref message)) if is_unique_violation(message) => {
+                DatabaseError::ConstraintViolation(message.clone())
             }
             _ => DatabaseError::query_failed(anyhow::Error::new(error), None),
         }
     }
 }
+
+/// 对应 `Db::IndexExists` 和 `Db::RecordExists` 的错误信息格式
+fn is_unique_violation(message: &str) -> bool {
+    (message.starts_with("Database index `") && message.contains("` already contains "))
+        || (message.starts_with("Database record `") && message.ends_with("` already exists"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use surrealdb::{
+        error::{Api, Db},
+        sql::Thing,
+    };
+
+    fn unique_index_error() -> Db {
+        Db::IndexExists {
+            thing: Thing::from(("users", "alice")),
+            index: "tenant_email_idx".to_string(),
+            value: "[NONE, 'alice@example.com']".to_string(),
+        }
+    }
+
+    #[test]
+    fn unique_index_violation_is_constraint_violation() {
+        let error = DatabaseError::from(surrealdb::Error::Db(unique_index_error()));
+        assert!(matches!(error, DatabaseError::ConstraintViolation(_)));
+    }
+
+    #[test]
+    fn remote_unique_index_violation_is_constraint_violation() {
+        let message = unique_index_error().to_string();
+        let error = DatabaseError::from(surrealdb::Error::Api(Api::Query(message)));
+        assert!(matches!(error, DatabaseError::ConstraintViolation(_)));
+    }
+
+    #[test]
+    fn existing_record_is_constraint_violation() {
+        let error = DatabaseError::from(surrealdb::Error::Db(Db::RecordExists {
+            thing: Thing::from(("users", "alice")),
+        }));
+        assert!(matches!(error, DatabaseError::ConstraintViolation(_)));
+    }
+
+    #[test]
+    fn missing_record_is_not_found() {
+        let error = DatabaseError::from(surrealdb::Error::Db(Db::IdNotFound {
+            rid: "users:alice".to_string(),
+        }));
+        assert!(matches!(error, DatabaseError::NotFound(_)));
+    }
+
+    #[test]
+    fn unrelated_message_mentioning_constraint_is_query_error() {
+        let error = DatabaseError::from(surrealdb::Error::Api(Api::Query(
+            "Parse error: unexpected token near constraint".to_string(),
+        )));
+        assert!(matches!(error, DatabaseError::QueryError { .. }));
+    }
+
+    #[test]
+    fn connection_errors_are_transient() {
+        let error = DatabaseError::from(surrealdb::Error::Api(Api::Ws(
+            "connection reset".to_string(),
+        )));
+        assert!(error.is_transient());
+    }
+}
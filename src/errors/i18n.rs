@@ -0,0 +1,98 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    #[default]
+    En,
+    Zh,
+}
+
+impl Locale {
+    /// 按 q 值从高到低选择第一个支持的语言，无法匹配时回退到英文
+    pub fn from_accept_language(header: &str) -> Self {
+        let mut candidates: Vec<(&str, f32)> = header
+            .split(',')
+            .filter_map(|part| {
+                let mut pieces = part.trim().split(';');
+                let tag = pieces.next()?.trim();
+                if tag.is_empty() {
+                    return None;
+                }
+                let quality = pieces
+                    .find_map(|p| p.trim().strip_prefix("q="))
+                    .and_then(|q| q.parse::<f32>().ok())
+                    .unwrap_or(1.0);
+                Some((tag, quality))
+            })
+            .collect();
+        candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        candidates
+            .into_iter()
+            .filter(|(_, quality)| *quality > 0.0)
+            .find_map(|(tag, _)| Self::from_tag(tag))
+            .unwrap_or_default()
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Zh => "zh",
+        }
+    }
+
+    fn from_tag(tag: &str) -> Option<Self> {
+        let primary = tag.split('-').next().unwrap_or(tag);
+        if primary.eq_ignore_ascii_case("en") {
+            Some(Locale::En)
+        } else if primary.eq_ignore_ascii_case("zh") {
+            Some(Locale::Zh)
+        } else {
+            None
+        }
+    }
+}
+
+/// 根据错误码查找本地化消息，英文直接使用错误本身的消息
+pub fn translate(code: &str, locale: Locale) -> Option<&'static str> {
+    match locale {
+        Locale::En => None,
+        Locale::Zh => zh(code),
+    }
+}
+
+fn zh(code: &str) -> Option<&'static str> {
+    let message = match code {
+        "AUTH_INVALID_CREDENTIALS" => "提供的凭据无效",
+        "AUTH_TOKEN_EXPIRED" => "访问令牌已过期",
+        "AUTH_TOKEN_NOT_PROVIDED" => "未提供身份验证令牌",
+        "AUTH_INVALID_TOKEN" => "身份验证令牌无效或格式错误",
+        "AUTH_EMAIL_EXISTS" => "该邮箱已被注册",
+        "AUTH_USER_NOT_EXISTS" => "令牌对应的用户已不存在",
+        "AUTH_EMPTY_PASSWORD" => "密码不能为空",
+        "AUTH_PASSWORD_TOO_LONG" => "密码长度超出限制",
+        "AUTH_COMPROMISED_PASSWORD" => "该密码已出现在数据泄露中，请更换其他密码",
+        "AUTH_PASSWORD_REUSED" => "新密码不能与最近使用过的密码相同",
+        "AUTH_SESSION_LIMIT_REACHED" => "活跃会话数量已达上限",
+        "AUTH_ACCOUNT_LOCKED" => "登录失败次数过多，账户已被暂时锁定",
+        "AUTH_ACCOUNT_PENDING_DELETION" => "账户已申请删除，请先重新激活",
+        "AUTH_LAST_LOGIN_METHOD" => "不能解绑最后一种登录方式，请先设置密码",
+        "AUTH_HASHING_ERROR" => "密码哈希时出错",
+        "AUTH_INVALID_HASH_FORMAT" => "密码哈希格式无效",
+        "AUTH_PERMISSION_DENIED" => "你无权执行此操作",
+        "AUTH_NOT_AUTHENTICATED" => "需要身份验证，请先登录",
+        "JWT_INVALID_TOKEN" => "无效的 JWT 令牌",
+        "JWT_TOKEN_EXPIRED" => "JWT 令牌已过期",
+        "JWT_INVALID_SIGNATURE" => "无效的 JWT 令牌",
+        "JWT_INVALID_FORMAT" => "无效的 JWT 令牌",
+        "JWT_INVALID_EXTRA_CLAIM" => "令牌包含不允许的自定义声明",
+        "API_NOT_FOUND" => "请求的资源不存在",
+        "API_METHOD_NOT_ALLOWED" => "该资源不支持此请求方法",
+        "API_RATE_LIMIT_EXCEEDED" => "请求过于频繁",
+        "API_UNSUPPORTED_MEDIA_TYPE" => "不支持的媒体类型",
+        "API_PAYLOAD_TOO_LARGE" => "请求体过大",
+        "VALIDATION_ERROR" => "参数校验失败",
+        "DB_CONSTRAINT_VIOLATION" => "数据与已有记录冲突",
+        "INTERNAL_SERVER_ERROR" => "服务器内部错误",
+        _ => return None,
+    };
+    Some(message)
+}
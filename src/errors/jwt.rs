@@ -22,9 +22,17 @@ pub enum JwtError {
     InvalidHeader,
     #[error("Invalid JWT payload")]
     InvalidPayload,
+    #[error("Extra claim rejected: {message}")]
+    InvalidExtraClaim { message: String },
 }
 
 impl JwtError {
+    pub fn invalid_extra_claim(message: impl Into<String>) -> Self {
+        Self::InvalidExtraClaim {
+            message: message.into(),
+        }
+    }
+
     pub fn error_code(&self) -> &'static str {
         match self {
             JwtError::InvalidToken => "JWT_INVALID_TOKEN",
@@ -37,6 +45,7 @@ impl JwtError {
             JwtError::DecodingError => "JWT_DECODING_ERROR",
             JwtError::InvalidHeader => "JWT_INVALID_HEADER",
             JwtError::InvalidPayload => "JWT_INVALID_PAYLOAD",
+            JwtError::InvalidExtraClaim { .. } => "JWT_INVALID_EXTRA_CLAIM",
         }
     }
 }
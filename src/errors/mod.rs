@@ -2,5 +2,6 @@ pub mod api;
 pub mod auth;
 pub mod core;
 pub mod db;
+pub mod i18n;
 pub mod jwt;
 pub mod response;
@@ -6,16 +6,17 @@ use axum::{
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ErrorResponse {
     pub error: ErrorDetail,
     pub trace_id: Option<String>,
     pub timestamp: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ErrorDetail {
     pub code: String,
     pub message: String,
@@ -145,12 +146,20 @@ impl HttpError {
     }
 
     pub fn into_http_response(self) -> Response {
-        let json_response = Json(ErrorResponse::new(
-            self.code.clone(),
-            self.message.clone(),
-            self.trace_id,
-        ));
-        (self.status, json_response).into_response()
+        let error_response = match self.details.clone() {
+            Some(details) => ErrorResponse::with_details(
+                self.code.clone(),
+                self.message.clone(),
+                details,
+                self.trace_id,
+            ),
+            None => ErrorResponse::new(self.code.clone(), self.message.clone(), self.trace_id),
+        };
+        let status = self.status;
+        let mut response = (status, Json(error_response)).into_response();
+        // 供本地化中间件按错误码改写消息
+        response.extensions_mut().insert(self);
+        response
     }
 }
 
@@ -0,0 +1,125 @@
+use std::net::IpAddr;
+
+use axum::{
+    extract::FromRequestParts,
+    http::{HeaderMap, Request, request::Parts},
+};
+use tower_governor::{GovernorError, key_extractor::KeyExtractor};
+
+use crate::{config::network::NetworkConfig, errors::core::Error};
+
+const X_FORWARDED_FOR: &str = "x-forwarded-for";
+const X_REAL_IP: &str = "x-real-ip";
+
+/// 真实客户端地址，由 client_ip_middleware 按 TRUSTED_PROXIES 解析后放入请求扩展
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientIp(pub IpAddr);
+
+impl ClientIp {
+    /// 直连对端是受信任代理时，从右向左跳过 X-Forwarded-For 中的受信任代理，取第一个不受信任的地址，
+    /// 没有 X-Forwarded-For 时使用 X-Real-IP；否则直接使用对端地址，避免客户端伪造请求头
+    pub fn resolve(network: &NetworkConfig, peer: IpAddr, headers: &HeaderMap) -> IpAddr {
+        if !network.is_trusted_proxy(peer) {
+            return peer;
+        }
+        let forwarded: Vec<IpAddr> = headers
+            .get_all(X_FORWARDED_FOR)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .filter_map(|entry| entry.trim().parse::<IpAddr>().ok())
+            .collect();
+        if let Some(ip) = forwarded
+            .iter()
+            .rev()
+            .find(|ip| !network.is_trusted_proxy(**ip))
+            .or(forwarded.first())
+        {
+            return *ip;
+        }
+        headers
+            .get(X_REAL_IP)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<IpAddr>().ok())
+            .unwrap_or(peer)
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for ClientIp {
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<ClientIp>()
+            .copied()
+            .ok_or_else(|| Error::internal("Client IP was not resolved for this request"))
+    }
+}
+
+/// 供 tower_governor 使用的限流键，代替只看对端地址的 PeerIpKeyExtractor
+#[derive(Debug, Clone, Copy)]
+pub struct ClientIpKeyExtractor;
+
+impl KeyExtractor for ClientIpKeyExtractor {
+    type Key = IpAddr;
+
+    fn extract<T>(&self, req: &Request<T>) -> Result<Self::Key, GovernorError> {
+        req.extensions()
+            .get::<ClientIp>()
+            .map(|ip| ip.0)
+            .ok_or(GovernorError::UnableToExtractKey)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn network(trusted_proxies: &[&str]) -> NetworkConfig {
+        NetworkConfig {
+            trusted_proxies: trusted_proxies
+                .iter()
+                .map(|net| net.parse().unwrap())
+                .collect(),
+            admin_allowlist: Vec::new(),
+            admin_denylist: Vec::new(),
+        }
+    }
+
+    fn headers(name: &'static str, value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(name, value.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn forwarded_header_from_untrusted_peer_is_ignored() {
+        let network = network(&["192.168.0.0/24"]);
+        let peer: IpAddr = "203.0.113.7".parse().unwrap();
+        let ip = ClientIp::resolve(&network, peer, &headers(X_FORWARDED_FOR, "10.8.0.1"));
+        assert_eq!(ip, peer);
+    }
+
+    #[test]
+    fn trusted_proxies_are_skipped_from_the_right() {
+        let network = network(&["192.168.0.0/24"]);
+        let peer: IpAddr = "192.168.0.2".parse().unwrap();
+        let headers = headers(X_FORWARDED_FOR, "1.2.3.4, 10.8.0.1, 192.168.0.3");
+        assert_eq!(
+            ClientIp::resolve(&network, peer, &headers),
+            "10.8.0.1".parse::<IpAddr>().unwrap()
+        );
+    }
+
+    #[test]
+    fn real_ip_header_is_used_without_forwarded_for() {
+        let network = network(&["192.168.0.0/24"]);
+        let peer: IpAddr = "192.168.0.2".parse().unwrap();
+        let headers = headers(X_REAL_IP, "10.8.0.1");
+        assert_eq!(
+            ClientIp::resolve(&network, peer, &headers),
+            "10.8.0.1".parse::<IpAddr>().unwrap()
+        );
+    }
+}
@@ -0,0 +1,45 @@
+use axum::{
+    Form, Json,
+    extract::{FromRequest, Request},
+    http::header::CONTENT_TYPE,
+    response::{IntoResponse, Response},
+};
+use serde::de::DeserializeOwned;
+
+use crate::errors::{api::ApiError, core::Error};
+
+/// 根据 Content-Type 解析 JSON 或 application/x-www-form-urlencoded 请求体，
+/// 解析结果相同，后续校验逻辑不受编码方式影响
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonOrForm<T>(pub T);
+
+impl<S, T> FromRequest<S> for JsonOrForm<T>
+where
+    S: Send + Sync,
+    T: DeserializeOwned,
+{
+    type Rejection = Response;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let content_type = request
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        if content_type.starts_with("application/x-www-form-urlencoded") {
+            let Form(payload) = Form::<T>::from_request(request, state)
+                .await
+                .map_err(IntoResponse::into_response)?;
+            Ok(Self(payload))
+        } else if content_type.starts_with("application/json") {
+            let Json(payload) = Json::<T>::from_request(request, state)
+                .await
+                .map_err(IntoResponse::into_response)?;
+            Ok(Self(payload))
+        } else {
+            Err(Error::from(ApiError::UnsupportedMediaType).into_response())
+        }
+    }
+}
@@ -0,0 +1,3 @@
+pub mod client_ip;
+pub mod json_or_form;
+pub mod tenant;
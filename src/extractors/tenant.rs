@@ -0,0 +1,53 @@
+use std::{convert::Infallible, sync::Arc};
+
+use axum::{
+    extract::FromRequestParts,
+    http::{HeaderMap, header::HOST, request::Parts},
+};
+
+use crate::{models::token_claims::TokenClaims, state::AppState};
+
+/// 当前请求所属的租户；已认证请求取令牌中的 tenant_id，
+/// 未认证请求（注册、登录）按 TENANT_BASE_DOMAIN 从子域名解析。
+/// 未启用多租户或无法解析时为 None，表示全局（无租户）用户空间
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TenantContext(pub Option<String>);
+
+impl TenantContext {
+    /// `acme.example.com` 在基础域名为 `example.com` 时解析为 `acme`，只接受单级子域名
+    pub fn from_host(host: &str, base_domain: &str) -> Option<String> {
+        let host = host.split(':').next().unwrap_or_default().to_lowercase();
+        let tenant = host.strip_suffix(base_domain)?.strip_suffix('.')?;
+        if tenant.is_empty() || tenant.contains('.') {
+            return None;
+        }
+        Some(tenant.to_string())
+    }
+
+    /// 只按 Host 子域名解析，不看令牌；未配置 TENANT_BASE_DOMAIN 时为 None
+    pub fn from_headers(headers: &HeaderMap, state: &AppState) -> Option<String> {
+        let base_domain = state.env.server_config.tenant_base_domain.as_deref()?;
+        headers
+            .get(HOST)
+            .and_then(|host| host.to_str().ok())
+            .and_then(|host| Self::from_host(host, base_domain))
+    }
+
+    pub fn tenant_id(&self) -> Option<String> {
+        self.0.clone()
+    }
+}
+
+impl FromRequestParts<Arc<AppState>> for TenantContext {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        if let Some(claims) = parts.extensions.get::<TokenClaims>() {
+            return Ok(Self(claims.tenant_id()));
+        }
+        Ok(Self(Self::from_headers(&parts.headers, state)))
+    }
+}
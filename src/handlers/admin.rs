@@ -1,28 +1,48 @@
 use axum::{
     Extension,
-    extract::{Query, State},
-    response::Json,
+    extract::{Path, Query, State},
+    response::{
+        Json,
+        sse::{Event, KeepAlive, Sse},
+    },
 };
+use chrono::Utc;
+use futures::{Stream, stream};
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use std::{convert::Infallible, sync::Arc, time::Duration};
+use surrealdb::Surreal;
+use utoipa::{IntoParams, ToSchema};
 
 use crate::{
-    errors::{auth::AuthError, core::Result},
-    models::{role::Role, token_claims::TokenClaims},
-    services::user::UserService,
+    database::tenant::CURRENT_TENANT_DB,
+    dtos::pagination::{Cursor, PaginatedResponse, Pagination, PaginationQuery},
+    errors::{
+        api::ApiError, auth::AuthError, core::Result, db::DatabaseError, response::ErrorResponse,
+    },
+    extractors::tenant::TenantContext,
+    models::{
+        device::DeviceInfo,
+        feature_flag::FeatureFlag,
+        maintenance::{MaintenanceMode, MaintenanceStatus},
+        notification::EmailCategory,
+        role::Role,
+        token_claims::TokenClaims,
+        token_scope::TokenScope,
+        token_session::{SessionIpFilter, SessionRevocationCriteria, TokenSession},
+        user::{User, UserListFilter},
+    },
+    services::{email::EmailService, user::UserService},
     state::AppState,
 };
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
 pub struct UserListQuery {
-    pub page: Option<u32>,
-    pub limit: Option<u32>,
     pub search: Option<String>,
     pub role: Option<String>,
     pub verified: Option<bool>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct AdminUserInfo {
     pub id: String,
     pub name: String,
@@ -31,20 +51,156 @@ pub struct AdminUserInfo {
     pub verified: bool,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_login_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub failed_login_attempts: u32,
+    pub locked_until: Option<chrono::DateTime<chrono::Utc>>,
     pub active_sessions: usize,
 }
 
-#[derive(Debug, Serialize)]
+/// ip 可以是单个 IPv4/IPv6 地址，或任意前缀长度的 CIDR 网段，如 10.0.0.0/12、2001:db8::/48
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct SessionSearchQuery {
+    pub ip: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AdminSessionInfo {
+    pub id: String,
+    pub user_id: String,
+    pub device_info: Option<DeviceInfo>,
+    pub ip_address: Option<String>,
+    pub location: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub last_active_at: chrono::DateTime<chrono::Utc>,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub is_active: bool,
+}
+
+impl From<TokenSession> for AdminSessionInfo {
+    fn from(session: TokenSession) -> Self {
+        Self {
+            id: session.id,
+            user_id: session.user_id,
+            device_info: session.device_info,
+            ip_address: session.ip_address,
+            location: session.location,
+            created_at: session.created_at,
+            last_active_at: session.last_active_at,
+            expires_at: session.expires_at,
+            is_active: session.is_active,
+        }
+    }
+}
+
+/// 会话所属用户的基本信息
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SessionOwnerInfo {
+    pub id: String,
+    pub name: String,
+    pub email: String,
+    pub role: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AdminSessionDetail {
+    pub session: AdminSessionInfo,
+    /// 用户已被删除时为空
+    pub user: Option<SessionOwnerInfo>,
+}
+
+/// 三个条件必须且只能提供一个
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BulkRevokeSessionsRequest {
+    pub older_than_days: Option<i64>,
+    pub ip_address: Option<String>,
+    pub role: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateFeatureFlagRequest {
+    pub enabled: bool,
+}
+
+/// retry_after_seconds 缺省时使用 MAINTENANCE_RETRY_AFTER
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateMaintenanceRequest {
+    pub mode: MaintenanceMode,
+    pub message: Option<String>,
+    pub retry_after_seconds: Option<u64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ScopeInfo {
+    pub scope: String,
+    pub description: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
 pub struct SystemStats {
     pub total_users: u64,
     pub verified_users: u64,
     pub active_sessions: u64,
+    /// 当前租户在 token_sessions 表中的行数，包括已撤销但尚未被清理的会话
+    pub total_sessions: u64,
+    /// 总行数超过 SESSION_TABLE_ALERT_THRESHOLD 时为 true，可直接用于告警
+    pub session_table_alert: bool,
     pub admin_users: u64,
     pub recent_registrations: u64,
 }
 
+async fn collect_system_stats(
+    app_state: Arc<AppState>,
+    tenant_id: Option<String>,
+) -> Result<SystemStats> {
+    let user_service = UserService::new();
+    let recent_since = Utc::now() - chrono::Duration::days(7);
+
+    // 告警针对的是整张表的增长，因此按全表行数判断，不暴露具体数值
+    let table_sessions = app_state
+        .token_service
+        .count_all_sessions(app_state.clone())
+        .await?;
+
+    Ok(SystemStats {
+        total_users: user_service
+            .count_users(app_state.clone(), tenant_id.clone())
+            .await?,
+        verified_users: user_service
+            .count_verified_users(app_state.clone(), tenant_id.clone())
+            .await?,
+        active_sessions: app_state
+            .token_service
+            .count_active_sessions(app_state.clone(), tenant_id.clone())
+            .await?,
+        total_sessions: app_state
+            .token_service
+            .count_tenant_sessions(app_state.clone(), tenant_id.clone())
+            .await?,
+        session_table_alert: table_sessions
+            > app_state.env.token_config.session_table_alert_threshold,
+        admin_users: user_service
+            .count_users_by_role(app_state.clone(), tenant_id.clone(), Role::Admin)
+            .await?,
+        recent_registrations: user_service
+            .count_users_created_since(app_state.clone(), tenant_id, recent_since)
+            .await?,
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/stats",
+    tag = "admin",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "System statistics", body = SystemStats),
+        (status = 403, description = "Admin scope required", body = ErrorResponse)
+    )
+)]
 pub async fn get_system_stats(
+    State(app_state): State<Arc<AppState>>,
     Extension(claims): Extension<TokenClaims>,
+    tenant: TenantContext,
 ) -> Result<Json<SystemStats>> {
     // 验证管理员权限
     if !claims
@@ -56,24 +212,88 @@ pub async fn get_system_stats(
         return Err(AuthError::PermissionDenied.into());
     }
 
-    // TODO: 实现实际的统计查询
-    // 这里需要在Repository中添加统计查询方法
-    let stats = SystemStats {
-        total_users: 0,          // 从数据库查询
-        verified_users: 0,       // 从数据库查询
-        active_sessions: 0,      // 从token_sessions表查询
-        admin_users: 0,          // 从数据库查询
-        recent_registrations: 0, // 查询最近注册的用户数
-    };
+    let stats = collect_system_stats(app_state, tenant.tenant_id()).await?;
 
     Ok(Json(stats))
 }
 
+/// 以 SSE 的形式定期推送系统统计，客户端断开时 axum 会丢弃该 stream
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/stats/stream",
+    tag = "admin",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "SSE stream of `stats` events carrying SystemStats", content_type = "text/event-stream", body = SystemStats),
+        (status = 403, description = "Admin scope required", body = ErrorResponse)
+    )
+)]
+pub async fn stream_system_stats(
+    State(app_state): State<Arc<AppState>>,
+    Extension(claims): Extension<TokenClaims>,
+    tenant: TenantContext,
+) -> Result<Sse<impl Stream<Item = std::result::Result<Event, Infallible>>>> {
+    if !claims
+        .role
+        .as_ref()
+        .map(|r| matches!(r, Role::Admin))
+        .unwrap_or(false)
+    {
+        return Err(AuthError::PermissionDenied.into());
+    }
+
+    let interval = tokio::time::interval(Duration::from_secs(
+        app_state.env.server_config.stats_stream_interval,
+    ));
+
+    // SSE 的 body 在 handler 返回后才被轮询，已离开租户中间件的作用域，需要自行保留租户库连接
+    let tenant_db = CURRENT_TENANT_DB.try_with(Surreal::clone).ok();
+
+    let stream = stream::unfold(
+        (app_state, tenant.tenant_id(), tenant_db, interval),
+        |(app_state, tenant_id, tenant_db, mut interval)| async move {
+            interval.tick().await;
+            let collect = collect_system_stats(app_state.clone(), tenant_id.clone());
+            let result = match tenant_db.clone() {
+                Some(db) => CURRENT_TENANT_DB.scope(db, collect).await,
+                None => collect.await,
+            };
+            let event = match result {
+                Ok(stats) => Event::default()
+                    .event("stats")
+                    .json_data(&stats)
+                    .unwrap_or_else(|_| Event::default().event("error")),
+                Err(err) => {
+                    err.log_error();
+                    Event::default().event("error").data(err.error_code())
+                }
+            };
+            Some((Ok(event), (app_state, tenant_id, tenant_db, interval)))
+        },
+    );
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
 /// 获取所有用户列表（仅管理员）
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/users",
+    tag = "admin",
+    params(PaginationQuery, UserListQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Paginated user list", body = PaginatedResponse<AdminUserInfo>),
+        (status = 403, description = "Admin scope required", body = ErrorResponse)
+    )
+)]
 pub async fn list_users(
+    State(app_state): State<Arc<AppState>>,
     Extension(claims): Extension<TokenClaims>,
+    tenant: TenantContext,
+    pagination: Pagination,
     Query(query): Query<UserListQuery>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<Json<PaginatedResponse<AdminUserInfo>>> {
     if !claims
         .role
         .as_ref()
@@ -83,29 +303,74 @@ pub async fn list_users(
         return Err(AuthError::PermissionDenied.into());
     }
 
-    // TODO: 实现分页用户查询
-    // 这里需要在UserRepository中添加分页查询方法
-    let _page = query.page.unwrap_or(1);
-    let _limit = query.limit.unwrap_or(10);
+    let role = match query.role.as_deref() {
+        None => None,
+        Some(role) => Some(
+            Role::parse(role)
+                .ok_or_else(|| ApiError::invalid_request(format!("Unknown role: {}", role)))?,
+        ),
+    };
+    let filter = UserListFilter {
+        search: query.search.filter(|search| !search.trim().is_empty()),
+        role,
+        verified: query.verified,
+        tenant_id: tenant.tenant_id(),
+    };
 
-    // 临时返回空列表
-    let users: Vec<AdminUserInfo> = vec![];
+    let user_service = UserService::new();
+    let (users, total) = user_service
+        .list_users(app_state.clone(), filter, pagination.clone())
+        .await?;
 
-    Ok(Json(serde_json::json!({
-        "users": users,
-        "pagination": {
-            "page": query.page.unwrap_or(1),
-            "limit": query.limit.unwrap_or(10),
-            "total": 0,
-            "pages": 0
-        }
-    })))
+    // 本页取满时才返回下一页游标
+    let next_cursor = users
+        .last()
+        .filter(|_| users.len() == pagination.limit as usize)
+        .map(|user| Cursor::new(user.created_at.unwrap_or_default(), user.id.clone()).encode());
+
+    let mut user_infos = Vec::with_capacity(users.len());
+    for user in users {
+        let active_sessions = app_state
+            .token_service
+            .count_user_active_sessions(app_state.clone(), user.id.clone())
+            .await?;
+        user_infos.push(AdminUserInfo {
+            id: user.id,
+            name: user.name,
+            email: user.email,
+            role: user.role.to_str().to_string(),
+            verified: user.verified,
+            created_at: user.created_at.unwrap_or_default(),
+            updated_at: user.updated_at,
+            last_login_at: user.last_login_at,
+            failed_login_attempts: user.failed_login_attempts,
+            locked_until: user.locked_until,
+            active_sessions: active_sessions as usize,
+        });
+    }
+
+    Ok(Json(
+        PaginatedResponse::new(user_infos, &pagination, total).with_next_cursor(next_cursor),
+    ))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/users/{id}",
+    tag = "admin",
+    params(("id" = String, Path, description = "User id")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "User with active sessions", body = serde_json::Value),
+        (status = 403, description = "Admin scope required", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse)
+    )
+)]
 pub async fn get_user_by_id(
     State(app_state): State<Arc<AppState>>,
     Extension(claims): Extension<TokenClaims>,
-    Json(payload): Json<serde_json::Value>,
+    tenant: TenantContext,
+    Path(user_id): Path<String>,
 ) -> Result<Json<serde_json::Value>> {
     if !claims
         .role
@@ -116,16 +381,7 @@ pub async fn get_user_by_id(
         return Err(AuthError::PermissionDenied.into());
     }
 
-    let user_id = payload
-        .get("user_id")
-        .and_then(|id| id.as_str())
-        .ok_or_else(|| AuthError::InvalidCredentials)?;
-
-    let user_service = UserService::new();
-    let user = user_service
-        .find_by_id(app_state.clone(), user_id.to_string())
-        .await?
-        .ok_or_else(|| crate::errors::db::DatabaseError::NotFound("User not found".to_string()))?;
+    let user = find_tenant_user(&app_state, &tenant, user_id).await?;
 
     let sessions = app_state
         .token_service
@@ -141,15 +397,155 @@ pub async fn get_user_by_id(
             "verified": user.verified,
             "created_at": user.created_at,
             "updated_at": user.updated_at,
+            "last_login_at": user.last_login_at,
+            "failed_login_attempts": user.failed_login_attempts,
+            "locked_until": user.locked_until,
             "active_sessions": sessions.len(),
             "sessions": sessions
         }
     })))
 }
 
+/// 按 id 查找当前租户内的用户，其他租户的用户视为不存在
+async fn find_tenant_user(
+    app_state: &Arc<AppState>,
+    tenant: &TenantContext,
+    user_id: String,
+) -> Result<User> {
+    UserService::new()
+        .find_by_id(app_state.clone(), user_id)
+        .await?
+        .filter(|user| user.tenant_id == tenant.tenant_id())
+        .ok_or_else(|| DatabaseError::NotFound("User not found".to_string()).into())
+}
+
+/// 清除失败登录计数和锁定时间，供客服协助被锁定的用户
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/users/{id}/unlock",
+    tag = "admin",
+    params(("id" = String, Path, description = "User id")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "User unlocked", body = serde_json::Value),
+        (status = 403, description = "Admin scope required", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse)
+    )
+)]
+pub async fn unlock_user(
+    State(app_state): State<Arc<AppState>>,
+    Extension(claims): Extension<TokenClaims>,
+    tenant: TenantContext,
+    Path(user_id): Path<String>,
+) -> Result<Json<serde_json::Value>> {
+    if !claims
+        .role
+        .as_ref()
+        .map(|r| matches!(r, Role::Admin))
+        .unwrap_or(false)
+    {
+        return Err(AuthError::PermissionDenied.into());
+    }
+
+    let user = find_tenant_user(&app_state, &tenant, user_id).await?;
+
+    tracing::info!(
+        admin_id = %claims.sub,
+        user_id = %user.id,
+        failed_login_attempts = user.failed_login_attempts,
+        locked_until = ?user.locked_until,
+        "Admin unlocked user account"
+    );
+
+    let user = UserService::new().unlock_user(app_state, user.id).await?;
+
+    Ok(Json(serde_json::json!({
+        "message": "User account unlocked successfully.",
+        "user_id": user.id
+    })))
+}
+
+/// 客服代用户重新发送验证邮件，已验证的用户直接返回提示
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/users/{id}/resend-verification",
+    tag = "admin",
+    params(("id" = String, Path, description = "User id")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Verification email sent", body = serde_json::Value),
+        (status = 403, description = "Admin scope required", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse)
+    )
+)]
+pub async fn admin_resend_verification(
+    State(app_state): State<Arc<AppState>>,
+    Extension(claims): Extension<TokenClaims>,
+    tenant: TenantContext,
+    Path(user_id): Path<String>,
+) -> Result<Json<serde_json::Value>> {
+    if !claims
+        .role
+        .as_ref()
+        .map(|r| matches!(r, Role::Admin))
+        .unwrap_or(false)
+    {
+        return Err(AuthError::PermissionDenied.into());
+    }
+
+    let user = find_tenant_user(&app_state, &tenant, user_id).await?;
+
+    if user.verified {
+        return Ok(Json(serde_json::json!({
+            "message": "Email is already verified.",
+            "user_id": user.id
+        })));
+    }
+
+    let verification_token = app_state
+        .token_service
+        .generate_email_verification_token(&user.id, &user.email)?;
+
+    EmailService::new()
+        .send_to_user(
+            &user,
+            EmailCategory::Transactional,
+            "Verify your email address",
+            &format!(
+                "Use this token to verify your email: {}",
+                verification_token
+            ),
+        )
+        .await?;
+
+    tracing::info!(
+        admin_id = %claims.sub,
+        user_id = %user.id,
+        "Admin resent verification email"
+    );
+
+    Ok(Json(serde_json::json!({
+        "message": "Verification email has been sent.",
+        "user_id": user.id
+    })))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/users/revoke-sessions",
+    tag = "admin",
+    request_body = serde_json::Value,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "User sessions revoked", body = serde_json::Value),
+        (status = 403, description = "Admin scope required", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse)
+    )
+)]
 pub async fn admin_revoke_user_sessions(
     State(app_state): State<Arc<AppState>>,
     Extension(claims): Extension<TokenClaims>,
+    tenant: TenantContext,
     Json(payload): Json<serde_json::Value>,
 ) -> Result<Json<serde_json::Value>> {
     if !claims
@@ -165,20 +561,34 @@ pub async fn admin_revoke_user_sessions(
         .get("user_id")
         .and_then(|id| id.as_str())
         .ok_or_else(|| AuthError::InvalidCredentials)?;
+    let user = find_tenant_user(&app_state, &tenant, user_id.to_string()).await?;
 
     app_state
         .token_service
-        .revoke_all_user_sessions(app_state.clone(), user_id.to_string())
+        .revoke_all_user_sessions(app_state.clone(), user.id.clone())
         .await?;
 
     Ok(Json(serde_json::json!({
-        "message": format!("All sessions for user {} have been revoked", user_id)
+        "message": format!("All sessions for user {} have been revoked", user.id)
     })))
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/users/role",
+    tag = "admin",
+    request_body = serde_json::Value,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Role updated", body = serde_json::Value),
+        (status = 403, description = "Admin scope required", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse)
+    )
+)]
 pub async fn update_user_role(
     State(app_state): State<Arc<AppState>>,
     Extension(claims): Extension<TokenClaims>,
+    tenant: TenantContext,
     Json(payload): Json<serde_json::Value>,
 ) -> Result<Json<serde_json::Value>> {
     if !claims
@@ -200,25 +610,35 @@ pub async fn update_user_role(
         .and_then(|r| r.as_str())
         .ok_or_else(|| AuthError::InvalidCredentials)?;
 
-    let _role = match new_role {
-        "Admin" => Role::Admin,
-        "User" => Role::User,
-        _ => return Err(AuthError::InvalidCredentials.into()),
-    };
+    let role = Role::parse(new_role)
+        .ok_or_else(|| ApiError::invalid_request(format!("Unknown role: {new_role}")))?;
 
-    // TODO: 实现更新用户角色的方法
-    // 这需要在UserRepository中添加update_role方法
+    let user = find_tenant_user(&app_state, &tenant, user_id.to_string()).await?;
+    let user = UserService::new()
+        .update_role(app_state.clone(), user.id, role)
+        .await?;
 
+    // 旧令牌中的角色和权限范围已经过时，强制重新登录
     app_state
         .token_service
-        .revoke_all_user_sessions(app_state.clone(), user_id.to_string())
+        .revoke_all_user_sessions(app_state.clone(), user.id.clone())
         .await?;
 
     Ok(Json(serde_json::json!({
-        "message": format!("User role updated to {}. User sessions have been revoked.", new_role)
+        "message": format!("User role updated to {}. User sessions have been revoked.", user.role)
     })))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/sessions/cleanup",
+    tag = "admin",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Expired sessions removed", body = serde_json::Value),
+        (status = 403, description = "Admin scope required", body = ErrorResponse)
+    )
+)]
 pub async fn cleanup_expired_sessions(
     State(app_state): State<Arc<AppState>>,
     Extension(claims): Extension<TokenClaims>,
@@ -242,3 +662,296 @@ pub async fn cleanup_expired_sessions(
         "cleaned_count": cleaned_count
     })))
 }
+
+/// 事件响应时按来源 IP 查找当前租户内所有用户的会话，包括已撤销的会话
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/sessions",
+    tag = "admin",
+    params(PaginationQuery, SessionSearchQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Paginated sessions from the given IP or subnet", body = PaginatedResponse<AdminSessionInfo>),
+        (status = 400, description = "Invalid IP address or subnet", body = ErrorResponse),
+        (status = 403, description = "Admin read scope required", body = ErrorResponse)
+    )
+)]
+pub async fn search_sessions(
+    State(app_state): State<Arc<AppState>>,
+    Extension(claims): Extension<TokenClaims>,
+    tenant: TenantContext,
+    pagination: Pagination,
+    Query(query): Query<SessionSearchQuery>,
+) -> Result<Json<PaginatedResponse<AdminSessionInfo>>> {
+    if !claims.has_scope(&TokenScope::AdminRead) {
+        return Err(AuthError::PermissionDenied.into());
+    }
+
+    let filter = SessionIpFilter::parse(&query.ip).ok_or_else(|| {
+        ApiError::invalid_request(format!("Invalid IP address or subnet: {}", query.ip))
+    })?;
+
+    tracing::info!(admin_id = %claims.sub, ?filter, "Admin session search by IP");
+
+    let (sessions, total) = app_state
+        .token_service
+        .find_sessions_by_ip(app_state.clone(), tenant.tenant_id(), filter, &pagination)
+        .await?;
+
+    let sessions = sessions.into_iter().map(AdminSessionInfo::from).collect();
+
+    Ok(Json(PaginatedResponse::new(sessions, &pagination, total)))
+}
+
+/// 单个会话的完整记录及其所属用户，用于事件调查；已撤销的会话同样可见
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/sessions/{id}",
+    tag = "admin",
+    params(("id" = String, Path, description = "Session id")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Session with its owner", body = AdminSessionDetail),
+        (status = 403, description = "Admin read scope required", body = ErrorResponse),
+        (status = 404, description = "Session not found", body = ErrorResponse)
+    )
+)]
+pub async fn get_session_by_id(
+    State(app_state): State<Arc<AppState>>,
+    Extension(claims): Extension<TokenClaims>,
+    tenant: TenantContext,
+    Path(session_id): Path<String>,
+) -> Result<Json<AdminSessionDetail>> {
+    if !claims.has_scope(&TokenScope::AdminRead) {
+        return Err(AuthError::PermissionDenied.into());
+    }
+
+    let session = app_state
+        .token_service
+        .find_session_by_id(app_state.clone(), session_id)
+        .await?
+        // 其他租户的会话视为不存在
+        .filter(|session| session.tenant_id == tenant.tenant_id())
+        .ok_or(ApiError::NotFound)?;
+
+    tracing::info!(admin_id = %claims.sub, session_id = %session.id, "Admin viewed session details");
+
+    let user = UserService::new()
+        .find_by_id(app_state, session.user_id.clone())
+        .await?
+        .map(|user| SessionOwnerInfo {
+            id: user.id,
+            name: user.name,
+            email: user.email,
+            role: user.role.to_str().to_string(),
+        });
+
+    Ok(Json(AdminSessionDetail {
+        session: session.into(),
+        user,
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/sessions/revoke",
+    tag = "admin",
+    request_body = BulkRevokeSessionsRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Matching sessions revoked", body = serde_json::Value),
+        (status = 400, description = "Invalid criteria", body = ErrorResponse),
+        (status = 403, description = "Admin scope required", body = ErrorResponse)
+    )
+)]
+pub async fn bulk_revoke_sessions(
+    State(app_state): State<Arc<AppState>>,
+    Extension(claims): Extension<TokenClaims>,
+    tenant: TenantContext,
+    Json(payload): Json<BulkRevokeSessionsRequest>,
+) -> Result<Json<serde_json::Value>> {
+    if !claims
+        .role
+        .as_ref()
+        .map(|r| matches!(r, Role::Admin))
+        .unwrap_or(false)
+    {
+        return Err(AuthError::PermissionDenied.into());
+    }
+
+    let criteria = match (payload.older_than_days, payload.ip_address, payload.role) {
+        (Some(days), None, None) if days >= 0 => {
+            // 过大的天数会让时间计算溢出，按无效请求处理
+            let cutoff_time = chrono::Duration::try_days(days)
+                .and_then(|age| Utc::now().checked_sub_signed(age))
+                .ok_or_else(|| {
+                    ApiError::invalid_request(format!("older_than_days out of range: {days}"))
+                })?;
+            SessionRevocationCriteria::CreatedBefore(cutoff_time)
+        }
+        (None, Some(ip_address), None) => SessionRevocationCriteria::IpAddress(ip_address),
+        (None, None, Some(role)) => SessionRevocationCriteria::UserRole(
+            Role::parse(&role)
+                .ok_or_else(|| ApiError::invalid_request(format!("Unknown role: {role}")))?,
+        ),
+        _ => {
+            return Err(ApiError::invalid_request(
+                "Provide exactly one of older_than_days, ip_address or role",
+            )
+            .into());
+        }
+    };
+
+    tracing::info!(admin_id = %claims.sub, ?criteria, "Admin bulk session revocation");
+
+    let revoked_count = app_state
+        .token_service
+        .revoke_sessions_matching(app_state.clone(), tenant.tenant_id(), criteria)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "message": format!("Revoked {} sessions", revoked_count),
+        "revoked_count": revoked_count
+    })))
+}
+
+/// 内置权限范围目录，供权限管理界面展示可授予的范围
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/scopes",
+    tag = "admin",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Known token scopes", body = [ScopeInfo]),
+        (status = 403, description = "admin:read scope required", body = ErrorResponse)
+    )
+)]
+pub async fn list_scopes() -> Json<Vec<ScopeInfo>> {
+    Json(
+        TokenScope::KNOWN
+            .iter()
+            .map(|scope| ScopeInfo {
+                scope: scope.to_str(),
+                description: scope.description().to_string(),
+            })
+            .collect(),
+    )
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/feature-flags",
+    tag = "admin",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Effective feature flags", body = [FeatureFlag]),
+        (status = 403, description = "Admin scope required", body = ErrorResponse)
+    )
+)]
+pub async fn list_feature_flags(
+    State(app_state): State<Arc<AppState>>,
+) -> Result<Json<Vec<FeatureFlag>>> {
+    Ok(Json(app_state.feature_flags.list()))
+}
+
+/// 开关写入数据库，其他实例在下一次缓存刷新后生效
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/feature-flags/{name}",
+    tag = "admin",
+    params(("name" = String, Path, description = "Feature flag name")),
+    request_body = UpdateFeatureFlagRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Feature flag updated", body = FeatureFlag),
+        (status = 400, description = "Invalid flag name", body = ErrorResponse),
+        (status = 403, description = "Admin scope required", body = ErrorResponse)
+    )
+)]
+pub async fn update_feature_flag(
+    State(app_state): State<Arc<AppState>>,
+    Extension(claims): Extension<TokenClaims>,
+    Path(name): Path<String>,
+    Json(payload): Json<UpdateFeatureFlagRequest>,
+) -> Result<Json<FeatureFlag>> {
+    if name.is_empty()
+        || name.len() > 64
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-')
+    {
+        return Err(ApiError::invalid_request(
+            "Feature flag names may only contain lowercase letters, digits, '_' and '-'",
+        )
+        .into());
+    }
+
+    let flag = app_state
+        .feature_flags
+        .set(app_state.clone(), name, payload.enabled)
+        .await?;
+
+    tracing::info!(
+        admin_id = %claims.sub,
+        flag = %flag.name,
+        enabled = flag.enabled,
+        "Admin updated feature flag"
+    );
+
+    Ok(Json(flag))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/maintenance",
+    tag = "admin",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Current maintenance status", body = MaintenanceStatus),
+        (status = 403, description = "Admin scope required", body = ErrorResponse)
+    )
+)]
+pub async fn get_maintenance(
+    State(app_state): State<Arc<AppState>>,
+) -> Result<Json<MaintenanceStatus>> {
+    Ok(Json(app_state.maintenance.current()))
+}
+
+/// 管理员路由和携带管理员令牌的请求不受维护模式限制，开启后仍可通过该接口关闭
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/maintenance",
+    tag = "admin",
+    request_body = UpdateMaintenanceRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Maintenance status updated", body = MaintenanceStatus),
+        (status = 403, description = "Admin scope required", body = ErrorResponse)
+    )
+)]
+pub async fn update_maintenance(
+    State(app_state): State<Arc<AppState>>,
+    Extension(claims): Extension<TokenClaims>,
+    Json(payload): Json<UpdateMaintenanceRequest>,
+) -> Result<Json<MaintenanceStatus>> {
+    let retry_after_seconds = payload
+        .retry_after_seconds
+        .unwrap_or(app_state.env.maintenance_config.retry_after_seconds);
+    let status = app_state
+        .maintenance
+        .set(
+            app_state.clone(),
+            payload.mode,
+            payload.message,
+            retry_after_seconds,
+        )
+        .await?;
+
+    tracing::warn!(
+        admin_id = %claims.sub,
+        mode = ?status.mode,
+        "Admin changed maintenance mode"
+    );
+
+    Ok(Json(status))
+}
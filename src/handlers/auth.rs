@@ -1,23 +1,66 @@
-use axum::{Extension, extract::State, http::StatusCode, response::Json};
+use axum::{
+    Extension,
+    extract::{Path, State},
+    http::{
+        HeaderMap, StatusCode,
+        header::{COOKIE, USER_AGENT},
+    },
+    response::{Json, Response},
+};
 use std::sync::Arc;
 use validator::Validate;
 
 use crate::{
+    config::password::PasswordPolicy,
     dtos::auth::{
-        ChangePasswordRequest, ForgotPasswordRequest, LoginRequest, LoginResponse, LogoutRequest,
-        LogoutResponse, RefreshTokenRequest, RefreshTokenResponse, RegisterRequest,
-        ResetPasswordRequest, UserInfo,
+        ChangeEmailRequest, ChangePasswordRequest, ConfirmEmailChangeRequest,
+        ForgotPasswordRequest, IntrospectRequest, IntrospectResponse, LoginRequest, LoginResponse,
+        LogoutRequest, LogoutResponse, ReactivateAccountRequest, RecoverAccountRequest,
+        RecoverAccountResponse, RecoveryCodesResponse, RefreshTokenRequest, RefreshTokenResponse,
+        RegenerateRecoveryCodesRequest, RegisterRequest, ResetPasswordRequest,
+        RevokeRefreshTokenRequest, TokenInfoResponse, UserInfo,
+    },
+    dtos::pagination::{PaginatedResponse, Pagination, PaginationQuery},
+    dtos::user::{SessionInfo, TrustSessionRequest},
+    errors::{auth::AuthError, core::Result, response::ErrorResponse},
+    extractors::{client_ip::ClientIp, json_or_form::JsonOrForm, tenant::TenantContext},
+    handlers::{etag::conditional_json, ownership::ensure_owner},
+    middlewares::auth::device_binding,
+    models::{
+        device::DeviceInfo, feature_flag, notification::EmailCategory, token_claims::TokenClaims,
+        token_scope::TokenScope, token_session::TokenSession, user::User,
+    },
+    services::{
+        email::EmailService,
+        token::{REFRESH_TOKEN_COOKIE, TokenService},
+        user::UserService,
     },
-    errors::{auth::AuthError, core::Result},
-    models::token_claims::TokenClaims,
-    services::user::UserService,
     state::AppState,
 };
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/register",
+    tag = "auth",
+    request_body(content(
+        (RegisterRequest = "application/json"),
+        (RegisterRequest = "application/x-www-form-urlencoded")
+    )),
+    responses(
+        (status = 201, description = "User registered", body = UserInfo),
+        (status = 404, description = "Registration is disabled", body = ErrorResponse),
+        (status = 409, description = "Email already exists", body = ErrorResponse),
+        (status = 422, description = "Validation failed", body = ErrorResponse)
+    )
+)]
 pub async fn register(
     State(app_state): State<Arc<AppState>>,
-    Json(payload): Json<RegisterRequest>,
+    tenant: TenantContext,
+    JsonOrForm(payload): JsonOrForm<RegisterRequest>,
 ) -> Result<(StatusCode, Json<UserInfo>)> {
+    app_state
+        .feature_flags
+        .ensure_enabled(feature_flag::REGISTRATION)?;
     payload.validate()?;
 
     let user_service = UserService::new();
@@ -27,6 +70,7 @@ pub async fn register(
             payload.name,
             payload.email,
             payload.password,
+            tenant.tenant_id(),
         )
         .await?;
 
@@ -42,68 +86,245 @@ pub async fn register(
     ))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/login",
+    tag = "auth",
+    request_body(content(
+        (LoginRequest = "application/json"),
+        (LoginRequest = "application/x-www-form-urlencoded")
+    )),
+    responses(
+        (status = 200, description = "Logged in", body = LoginResponse),
+        (status = 401, description = "Invalid credentials", body = ErrorResponse),
+        (status = 422, description = "Validation failed", body = ErrorResponse)
+    )
+)]
 pub async fn login(
     State(app_state): State<Arc<AppState>>,
-    Json(payload): Json<LoginRequest>,
-) -> Result<Json<LoginResponse>> {
+    tenant: TenantContext,
+    ClientIp(ip_address): ClientIp,
+    headers: HeaderMap,
+    JsonOrForm(payload): JsonOrForm<LoginRequest>,
+) -> Result<(HeaderMap, Json<LoginResponse>)> {
     payload.validate()?;
 
+    // 优先解析 User-Agent，没有时退回客户端上报的 device_info
+    let device_info = headers
+        .get(USER_AGENT)
+        .and_then(|header| header.to_str().ok())
+        .map(str::to_string)
+        .or(payload.device_info)
+        .map(|user_agent| DeviceInfo::from_user_agent(&user_agent));
+
     let user_service = UserService::new();
     let user = user_service
-        .authenticate_user(app_state.clone(), payload.email, payload.password)
+        .authenticate_user(
+            app_state.clone(),
+            payload.email,
+            payload.password,
+            tenant.tenant_id(),
+        )
         .await?;
 
+    // 必须在创建本次会话之前判断，否则新会话本身就会匹配指纹
+    let fingerprint = DeviceInfo::fingerprint(device_info.as_ref(), Some(ip_address));
+    let new_device = app_state
+        .token_service
+        .is_new_device(app_state.clone(), &user.id, &fingerprint)
+        .await
+        .unwrap_or_else(|e| {
+            e.log_error();
+            false
+        });
+
     // 使用TokenService创建会话
-    let (access_token, refresh_token, _session) = app_state
+    let (access_token, refresh_token, session) = app_state
         .token_service
         .create_session(
             app_state.clone(),
-            &user.id,
-            &user.email,
-            &user.role,
-            payload.device_info,
+            &user,
+            device_info,
+            Some(ip_address),
             None,
+            app_state
+                .env
+                .token_config
+                .device_binding
+                .then(|| device_binding(&headers)),
         )
         .await?;
+    if new_device {
+        notify_new_device_login(&user, &session).await;
+    }
+    let scopes = access_token_scopes(&app_state, &access_token).await?;
+    let cookies = token_cookies(
+        &app_state,
+        payload.use_cookies,
+        &access_token,
+        &refresh_token,
+    );
 
-    Ok(Json(LoginResponse {
-        access_token,
-        refresh_token,
-        token_type: "Bearer".to_string(),
-        expires_in: app_state.env.token_config.access_token_expires_in,
-        user: UserInfo {
-            id: user.id,
-            email: user.email,
-            name: user.name,
-            role: user.role.to_str().to_string(),
-            created_at: user.created_at.unwrap_or_default(),
-        },
-    }))
+    Ok((
+        cookies,
+        Json(LoginResponse {
+            access_token,
+            refresh_token,
+            token_type: "Bearer".to_string(),
+            expires_in: app_state.env.token_config.access_token_expires_in,
+            scopes,
+            requires_2fa: false,
+            requires_email_verification: !user.verified,
+            user: UserInfo {
+                id: user.id,
+                email: user.email,
+                name: user.name,
+                role: user.role.to_str().to_string(),
+                created_at: user.created_at.unwrap_or_default(),
+            },
+        }),
+    ))
 }
 
 /// 刷新访问令牌
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/refresh",
+    tag = "auth",
+    request_body(content(
+        (RefreshTokenRequest = "application/json"),
+        (RefreshTokenRequest = "application/x-www-form-urlencoded")
+    )),
+    responses(
+        (status = 200, description = "Tokens refreshed", body = RefreshTokenResponse),
+        (status = 401, description = "Invalid refresh token", body = ErrorResponse)
+    )
+)]
 pub async fn refresh_token(
     State(app_state): State<Arc<AppState>>,
-    Json(payload): Json<RefreshTokenRequest>,
-) -> Result<Json<RefreshTokenResponse>> {
+    headers: HeaderMap,
+    JsonOrForm(payload): JsonOrForm<RefreshTokenRequest>,
+) -> Result<(HeaderMap, Json<RefreshTokenResponse>)> {
+    // 请求体中没有时从 Cookie 读取
+    let refresh_token = payload
+        .refresh_token
+        .or_else(|| {
+            headers
+                .get(COOKIE)
+                .and_then(|header| header.to_str().ok())
+                .and_then(|cookies| {
+                    TokenService::extract_token_from_cookie(cookies, REFRESH_TOKEN_COOKIE)
+                })
+                .map(str::to_string)
+        })
+        .ok_or(AuthError::InvalidToken)?;
     let (new_access_token, new_refresh_token) = app_state
         .token_service
-        .refresh_session(app_state.clone(), &payload.refresh_token)
+        .refresh_session(app_state.clone(), &refresh_token)
         .await?;
+    let scopes = access_token_scopes(&app_state, &new_access_token).await?;
+    let cookies = token_cookies(
+        &app_state,
+        payload.use_cookies,
+        &new_access_token,
+        &new_refresh_token,
+    );
 
-    Ok(Json(RefreshTokenResponse {
-        access_token: new_access_token,
-        refresh_token: new_refresh_token,
-        token_type: "Bearer".to_string(),
-        expires_in: app_state.env.token_config.access_token_expires_in,
-    }))
+    Ok((
+        cookies,
+        Json(RefreshTokenResponse {
+            access_token: new_access_token,
+            refresh_token: new_refresh_token,
+            token_type: "Bearer".to_string(),
+            expires_in: app_state.env.token_config.access_token_expires_in,
+            scopes,
+        }),
+    ))
+}
+
+// 配置开启或请求要求时返回令牌 Cookie，否则返回空的头部集合
+fn token_cookies(
+    app_state: &AppState,
+    requested: bool,
+    access_token: &str,
+    refresh_token: &str,
+) -> HeaderMap {
+    if requested || app_state.env.token_config.cookie_delivery {
+        app_state
+            .token_service
+            .token_cookies(access_token, refresh_token)
+    } else {
+        HeaderMap::new()
+    }
+}
+
+// 新设备登录提醒属于关键安全邮件，不受通知偏好影响；发送失败只记录日志，不影响登录
+async fn notify_new_device_login(user: &User, session: &TokenSession) {
+    let device = session
+        .device_info
+        .as_ref()
+        .map(|info| info.display_name())
+        .unwrap_or_else(|| "Unknown device".to_string());
+    let body = format!(
+        "A new sign-in to your account was detected.\nDevice: {}\nIP address: {}\nLocation: {}\nTime: {}\n\nIf this wasn't you, change your password and revoke the session.",
+        device,
+        session.ip_address.as_deref().unwrap_or("unknown"),
+        session.location.as_deref().unwrap_or("unknown"),
+        session.created_at.to_rfc3339(),
+    );
+    send_best_effort(
+        user,
+        EmailCategory::CriticalSecurity,
+        "New sign-in to your account",
+        &body,
+    )
+    .await;
+}
+
+// 按类别和通知偏好发送，失败只记录日志，不影响请求结果
+async fn send_best_effort(user: &User, category: EmailCategory, subject: &str, body: &str) {
+    if let Err(e) = EmailService::new()
+        .send_to_user(user, category, subject, body)
+        .await
+    {
+        e.log_error();
+    }
 }
 
+// 从刚签发的访问令牌中读取权限范围，保证与令牌内容一致
+async fn access_token_scopes(
+    app_state: &Arc<AppState>,
+    access_token: &str,
+) -> Result<Vec<String>> {
+    Ok(app_state
+        .token_service
+        .resolve_access_token(app_state.clone(), access_token)
+        .await?
+        .scopes
+        .iter()
+        .map(|scope| scope.to_str())
+        .collect())
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/logout",
+    tag = "auth",
+    request_body(content(
+        (LogoutRequest = "application/json"),
+        (LogoutRequest = "application/x-www-form-urlencoded")
+    )),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Logged out", body = LogoutResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse)
+    )
+)]
 pub async fn logout(
     State(app_state): State<Arc<AppState>>,
     Extension(claims): Extension<TokenClaims>,
-    Json(payload): Json<LogoutRequest>,
-) -> Result<Json<LogoutResponse>> {
+    JsonOrForm(payload): JsonOrForm<LogoutRequest>,
+) -> Result<(HeaderMap, Json<LogoutResponse>)> {
     // 如果提供了refresh_token，通过它找到session并撤销
     if let Some(refresh_token) = payload.refresh_token {
         let refresh_claims = app_state
@@ -140,20 +361,71 @@ pub async fn logout(
         }
     }
 
-    Ok(Json(LogoutResponse {
-        message: "Successfully logged out".to_string(),
-    }))
+    // 无论是否使用 Cookie 登录都清除，避免残留的令牌 Cookie
+    Ok((
+        app_state.token_service.clear_token_cookies(),
+        Json(LogoutResponse {
+            message: "Successfully logged out".to_string(),
+        }),
+    ))
+}
+
+/// 只撤销某个刷新令牌（例如已泄露），不结束会话
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/revoke-refresh",
+    tag = "auth",
+    request_body(content(
+        (RevokeRefreshTokenRequest = "application/json"),
+        (RevokeRefreshTokenRequest = "application/x-www-form-urlencoded")
+    )),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Refresh token revoked, the session stays active", body = serde_json::Value),
+        (status = 401, description = "Invalid or already revoked refresh token", body = ErrorResponse),
+        (status = 403, description = "Refresh token belongs to another user", body = ErrorResponse)
+    )
+)]
+pub async fn revoke_refresh_token(
+    State(app_state): State<Arc<AppState>>,
+    Extension(claims): Extension<TokenClaims>,
+    JsonOrForm(payload): JsonOrForm<RevokeRefreshTokenRequest>,
+) -> Result<Json<serde_json::Value>> {
+    payload.validate()?;
+    app_state
+        .token_service
+        .revoke_refresh_token(app_state.clone(), &claims.sub, &payload.refresh_token)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "message": "Refresh token revoked successfully."
+    })))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/change-password",
+    tag = "auth",
+    request_body(content(
+        (ChangePasswordRequest = "application/json"),
+        (ChangePasswordRequest = "application/x-www-form-urlencoded")
+    )),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Password changed", body = serde_json::Value),
+        (status = 401, description = "Invalid credentials", body = ErrorResponse),
+        (status = 422, description = "Validation failed", body = ErrorResponse)
+    )
+)]
 pub async fn change_password(
     State(app_state): State<Arc<AppState>>,
     Extension(claims): Extension<TokenClaims>,
-    Json(payload): Json<ChangePasswordRequest>,
+    JsonOrForm(payload): JsonOrForm<ChangePasswordRequest>,
 ) -> Result<(StatusCode, Json<serde_json::Value>)> {
     payload.validate()?;
 
     let user_service = UserService::new();
-    let _updated_user = user_service
+    let updated_user = user_service
         .change_password(
             app_state.clone(),
             claims.sub.clone(),
@@ -167,6 +439,13 @@ pub async fn change_password(
         .token_service
         .revoke_all_user_sessions(app_state.clone(), claims.sub)
         .await?;
+    send_best_effort(
+        &updated_user,
+        EmailCategory::Security,
+        "Your password was changed",
+        "The password of your account was just changed and all sessions were signed out.\n\nIf this wasn't you, reset your password immediately.",
+    )
+    .await;
 
     Ok((
         StatusCode::OK,
@@ -176,9 +455,23 @@ pub async fn change_password(
     ))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/forgot-password",
+    tag = "auth",
+    request_body(content(
+        (ForgotPasswordRequest = "application/json"),
+        (ForgotPasswordRequest = "application/x-www-form-urlencoded")
+    )),
+    responses(
+        (status = 200, description = "Reset link sent if the email exists", body = serde_json::Value),
+        (status = 422, description = "Validation failed", body = ErrorResponse)
+    )
+)]
 pub async fn forgot_password(
     State(app_state): State<Arc<AppState>>,
-    Json(payload): Json<ForgotPasswordRequest>,
+    tenant: TenantContext,
+    JsonOrForm(payload): JsonOrForm<ForgotPasswordRequest>,
 ) -> Result<Json<serde_json::Value>> {
     payload.validate()?;
 
@@ -186,7 +479,7 @@ pub async fn forgot_password(
 
     // 检查用户是否存在
     if let Some(user) = user_service
-        .find_by_email(app_state.clone(), payload.email.clone())
+        .find_by_email(app_state.clone(), payload.email.clone(), tenant.tenant_id())
         .await?
     {
         // 生成密码重置令牌
@@ -194,14 +487,14 @@ pub async fn forgot_password(
             .token_service
             .generate_password_reset_token(&user.id, &user.email)?;
 
-        // TODO: 在实际应用中，这里应该发送邮件
-        // email_service.send_password_reset_email(&user.email, &reset_token).await?;
-
-        tracing::info!(
-            "Password reset token generated for user {}: {}",
-            user.email,
-            reset_token
-        );
+        // 发送失败只记录日志，返回值必须与邮箱不存在时一致
+        send_best_effort(
+            &user,
+            EmailCategory::Transactional,
+            "Reset your password",
+            &format!("Use this token to reset your password: {}", reset_token),
+        )
+        .await;
     }
 
     // 无论用户是否存在，都返回相同的消息（防止用户枚举攻击）
@@ -210,9 +503,121 @@ pub async fn forgot_password(
     })))
 }
 
+/// 申请修改邮箱，验证链接发送到新邮箱
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/change-email",
+    tag = "auth",
+    request_body(content(
+        (ChangeEmailRequest = "application/json"),
+        (ChangeEmailRequest = "application/x-www-form-urlencoded")
+    )),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Confirmation link sent to the new email", body = serde_json::Value),
+        (status = 409, description = "Email already exists", body = ErrorResponse),
+        (status = 422, description = "Validation failed", body = ErrorResponse)
+    )
+)]
+pub async fn change_email(
+    State(app_state): State<Arc<AppState>>,
+    Extension(claims): Extension<TokenClaims>,
+    JsonOrForm(payload): JsonOrForm<ChangeEmailRequest>,
+) -> Result<Json<serde_json::Value>> {
+    payload.validate()?;
+
+    let user_service = UserService::new();
+    let user = user_service
+        .request_email_change(app_state.clone(), claims.sub, payload.new_email)
+        .await?;
+    let pending_email = user.pending_email.unwrap_or_default();
+    send_email_change_confirmation(&app_state, &user.id, &pending_email).await?;
+
+    Ok(Json(serde_json::json!({
+        "message": "A confirmation link has been sent to the new email address."
+    })))
+}
+
+/// 确认邮件发往新邮箱，不经过通知偏好
+pub(crate) async fn send_email_change_confirmation(
+    app_state: &AppState,
+    user_id: &str,
+    new_email: &str,
+) -> Result<()> {
+    let verification_token = app_state
+        .token_service
+        .generate_email_verification_token(user_id, new_email)?;
+
+    EmailService::new()
+        .send(
+            new_email,
+            "Confirm your new email address",
+            &format!(
+                "Use this token to confirm your new email: {}",
+                verification_token
+            ),
+        )
+        .await
+}
+
+/// 确认修改邮箱，确认后新邮箱替换旧邮箱并视为已验证
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/confirm-email-change",
+    tag = "auth",
+    request_body(content(
+        (ConfirmEmailChangeRequest = "application/json"),
+        (ConfirmEmailChangeRequest = "application/x-www-form-urlencoded")
+    )),
+    responses(
+        (status = 200, description = "Email changed", body = serde_json::Value),
+        (status = 401, description = "Invalid or expired token", body = ErrorResponse),
+        (status = 409, description = "Email already exists", body = ErrorResponse)
+    )
+)]
+pub async fn confirm_email_change(
+    State(app_state): State<Arc<AppState>>,
+    JsonOrForm(payload): JsonOrForm<ConfirmEmailChangeRequest>,
+) -> Result<Json<serde_json::Value>> {
+    payload.validate()?;
+
+    let claims = app_state
+        .token_service
+        .verify_email_verification_token(&payload.token)?;
+
+    if claims.is_expired(app_state.env.token_config.clock_skew_leeway) {
+        return Err(AuthError::TokenExpired.into());
+    }
+
+    let email = claims.email.ok_or(AuthError::InvalidToken)?;
+    let user_service = UserService::new();
+    let user = user_service
+        .confirm_email_change(app_state.clone(), claims.sub, email)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "message": "Email changed successfully.",
+        "email": user.email
+    })))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/reset-password",
+    tag = "auth",
+    request_body(content(
+        (ResetPasswordRequest = "application/json"),
+        (ResetPasswordRequest = "application/x-www-form-urlencoded")
+    )),
+    responses(
+        (status = 200, description = "Password reset", body = serde_json::Value),
+        (status = 401, description = "Invalid or expired token", body = ErrorResponse),
+        (status = 422, description = "Validation failed", body = ErrorResponse)
+    )
+)]
 pub async fn reset_password(
     State(app_state): State<Arc<AppState>>,
-    Json(payload): Json<ResetPasswordRequest>,
+    JsonOrForm(payload): JsonOrForm<ResetPasswordRequest>,
 ) -> Result<Json<serde_json::Value>> {
     payload.validate()?;
 
@@ -220,7 +625,7 @@ pub async fn reset_password(
         .token_service
         .verify_password_reset_token(&payload.token)?;
 
-    if claims.is_expired() {
+    if claims.is_expired(app_state.env.token_config.clock_skew_leeway) {
         return Err(AuthError::TokenExpired.into());
     }
 
@@ -239,9 +644,135 @@ pub async fn reset_password(
     })))
 }
 
+/// 重新生成账户恢复码，需要再次输入当前密码
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/recovery-codes",
+    tag = "auth",
+    request_body(content(
+        (RegenerateRecoveryCodesRequest = "application/json"),
+        (RegenerateRecoveryCodesRequest = "application/x-www-form-urlencoded")
+    )),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "New recovery codes, previous codes are invalidated", body = RecoveryCodesResponse),
+        (status = 401, description = "Invalid credentials", body = ErrorResponse),
+        (status = 422, description = "Validation failed", body = ErrorResponse)
+    )
+)]
+pub async fn regenerate_recovery_codes(
+    State(app_state): State<Arc<AppState>>,
+    Extension(claims): Extension<TokenClaims>,
+    JsonOrForm(payload): JsonOrForm<RegenerateRecoveryCodesRequest>,
+) -> Result<Json<RecoveryCodesResponse>> {
+    payload.validate()?;
+
+    let user_service = UserService::new();
+    let recovery_codes = user_service
+        .regenerate_recovery_codes(app_state, claims.sub, payload.password)
+        .await?;
+
+    Ok(Json(RecoveryCodesResponse { recovery_codes }))
+}
+
+/// 无法使用邮箱时用恢复码找回账户，成功后返回密码重置令牌，强制用户设置新密码
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/recover",
+    tag = "auth",
+    request_body(content(
+        (RecoverAccountRequest = "application/json"),
+        (RecoverAccountRequest = "application/x-www-form-urlencoded")
+    )),
+    responses(
+        (status = 200, description = "Recovery code accepted", body = RecoverAccountResponse),
+        (status = 401, description = "Invalid email or recovery code", body = ErrorResponse),
+        (status = 403, description = "Account pending deletion", body = ErrorResponse),
+        (status = 423, description = "Account locked", body = ErrorResponse),
+        (status = 422, description = "Validation failed", body = ErrorResponse)
+    )
+)]
+pub async fn recover_account(
+    State(app_state): State<Arc<AppState>>,
+    tenant: TenantContext,
+    JsonOrForm(payload): JsonOrForm<RecoverAccountRequest>,
+) -> Result<Json<RecoverAccountResponse>> {
+    payload.validate()?;
+
+    let user_service = UserService::new();
+    let (user, remaining_recovery_codes) = user_service
+        .recover_account(
+            app_state.clone(),
+            payload.email,
+            payload.recovery_code,
+            tenant.tenant_id(),
+        )
+        .await?;
+
+    let reset_token = app_state
+        .token_service
+        .generate_password_reset_token(&user.id, &user.email)?;
+
+    Ok(Json(RecoverAccountResponse {
+        reset_token,
+        remaining_recovery_codes,
+    }))
+}
+
+/// 在删除宽限期内撤销删除申请，之后需要重新登录
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/reactivate",
+    tag = "auth",
+    request_body(content(
+        (ReactivateAccountRequest = "application/json"),
+        (ReactivateAccountRequest = "application/x-www-form-urlencoded")
+    )),
+    responses(
+        (status = 200, description = "Account reactivated", body = serde_json::Value),
+        (status = 401, description = "Invalid credentials or no pending deletion", body = ErrorResponse),
+        (status = 423, description = "Account locked", body = ErrorResponse),
+        (status = 422, description = "Validation failed", body = ErrorResponse)
+    )
+)]
+pub async fn reactivate_account(
+    State(app_state): State<Arc<AppState>>,
+    tenant: TenantContext,
+    JsonOrForm(payload): JsonOrForm<ReactivateAccountRequest>,
+) -> Result<Json<serde_json::Value>> {
+    payload.validate()?;
+
+    let user_service = UserService::new();
+    user_service
+        .reactivate_account(
+            app_state,
+            payload.email,
+            payload.password,
+            tenant.tenant_id(),
+        )
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "message": "Account reactivated. Please log in again."
+    })))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/verify-email",
+    tag = "auth",
+    request_body(content(
+        (serde_json::Value = "application/json"),
+        (serde_json::Value = "application/x-www-form-urlencoded")
+    )),
+    responses(
+        (status = 200, description = "Email verified", body = serde_json::Value),
+        (status = 401, description = "Invalid or expired token", body = ErrorResponse)
+    )
+)]
 pub async fn verify_email(
     State(app_state): State<Arc<AppState>>,
-    Json(payload): Json<serde_json::Value>,
+    JsonOrForm(payload): JsonOrForm<serde_json::Value>,
 ) -> Result<Json<serde_json::Value>> {
     let token = payload
         .get("token")
@@ -252,13 +783,13 @@ pub async fn verify_email(
         .token_service
         .verify_email_verification_token(token)?;
 
-    if claims.is_expired() {
+    if claims.is_expired(app_state.env.token_config.clock_skew_leeway) {
         return Err(AuthError::TokenExpired.into());
     }
 
     let user_service = UserService::new();
     let _updated_user = user_service
-        .verify_email(app_state.clone(), claims.sub)
+        .verify_email(app_state.clone(), claims.sub, claims.email)
         .await?;
 
     Ok(Json(serde_json::json!({
@@ -266,42 +797,73 @@ pub async fn verify_email(
     })))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/sessions",
+    tag = "sessions",
+    params(PaginationQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Active sessions of the current user, most recently active first", body = PaginatedResponse<SessionInfo>),
+        (status = 304, description = "Sessions unchanged since the given ETag"),
+        (status = 401, description = "Not authenticated", body = ErrorResponse)
+    )
+)]
 pub async fn get_user_sessions(
     State(app_state): State<Arc<AppState>>,
     Extension(claims): Extension<TokenClaims>,
-) -> Result<Json<serde_json::Value>> {
-    let sessions = app_state
+    pagination: Pagination,
+    headers: HeaderMap,
+) -> Result<Response> {
+    let (sessions, total) = app_state
         .token_service
-        .get_user_active_sessions(app_state.clone(), claims.sub.clone())
+        .get_user_active_sessions_page(app_state.clone(), claims.sub.clone(), &pagination)
         .await?;
 
     let current_jti = claims.jti.as_ref();
 
-    let session_info: Vec<serde_json::Value> = sessions
+    let session_info: Vec<SessionInfo> = sessions
         .into_iter()
         .map(|session| {
             let is_current = current_jti
                 .map(|jti| jti == &session.access_token_jti)
                 .unwrap_or(false);
+            let trusted = session.is_trusted();
 
-            serde_json::json!({
-                "id": session.id,
-                "device_info": session.device_info,
-                "ip_address": session.ip_address,
-                "location": session.location,
-                "created_at": session.created_at,
-                "last_active_at": session.last_active_at,
-                "is_current": is_current
-            })
+            SessionInfo {
+                id: session.id,
+                device: session
+                    .device_info
+                    .as_ref()
+                    .map(|device| device.display_name()),
+                device_info: session.device_info,
+                ip_address: session.ip_address,
+                location: session.location,
+                created_at: session.created_at,
+                last_active_at: session.last_active_at,
+                is_current,
+                trusted,
+                trusted_until: session.trusted_until,
+            }
         })
         .collect();
 
-    Ok(Json(serde_json::json!({
-        "sessions": session_info,
-        "total": session_info.len()
-    })))
+    Ok(conditional_json(
+        &headers,
+        &PaginatedResponse::new(session_info, &pagination, total),
+    ))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/sessions/revoke-all",
+    tag = "sessions",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "All sessions revoked", body = serde_json::Value),
+        (status = 401, description = "Not authenticated", body = ErrorResponse)
+    )
+)]
 pub async fn revoke_all_sessions(
     State(app_state): State<Arc<AppState>>,
     Extension(claims): Extension<TokenClaims>,
@@ -316,6 +878,18 @@ pub async fn revoke_all_sessions(
     })))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/sessions/revoke",
+    tag = "sessions",
+    request_body = serde_json::Value,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Session revoked", body = serde_json::Value),
+        (status = 403, description = "Session belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Session not found", body = ErrorResponse)
+    )
+)]
 pub async fn revoke_session(
     State(app_state): State<Arc<AppState>>,
     Extension(claims): Extension<TokenClaims>,
@@ -333,9 +907,7 @@ pub async fn revoke_session(
         .find_by_id(app_state.clone(), session_id.to_string())
         .await?
     {
-        if session.user_id != claims.sub {
-            return Err(AuthError::PermissionDenied.into());
-        }
+        ensure_owner(&session.user_id, &claims)?;
 
         app_state
             .token_service
@@ -350,6 +922,191 @@ pub async fn revoke_session(
     }
 }
 
+#[utoipa::path(
+    patch,
+    path = "/api/v1/sessions/{id}/trust",
+    tag = "sessions",
+    params(("id" = String, Path, description = "Session id")),
+    request_body = TrustSessionRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Session trust updated", body = serde_json::Value),
+        (status = 403, description = "Session belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Session not found", body = ErrorResponse)
+    )
+)]
+pub async fn trust_session(
+    State(app_state): State<Arc<AppState>>,
+    Extension(claims): Extension<TokenClaims>,
+    Path(session_id): Path<String>,
+    Json(payload): Json<TrustSessionRequest>,
+) -> Result<Json<serde_json::Value>> {
+    let session = app_state
+        .token_service
+        .set_session_trust(app_state.clone(), &claims.sub, session_id, payload.trusted)
+        .await?;
+    if let Some(user) = UserService::new()
+        .find_by_id(app_state.clone(), claims.sub.clone())
+        .await?
+    {
+        let action = if payload.trusted {
+            "marked as trusted"
+        } else {
+            "no longer trusted"
+        };
+        let device = session
+            .device_info
+            .as_ref()
+            .map(|info| info.display_name())
+            .unwrap_or_else(|| "Unknown device".to_string());
+        send_best_effort(
+            &user,
+            EmailCategory::AccountActivity,
+            "Session trust updated",
+            &format!("The session on {device} is {action}."),
+        )
+        .await;
+    }
+
+    Ok(Json(serde_json::json!({
+        "id": session.id,
+        "trusted": session.is_trusted(),
+        "trusted_until": session.trusted_until
+    })))
+}
+
+/// 主动轮换当前会话的令牌，无需重新登录；旧的刷新令牌立即失效，JWT 格式的旧访问令牌在过期前仍然有效
+#[utoipa::path(
+    post,
+    path = "/api/v1/sessions/current/rotate",
+    tag = "sessions",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Tokens of the current session rotated; the old refresh token stops working at once, an old JWT access token stays valid until it expires", body = RefreshTokenResponse),
+        (status = 401, description = "Current session is not active", body = ErrorResponse),
+        (status = 403, description = "Session belongs to another user", body = ErrorResponse)
+    )
+)]
+pub async fn rotate_current_session(
+    State(app_state): State<Arc<AppState>>,
+    Extension(claims): Extension<TokenClaims>,
+) -> Result<(HeaderMap, Json<RefreshTokenResponse>)> {
+    let (new_access_token, new_refresh_token) = app_state
+        .token_service
+        .rotate_current_session(app_state.clone(), &claims)
+        .await?;
+    tracing::info!(user_id = %claims.sub, "Rotated tokens of the current session");
+
+    let scopes = access_token_scopes(&app_state, &new_access_token).await?;
+    let cookies = token_cookies(&app_state, false, &new_access_token, &new_refresh_token);
+
+    Ok((
+        cookies,
+        Json(RefreshTokenResponse {
+            access_token: new_access_token,
+            refresh_token: new_refresh_token,
+            token_type: "Bearer".to_string(),
+            expires_in: app_state.env.token_config.access_token_expires_in,
+            scopes,
+        }),
+    ))
+}
+
+/// 当前生效的密码规则，供前端展示要求并在提交前做一致的校验
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/password-policy",
+    tag = "auth",
+    responses((status = 200, description = "Configured password policy", body = PasswordPolicy))
+)]
+pub async fn password_policy(State(app_state): State<Arc<AppState>>) -> Json<PasswordPolicy> {
+    Json(app_state.env.password_policy.clone())
+}
+
+/// 当前访问令牌的过期时间和权限范围，不访问数据库，供客户端提前安排刷新
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/token-info",
+    tag = "auth",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Current access token info", body = TokenInfoResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse)
+    )
+)]
+pub async fn token_info(Extension(claims): Extension<TokenClaims>) -> Json<TokenInfoResponse> {
+    let now = chrono::Utc::now().timestamp();
+    Json(TokenInfoResponse {
+        expires_at: chrono::DateTime::from_timestamp(claims.exp, 0).unwrap_or_default(),
+        expires_in_seconds: (claims.exp - now).max(0),
+        scopes: claims.scopes.iter().map(|scope| scope.to_str()).collect(),
+        token_type: claims.token_type.to_str().to_string(),
+    })
+}
+
+/// RFC 7662 风格的令牌内省，仅限拥有 admin:read 权限的调用方
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/introspect",
+    tag = "auth",
+    request_body(content(
+        (IntrospectRequest = "application/x-www-form-urlencoded"),
+        (IntrospectRequest = "application/json")
+    )),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Token introspection result", body = IntrospectResponse),
+        (status = 403, description = "Admin scope required", body = ErrorResponse)
+    )
+)]
+pub async fn introspect(
+    State(app_state): State<Arc<AppState>>,
+    Extension(claims): Extension<TokenClaims>,
+    JsonOrForm(payload): JsonOrForm<IntrospectRequest>,
+) -> Result<Json<IntrospectResponse>> {
+    if !claims.has_scope(&TokenScope::AdminRead) {
+        return Err(AuthError::PermissionDenied.into());
+    }
+
+    let prefer_refresh = payload.token_type_hint.as_deref() == Some("refresh_token");
+    let Some(token_claims) = app_state
+        .token_service
+        .introspect(app_state.clone(), &payload.token, prefer_refresh)
+        .await
+    else {
+        return Ok(Json(IntrospectResponse::default()));
+    };
+
+    let scope = token_claims
+        .scopes
+        .iter()
+        .map(|scope| scope.to_str())
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    Ok(Json(IntrospectResponse {
+        active: true,
+        sub: Some(token_claims.sub),
+        scope: Some(scope),
+        exp: Some(token_claims.exp),
+        iat: Some(token_claims.iat),
+        token_type: Some(token_claims.token_type.to_str().to_string()),
+        jti: token_claims.jti,
+        iss: token_claims.iss,
+        aud: token_claims.aud,
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/resend-verification",
+    tag = "auth",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Verification email sent", body = serde_json::Value),
+        (status = 401, description = "Not authenticated", body = ErrorResponse)
+    )
+)]
 pub async fn resend_verification_email(
     State(app_state): State<Arc<AppState>>,
     Extension(claims): Extension<TokenClaims>,
@@ -370,14 +1127,17 @@ pub async fn resend_verification_email(
         .token_service
         .generate_email_verification_token(&user.id, &user.email)?;
 
-    // TODO: 在实际应用中，这里应该发送邮件
-    // email_service.send_verification_email(&user.email, &verification_token).await?;
-
-    tracing::info!(
-        "Email verification token generated for user {}: {}",
-        user.email,
-        verification_token
-    );
+    EmailService::new()
+        .send_to_user(
+            &user,
+            EmailCategory::Transactional,
+            "Verify your email address",
+            &format!(
+                "Use this token to verify your email: {}",
+                verification_token
+            ),
+        )
+        .await?;
 
     Ok(Json(serde_json::json!({
         "message": "Verification email has been sent."
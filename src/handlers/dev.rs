@@ -0,0 +1,86 @@
+//! 仅在 debug 构建中编译的开发辅助接口，release 构建中这些路由不存在
+
+use axum::{
+    Extension, Json,
+    extract::{Query, State},
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::{IntoParams, OpenApi, ToSchema};
+
+use crate::{
+    errors::{api::ApiError, auth::AuthError, core::Result, response::ErrorResponse},
+    extractors::tenant::TenantContext,
+    models::{role::Role, token_claims::TokenClaims},
+    services::user::UserService,
+    state::AppState,
+};
+
+const MAX_SEED_USERS: u32 = 1000;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SeedUsersQuery {
+    /// 生成的用户数量，1 到 1000，默认 10
+    #[serde(default = "default_seed_count")]
+    pub count: u32,
+}
+
+fn default_seed_count() -> u32 {
+    10
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SeedUsersResponse {
+    pub ids: Vec<String>,
+    /// 本批用户共用的明文密码，便于直接登录测试
+    pub password: String,
+}
+
+/// 批量创建随机测试用户（仅 debug 构建，仅管理员）
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/dev/seed-users",
+    tag = "admin",
+    params(SeedUsersQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Users created", body = SeedUsersResponse),
+        (status = 400, description = "Invalid count", body = ErrorResponse),
+        (status = 403, description = "Admin scope required", body = ErrorResponse)
+    )
+)]
+pub async fn seed_users(
+    State(app_state): State<Arc<AppState>>,
+    Extension(claims): Extension<TokenClaims>,
+    tenant: TenantContext,
+    Query(query): Query<SeedUsersQuery>,
+) -> Result<Json<SeedUsersResponse>> {
+    if !claims
+        .role
+        .as_ref()
+        .map(|r| matches!(r, Role::Admin))
+        .unwrap_or(false)
+    {
+        return Err(AuthError::PermissionDenied.into());
+    }
+    if query.count == 0 || query.count > MAX_SEED_USERS {
+        return Err(ApiError::invalid_request(format!(
+            "count must be between 1 and {MAX_SEED_USERS}"
+        ))
+        .into());
+    }
+
+    let user_service = UserService::new();
+    let (ids, password) = user_service
+        .seed_users(app_state, query.count, tenant.tenant_id())
+        .await?;
+
+    tracing::info!(admin_id = %claims.sub, count = ids.len(), "Seeded test users");
+
+    Ok(Json(SeedUsersResponse { ids, password }))
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(seed_users), components(schemas(SeedUsersResponse)))]
+pub struct DevApiDoc;
@@ -0,0 +1,11 @@
+use crate::errors::{api::ApiError, core::Error};
+
+/// 未匹配任何路由的请求，返回与其他接口一致的 JSON 错误体
+pub async fn not_found() -> Error {
+    ApiError::NotFound.into()
+}
+
+/// 路径存在但方法不支持；Allow 头由 axum 根据已注册的方法自动补上
+pub async fn method_not_allowed() -> Error {
+    ApiError::MethodNotAllowed.into()
+}
@@ -0,0 +1,114 @@
+use axum::{extract::State, http::StatusCode, response::Json};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::{Arc, atomic::Ordering};
+use utoipa::ToSchema;
+
+use crate::state::AppState;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReadinessResponse {
+    pub status: String,
+    pub checks: ReadinessChecks,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReadinessChecks {
+    pub database: DatabaseCheck,
+    pub token_cleanup: TokenCleanupCheck,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DatabaseCheck {
+    pub healthy: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TokenCleanupCheck {
+    pub healthy: bool,
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub max_interval_seconds: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BuildInfo {
+    pub version: String,
+    pub git_sha: String,
+    pub started_at: DateTime<Utc>,
+    pub uptime_seconds: i64,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/health",
+    tag = "health",
+    responses((status = 200, description = "The process is alive", body = serde_json::Value))
+)]
+pub async fn health() -> Json<serde_json::Value> {
+    Json(serde_json::json!({ "status": "ok" }))
+}
+
+/// 版本和运行时长，便于把问题反馈对应到具体部署；不包含任何配置信息
+#[utoipa::path(
+    get,
+    path = "/api/v1/info",
+    tag = "health",
+    responses((status = 200, description = "Build and uptime information", body = BuildInfo))
+)]
+pub async fn info(State(app_state): State<Arc<AppState>>) -> Json<BuildInfo> {
+    Json(BuildInfo {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        git_sha: env!("GIT_SHA").to_string(),
+        started_at: app_state.started_at,
+        uptime_seconds: (Utc::now() - app_state.started_at).num_seconds(),
+    })
+}
+
+/// 数据库不可用，或后台清理任务超过两个周期未成功运行时，返回 503
+#[utoipa::path(
+    get,
+    path = "/api/v1/ready",
+    tag = "health",
+    responses(
+        (status = 200, description = "Ready to serve traffic", body = ReadinessResponse),
+        (status = 503, description = "Degraded", body = ReadinessResponse)
+    )
+)]
+pub async fn ready(
+    State(app_state): State<Arc<AppState>>,
+) -> (StatusCode, Json<ReadinessResponse>) {
+    let database = DatabaseCheck {
+        healthy: app_state.db().health().await.is_ok(),
+    };
+
+    let max_interval_seconds = 2 * app_state.env.token_config.token_cleanup_interval;
+    let last_run = app_state.cleanup_last_run.load(Ordering::Relaxed);
+    let last_run_at = (last_run > 0)
+        .then(|| DateTime::from_timestamp(last_run, 0))
+        .flatten();
+    let token_cleanup = TokenCleanupCheck {
+        healthy: last_run_at
+            .map(|at| (Utc::now() - at).num_seconds() <= max_interval_seconds)
+            .unwrap_or(false),
+        last_run_at,
+        max_interval_seconds,
+    };
+
+    let is_ready = database.healthy && token_cleanup.healthy;
+    let status = if is_ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (
+        status,
+        Json(ReadinessResponse {
+            status: if is_ready { "ready" } else { "degraded" }.to_string(),
+            checks: ReadinessChecks {
+                database,
+                token_cleanup,
+            },
+        }),
+    )
+}
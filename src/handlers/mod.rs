@@ -1,2 +1,11 @@
 pub mod admin;
 pub mod auth;
+#[cfg(debug_assertions)]
+pub mod dev;
+pub mod etag;
+pub mod fallback;
+pub mod health;
+pub mod note;
+pub mod ownership;
+pub mod user;
+pub mod ws;
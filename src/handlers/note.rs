@@ -0,0 +1,241 @@
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use std::sync::Arc;
+use validator::Validate;
+
+use crate::{
+    dtos::{
+        note::{CreateNoteRequest, NoteResponse, UpdateNoteRequest},
+        pagination::{PaginatedResponse, Pagination, PaginationQuery},
+    },
+    errors::{core::Result, response::ErrorResponse},
+    extractors::json_or_form::JsonOrForm,
+    handlers::ownership::{ensure_owner, ensure_owner_or_admin},
+    models::{note::Note, role::Role, token_claims::TokenClaims},
+    services::note::NoteService,
+    state::AppState,
+};
+
+/// 笔记列表，需要 read 权限；管理员可以看到同一租户内所有用户的笔记
+#[utoipa::path(
+    get,
+    path = "/api/v1/notes",
+    tag = "notes",
+    params(PaginationQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Paginated notes", body = PaginatedResponse<NoteResponse>),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Read scope required")
+    )
+)]
+pub async fn list_notes(
+    State(app_state): State<Arc<AppState>>,
+    Extension(claims): Extension<TokenClaims>,
+    pagination: Pagination,
+) -> Result<Json<PaginatedResponse<NoteResponse>>> {
+    let is_admin = claims
+        .role
+        .as_ref()
+        .map(|r| matches!(r, Role::Admin))
+        .unwrap_or(false);
+
+    let note_service = NoteService::new();
+    let (notes, total) = note_service
+        .list_notes(
+            app_state,
+            claims.sub.clone(),
+            claims.tenant_id(),
+            is_admin,
+            &pagination,
+        )
+        .await?;
+    let notes = notes.into_iter().map(NoteResponse::from).collect();
+    Ok(Json(PaginatedResponse::new(notes, &pagination, total)))
+}
+
+/// 创建笔记，需要 write 权限
+#[utoipa::path(
+    post,
+    path = "/api/v1/notes",
+    tag = "notes",
+    request_body(content(
+        (CreateNoteRequest = "application/json"),
+        (CreateNoteRequest = "application/x-www-form-urlencoded")
+    )),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 201, description = "Note created", body = NoteResponse),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Write scope required"),
+        (status = 422, description = "Validation failed", body = ErrorResponse)
+    )
+)]
+pub async fn create_note(
+    State(app_state): State<Arc<AppState>>,
+    Extension(claims): Extension<TokenClaims>,
+    JsonOrForm(payload): JsonOrForm<CreateNoteRequest>,
+) -> Result<(StatusCode, Json<NoteResponse>)> {
+    payload.validate()?;
+
+    let note_service = NoteService::new();
+    let note = note_service
+        .create_note(
+            app_state,
+            claims.sub.clone(),
+            claims.tenant_id(),
+            payload.title,
+            payload.content,
+        )
+        .await?;
+    Ok((StatusCode::CREATED, Json(note.into())))
+}
+
+/// 获取单条笔记，只能访问自己的笔记，同一租户的管理员除外
+#[utoipa::path(
+    get,
+    path = "/api/v1/notes/{id}",
+    tag = "notes",
+    params(("id" = String, Path, description = "Note id")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Note", body = NoteResponse),
+        (status = 403, description = "Read scope required or not the owner", body = ErrorResponse),
+        (status = 404, description = "Note not found", body = ErrorResponse)
+    )
+)]
+pub async fn get_note(
+    State(app_state): State<Arc<AppState>>,
+    Extension(claims): Extension<TokenClaims>,
+    Path(note_id): Path<String>,
+) -> Result<Json<NoteResponse>> {
+    let note_service = NoteService::new();
+    let note = note_service.get_note(app_state, note_id).await?;
+    ensure_can_read(&note, &claims)?;
+    Ok(Json(note.into()))
+}
+
+/// 读取笔记的权限检查：作者本人或同一租户的管理员
+fn ensure_can_read(note: &Note, claims: &TokenClaims) -> Result<()> {
+    ensure_owner_or_admin(&note.user_id, note.tenant_id.as_deref(), claims)
+}
+
+/// 修改笔记标题或内容，需要 write 权限，且只能修改自己的笔记
+#[utoipa::path(
+    patch,
+    path = "/api/v1/notes/{id}",
+    tag = "notes",
+    params(("id" = String, Path, description = "Note id")),
+    request_body(content(
+        (UpdateNoteRequest = "application/json"),
+        (UpdateNoteRequest = "application/x-www-form-urlencoded")
+    )),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Note updated", body = NoteResponse),
+        (status = 403, description = "Write scope required or not the owner", body = ErrorResponse),
+        (status = 404, description = "Note not found", body = ErrorResponse),
+        (status = 422, description = "Validation failed", body = ErrorResponse)
+    )
+)]
+pub async fn update_note(
+    State(app_state): State<Arc<AppState>>,
+    Extension(claims): Extension<TokenClaims>,
+    Path(note_id): Path<String>,
+    JsonOrForm(payload): JsonOrForm<UpdateNoteRequest>,
+) -> Result<Json<NoteResponse>> {
+    payload.validate()?;
+
+    let note_service = NoteService::new();
+    let note = note_service.get_note(app_state.clone(), note_id).await?;
+    ensure_owner(&note.user_id, &claims)?;
+    let note = note_service
+        .update_note(app_state, note, payload.title, payload.content)
+        .await?;
+    Ok(Json(note.into()))
+}
+
+/// 删除笔记，需要 delete 权限（默认只有管理员拥有），且只能删除自己的笔记
+#[utoipa::path(
+    delete,
+    path = "/api/v1/notes/{id}",
+    tag = "notes",
+    params(("id" = String, Path, description = "Note id")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 204, description = "Note deleted"),
+        (status = 403, description = "Delete scope required or not the owner", body = ErrorResponse),
+        (status = 404, description = "Note not found", body = ErrorResponse)
+    )
+)]
+pub async fn delete_note(
+    State(app_state): State<Arc<AppState>>,
+    Extension(claims): Extension<TokenClaims>,
+    Path(note_id): Path<String>,
+) -> Result<StatusCode> {
+    let note_service = NoteService::new();
+    let note = note_service.get_note(app_state.clone(), note_id).await?;
+    ensure_owner(&note.user_id, &claims)?;
+    note_service.delete_note(app_state, note.id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::token_claims::TENANT_ID_CLAIM;
+
+    fn claims_for(user_id: &str, role: Role, tenant_id: Option<&str>) -> TokenClaims {
+        let mut claims = TokenClaims::new_access_token(
+            user_id.to_string(),
+            format!("{user_id}@example.com"),
+            role.clone(),
+            0,
+            i64::MAX,
+            TokenClaims::default_scopes_for_role(&role),
+        );
+        if let Some(tenant_id) = tenant_id {
+            claims
+                .extra
+                .insert(TENANT_ID_CLAIM.to_string(), serde_json::json!(tenant_id));
+        }
+        claims
+    }
+
+    fn note_of(user_id: &str, tenant_id: Option<&str>) -> Note {
+        Note::new(
+            user_id.to_string(),
+            tenant_id.map(str::to_string),
+            "title".to_string(),
+            "content".to_string(),
+        )
+    }
+
+    #[test]
+    fn owner_can_read_own_note() {
+        let note = note_of("alice", Some("acme"));
+        assert!(ensure_can_read(&note, &claims_for("alice", Role::User, Some("acme"))).is_ok());
+    }
+
+    #[test]
+    fn user_cannot_read_another_users_note() {
+        let note = note_of("alice", Some("acme"));
+        assert!(ensure_can_read(&note, &claims_for("bob", Role::User, Some("acme"))).is_err());
+    }
+
+    #[test]
+    fn admin_can_read_note_in_same_tenant() {
+        let note = note_of("alice", Some("acme"));
+        assert!(ensure_can_read(&note, &claims_for("root", Role::Admin, Some("acme"))).is_ok());
+    }
+
+    #[test]
+    fn admin_cannot_read_note_from_another_tenant() {
+        let note = note_of("alice", Some("acme"));
+        assert!(ensure_can_read(&note, &claims_for("root", Role::Admin, Some("globex"))).is_err());
+        assert!(ensure_can_read(&note, &claims_for("root", Role::Admin, None)).is_err());
+    }
+}
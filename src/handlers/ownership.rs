@@ -0,0 +1,63 @@
+use crate::{
+    errors::{auth::AuthError, core::Result},
+    models::{role::Role, token_claims::TokenClaims},
+};
+
+/// 资源必须属于当前令牌的用户，否则返回 PermissionDenied
+pub fn ensure_owner(resource_owner_id: &str, claims: &TokenClaims) -> Result<()> {
+    if resource_owner_id != claims.sub {
+        return Err(AuthError::PermissionDenied.into());
+    }
+    Ok(())
+}
+
+/// 与 `ensure_owner` 相同，但管理员可以访问同一租户内任意用户的资源
+pub fn ensure_owner_or_admin(
+    resource_owner_id: &str,
+    resource_tenant_id: Option<&str>,
+    claims: &TokenClaims,
+) -> Result<()> {
+    if matches!(claims.role, Some(Role::Admin))
+        && claims.tenant_id().as_deref() == resource_tenant_id
+    {
+        return Ok(());
+    }
+    ensure_owner(resource_owner_id, claims)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn claims_for(user_id: &str, role: Role) -> TokenClaims {
+        TokenClaims::new_access_token(
+            user_id.to_string(),
+            format!("{user_id}@example.com"),
+            role.clone(),
+            0,
+            i64::MAX,
+            TokenClaims::default_scopes_for_role(&role),
+        )
+    }
+
+    #[test]
+    fn owner_is_allowed() {
+        assert!(ensure_owner("alice", &claims_for("alice", Role::User)).is_ok());
+    }
+
+    #[test]
+    fn non_owner_is_denied() {
+        assert!(ensure_owner("alice", &claims_for("bob", Role::User)).is_err());
+        assert!(ensure_owner("alice", &claims_for("root", Role::Admin)).is_err());
+    }
+
+    #[test]
+    fn admin_bypasses_ownership_within_the_same_tenant() {
+        assert!(ensure_owner_or_admin("alice", None, &claims_for("root", Role::Admin)).is_ok());
+        assert!(ensure_owner_or_admin("alice", None, &claims_for("bob", Role::User)).is_err());
+        assert!(ensure_owner_or_admin("alice", None, &claims_for("mod", Role::Moderator)).is_err());
+        assert!(
+            ensure_owner_or_admin("alice", Some("acme"), &claims_for("root", Role::Admin)).is_err()
+        );
+    }
+}
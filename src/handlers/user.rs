@@ -0,0 +1,344 @@
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::{HeaderMap, HeaderValue, header},
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+use std::sync::Arc;
+use validator::Validate;
+
+use crate::{
+    dtos::user::{
+        ConnectionInfo, DeleteAccountRequest, DeleteAccountResponse, ExportedProfile,
+        ExportedSession, ProfileResponse, ReplaceProfileRequest, UpdateProfileRequest,
+        UserDataExport,
+    },
+    errors::{auth::AuthError, core::Result, response::ErrorResponse},
+    extractors::json_or_form::JsonOrForm,
+    handlers::{auth::send_email_change_confirmation, etag::conditional_json},
+    models::{notification::NotificationPreferences, token_claims::TokenClaims, user::User},
+    services::user::UserService,
+    state::AppState,
+};
+
+/// 当前用户资料，支持 If-None-Match 条件请求
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/me",
+    tag = "users",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Current user profile", body = ProfileResponse),
+        (status = 304, description = "Profile unchanged since the given ETag"),
+        (status = 401, description = "Not authenticated", body = ErrorResponse)
+    )
+)]
+pub async fn get_profile(
+    State(app_state): State<Arc<AppState>>,
+    Extension(claims): Extension<TokenClaims>,
+    headers: HeaderMap,
+) -> Result<Response> {
+    let user_service = UserService::new();
+    let user = user_service
+        .find_by_id(app_state.clone(), claims.sub.clone())
+        .await?
+        .ok_or(AuthError::UserNoLongerExists)?;
+
+    let profile = profile_response(&app_state, user).await?;
+
+    Ok(conditional_json(&headers, &profile))
+}
+
+/// 部分更新当前用户资料，只修改请求中提供的字段；修改邮箱会向新邮箱发送确认邮件
+#[utoipa::path(
+    patch,
+    path = "/api/v1/users/me",
+    tag = "users",
+    security(("bearer_auth" = [])),
+    request_body(content(
+        (UpdateProfileRequest = "application/json"),
+        (UpdateProfileRequest = "application/x-www-form-urlencoded")
+    )),
+    responses(
+        (status = 200, description = "Updated profile", body = ProfileResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 409, description = "Email already exists", body = ErrorResponse),
+        (status = 422, description = "Validation failed", body = ErrorResponse)
+    )
+)]
+pub async fn update_profile(
+    State(app_state): State<Arc<AppState>>,
+    Extension(claims): Extension<TokenClaims>,
+    JsonOrForm(payload): JsonOrForm<UpdateProfileRequest>,
+) -> Result<Json<ProfileResponse>> {
+    payload.validate()?;
+
+    apply_profile_update(app_state, claims.sub, payload.name, payload.email).await
+}
+
+/// 替换当前用户资料，所有字段都必须提供；修改邮箱会向新邮箱发送确认邮件
+#[utoipa::path(
+    put,
+    path = "/api/v1/users/me",
+    tag = "users",
+    security(("bearer_auth" = [])),
+    request_body(content(
+        (ReplaceProfileRequest = "application/json"),
+        (ReplaceProfileRequest = "application/x-www-form-urlencoded")
+    )),
+    responses(
+        (status = 200, description = "Updated profile", body = ProfileResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 409, description = "Email already exists", body = ErrorResponse),
+        (status = 422, description = "Validation failed", body = ErrorResponse)
+    )
+)]
+pub async fn replace_profile(
+    State(app_state): State<Arc<AppState>>,
+    Extension(claims): Extension<TokenClaims>,
+    JsonOrForm(payload): JsonOrForm<ReplaceProfileRequest>,
+) -> Result<Json<ProfileResponse>> {
+    payload.validate()?;
+
+    apply_profile_update(
+        app_state,
+        claims.sub,
+        Some(payload.name),
+        Some(payload.email),
+    )
+    .await
+}
+
+// 与当前邮箱相同的邮箱视为未修改，不会重新发送确认邮件
+async fn apply_profile_update(
+    app_state: Arc<AppState>,
+    user_id: String,
+    name: Option<String>,
+    email: Option<String>,
+) -> Result<Json<ProfileResponse>> {
+    let user_service = UserService::new();
+    let user = user_service
+        .find_by_id(app_state.clone(), user_id.clone())
+        .await?
+        .ok_or(AuthError::UserNoLongerExists)?;
+    let new_email = email.filter(|email| email != &user.email);
+
+    let user = user_service
+        .update_profile(app_state.clone(), user_id, name, new_email.clone())
+        .await?;
+    if let Some(new_email) = new_email {
+        send_email_change_confirmation(&app_state, &user.id, &new_email).await?;
+    }
+
+    Ok(Json(profile_response(&app_state, user).await?))
+}
+
+async fn profile_response(app_state: &Arc<AppState>, user: User) -> Result<ProfileResponse> {
+    let active_sessions = app_state
+        .token_service
+        .count_user_active_sessions(app_state.clone(), user.id.clone())
+        .await?;
+
+    Ok(ProfileResponse {
+        id: user.id,
+        name: user.name,
+        email: user.email,
+        verified: user.verified,
+        role: user.role.to_str().to_string(),
+        created_at: user.created_at.unwrap_or_default(),
+        active_sessions: active_sessions as usize,
+        pending_email: user.pending_email,
+    })
+}
+
+/// 当前用户的邮件通知偏好
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/me/notifications",
+    tag = "users",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Notification preferences", body = NotificationPreferences),
+        (status = 401, description = "Not authenticated", body = ErrorResponse)
+    )
+)]
+pub async fn get_notification_preferences(
+    State(app_state): State<Arc<AppState>>,
+    Extension(claims): Extension<TokenClaims>,
+) -> Result<Json<NotificationPreferences>> {
+    let user_service = UserService::new();
+    let user = user_service
+        .find_by_id(app_state, claims.sub)
+        .await?
+        .ok_or(AuthError::UserNoLongerExists)?;
+    Ok(Json(user.notification_preferences))
+}
+
+/// 整体替换邮件通知偏好，未提供的字段使用默认值
+#[utoipa::path(
+    put,
+    path = "/api/v1/users/me/notifications",
+    tag = "users",
+    request_body = NotificationPreferences,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Updated notification preferences", body = NotificationPreferences),
+        (status = 401, description = "Not authenticated", body = ErrorResponse)
+    )
+)]
+pub async fn update_notification_preferences(
+    State(app_state): State<Arc<AppState>>,
+    Extension(claims): Extension<TokenClaims>,
+    Json(payload): Json<NotificationPreferences>,
+) -> Result<Json<NotificationPreferences>> {
+    let user_service = UserService::new();
+    let user = user_service
+        .update_notification_preferences(app_state, claims.sub, payload)
+        .await?;
+    Ok(Json(user.notification_preferences))
+}
+
+/// 导出当前用户的全部数据（资料和所有会话），以 JSON 附件形式下载
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/me/export",
+    tag = "users",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "User data export as a JSON attachment", body = UserDataExport),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 429, description = "Too many export requests")
+    )
+)]
+pub async fn export_user_data(
+    State(app_state): State<Arc<AppState>>,
+    Extension(claims): Extension<TokenClaims>,
+) -> Result<Response> {
+    let user_service = UserService::new();
+    let user = user_service
+        .find_by_id(app_state.clone(), claims.sub.clone())
+        .await?
+        .ok_or(AuthError::UserNoLongerExists)?;
+
+    let sessions = app_state
+        .token_service
+        .get_all_user_sessions(app_state.clone(), user.id.clone())
+        .await?;
+
+    let filename = format!("attachment; filename=\"user-data-{}.json\"", user.id);
+    let export = UserDataExport {
+        exported_at: Utc::now(),
+        profile: ExportedProfile {
+            id: user.id,
+            name: user.name,
+            email: user.email,
+            role: user.role.to_str().to_string(),
+            verified: user.verified,
+            created_at: user.created_at,
+            updated_at: user.updated_at,
+            last_login_at: user.last_login_at,
+            pending_email: user.pending_email,
+            tenant_id: user.tenant_id,
+            notification_preferences: user.notification_preferences,
+        },
+        sessions: sessions.into_iter().map(ExportedSession::from).collect(),
+    };
+
+    let mut response = Json(export).into_response();
+    if let Ok(value) = HeaderValue::from_str(&filename) {
+        response
+            .headers_mut()
+            .insert(header::CONTENT_DISPOSITION, value);
+    }
+    Ok(response)
+}
+
+/// 申请删除当前账户：立即撤销所有会话，宽限期结束后由后台任务彻底删除
+#[utoipa::path(
+    delete,
+    path = "/api/v1/users/me",
+    tag = "users",
+    security(("bearer_auth" = [])),
+    request_body(content(
+        (DeleteAccountRequest = "application/json"),
+        (DeleteAccountRequest = "application/x-www-form-urlencoded")
+    )),
+    responses(
+        (status = 200, description = "Account scheduled for deletion", body = DeleteAccountResponse),
+        (status = 401, description = "Invalid password", body = ErrorResponse),
+        (status = 422, description = "Validation failed", body = ErrorResponse)
+    )
+)]
+pub async fn delete_account(
+    State(app_state): State<Arc<AppState>>,
+    Extension(claims): Extension<TokenClaims>,
+    JsonOrForm(payload): JsonOrForm<DeleteAccountRequest>,
+) -> Result<Json<DeleteAccountResponse>> {
+    payload.validate()?;
+
+    let user_service = UserService::new();
+    let purge_at = user_service
+        .schedule_deletion(app_state.clone(), claims.sub.clone(), payload.password)
+        .await?;
+
+    app_state
+        .token_service
+        .revoke_all_user_sessions(app_state.clone(), claims.sub)
+        .await?;
+
+    Ok(Json(DeleteAccountResponse {
+        message: "Account scheduled for deletion".to_string(),
+        purge_at,
+    }))
+}
+
+/// 当前用户绑定的第三方登录提供方
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/me/connections",
+    tag = "users",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Connected sign-in providers", body = Vec<ConnectionInfo>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse)
+    )
+)]
+pub async fn list_connections(
+    State(app_state): State<Arc<AppState>>,
+    Extension(claims): Extension<TokenClaims>,
+) -> Result<Json<Vec<ConnectionInfo>>> {
+    let connections = UserService::new()
+        .list_connections(app_state, claims.sub)
+        .await?;
+    Ok(Json(
+        connections.into_iter().map(ConnectionInfo::from).collect(),
+    ))
+}
+
+/// 解绑某个提供方；没有设置密码时不能解绑最后一个提供方
+#[utoipa::path(
+    delete,
+    path = "/api/v1/users/me/connections/{provider}",
+    tag = "users",
+    params(("provider" = String, Path, description = "Provider name, e.g. github")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Provider unlinked", body = serde_json::Value),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 404, description = "Provider not connected", body = ErrorResponse),
+        (status = 409, description = "Last remaining sign-in method", body = ErrorResponse)
+    )
+)]
+pub async fn unlink_connection(
+    State(app_state): State<Arc<AppState>>,
+    Extension(claims): Extension<TokenClaims>,
+    Path(provider): Path<String>,
+) -> Result<Json<serde_json::Value>> {
+    UserService::new()
+        .unlink_connection(app_state, claims.sub, provider.clone())
+        .await?;
+    Ok(Json(serde_json::json!({
+        "message": format!("Provider {} has been unlinked", provider.to_lowercase())
+    })))
+}
@@ -0,0 +1,90 @@
+use axum::{
+    extract::{
+        Query, State,
+        ws::{Message, WebSocket, WebSocketUpgrade},
+    },
+    http::HeaderMap,
+    response::Response,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use tokio::sync::broadcast::{Receiver, error::RecvError};
+use utoipa::IntoParams;
+
+use crate::{
+    errors::{auth::AuthError, core::Result, response::ErrorResponse},
+    middlewares::auth::request_token,
+    models::session_event::SessionEvent,
+    state::AppState,
+};
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct WsQuery {
+    pub token: Option<String>,
+}
+
+/// 浏览器的 WebSocket 握手无法携带 Authorization header，优先使用同源握手自动带上的 access_token Cookie；
+/// 不使用 Cookie 的客户端才退回 `?token=` 查询参数，请求日志只记录路径，不会写入查询参数中的令牌
+#[utoipa::path(
+    get,
+    path = "/api/v1/ws",
+    tag = "sessions",
+    params(WsQuery),
+    responses(
+        (status = 101, description = "Upgraded to a WebSocket streaming session events"),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse)
+    )
+)]
+pub async fn ws_handler(
+    ws: WebSocketUpgrade,
+    State(app_state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<WsQuery>,
+) -> Result<Response> {
+    let token = request_token(&headers)
+        .map(str::to_string)
+        .or(query.token)
+        .ok_or(AuthError::TokenNotProvided)?;
+
+    let claims = app_state
+        .token_service
+        .verify_access_token_with_session(app_state.clone(), &token)
+        .await?;
+
+    let receiver = app_state.session_events.subscribe();
+
+    Ok(ws.on_upgrade(move |socket| handle_socket(socket, claims.sub, receiver)))
+}
+
+async fn handle_socket(
+    mut socket: WebSocket,
+    user_id: String,
+    mut receiver: Receiver<SessionEvent>,
+) {
+    loop {
+        tokio::select! {
+            event = receiver.recv() => match event {
+                Ok(event) => {
+                    if event.user_id() != user_id {
+                        continue;
+                    }
+                    let Ok(payload) = serde_json::to_string(&event) else {
+                        continue;
+                    };
+                    if socket.send(Message::Text(payload.into())).await.is_err() {
+                        break;
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!(user_id = %user_id, skipped, "WebSocket subscriber lagged behind");
+                }
+                Err(RecvError::Closed) => break,
+            },
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+    // receiver 在此处被 drop，订阅随连接一起释放
+}
@@ -1,92 +1,194 @@
+mod cli;
 mod config;
 mod database;
 mod dtos;
 mod errors;
+mod extractors;
 mod handlers;
 mod middlewares;
 mod models;
 mod routes;
 mod services;
 mod state;
+mod tasks;
 
+use crate::cli::{Command, USAGE};
 use crate::config::Config;
+use crate::config::logging::{LogFormat, LoggingConfig};
 use crate::config::token::TokenConfig;
-use crate::database::init::initialize_database;
+use crate::database::init::{connect, initialize_database};
+use crate::database::seed::seed_default_admin;
+use crate::database::tenant::TenantDatabases;
+use crate::errors::core::set_verbose_errors;
 use crate::routes::all_routes;
+use crate::services::breach::BreachService;
+use crate::services::feature_flags::FeatureFlags;
+use crate::services::maintenance::Maintenance;
+use crate::services::rate_limit::UserRateLimiter;
 use crate::services::token::TokenService;
 use crate::services::user::UserService;
 use crate::state::AppState;
+use crate::tasks::cleanup::spawn_token_cleanup;
+use crate::tasks::feature_flags::spawn_feature_flag_refresh;
 use axum::serve;
+use chrono::Utc;
+use std::net::SocketAddr;
 use std::sync::Arc;
-use surrealdb::Surreal;
-use surrealdb::engine::remote::ws::{Client, Ws};
-use surrealdb::opt::auth::Root;
-use tracing::{error, info};
-use tracing_subscriber::EnvFilter;
+use std::sync::atomic::{AtomicI64, AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::sync::{broadcast, oneshot};
+use tracing::{error, info, warn};
+
+const SESSION_EVENTS_CAPACITY: usize = 1024;
+
+fn init_tracing(logging_config: &LoggingConfig) {
+    let subscriber = tracing_subscriber::fmt()
+        .with_env_filter(logging_config.env_filter())
+        .with_target(true);
+    match logging_config.format {
+        LogFormat::Pretty => subscriber.pretty().init(),
+        // trace_id、error_code 等事件字段展开到顶层，日志系统可以直接按字段检索
+        LogFormat::Json => subscriber
+            .json()
+            .flatten_event(true)
+            .with_current_span(true)
+            .init(),
+        LogFormat::Compact => subscriber.compact().init(),
+    }
+}
 
 pub async fn run() {
     dotenvy::dotenv().ok();
 
-    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("debug"));
-    tracing_subscriber::fmt()
-        .with_env_filter(filter)
-        .with_target(true)
-        .pretty()
-        .init();
+    init_tracing(&LoggingConfig::new());
+
+    let command = match Command::from_args(std::env::args().skip(1)) {
+        Ok(command) => command,
+        Err(message) => {
+            error!("❌ {}", message);
+            error!("{}", USAGE);
+            std::process::exit(2);
+        }
+    };
 
     let config = Config::new();
+    set_verbose_errors(config.server_config.verbose_errors);
+    if config.server_config.verbose_errors {
+        warn!("⚠️ VERBOSE_ERRORS is enabled, error responses expose internal details.");
+    }
 
-    let db: Surreal<Client> = Surreal::<Client>::init();
-    db.connect::<Ws>(&config.db_config.surreal_url)
+    let db = connect(&config.db_config, &config.db_config.surreal_root_db)
         .await
-        .unwrap();
-    db.signin(Root {
-        username: &config.db_config.surreal_root_username,
-        password: &config.db_config.surreal_root_password,
-    })
-    .await
-    .unwrap();
-    db.use_ns(&config.db_config.surreal_root_ns)
-        .use_db(&config.db_config.surreal_root_db)
-        .await
-        .unwrap();
+        .expect("Failed to connect to SurrealDB");
 
     let port = config.server_config.server_port;
-    info!(
-        "{}",
-        format!("✅ The server is running on http://localhost:{port}")
-    );
-    info!("✅ You can press Ctrl+C to shut it down.");
-
     let app_state = Arc::new(AppState {
         env: config.clone(),
-        db: db,
+        root_db: db,
+        tenant_databases: TenantDatabases::new(),
         token_service: TokenService::new(TokenConfig::new()),
         user_service: UserService::new(),
+        breach_service: BreachService::new(config.password_policy.breach_check_timeout_ms),
+        feature_flags: FeatureFlags::new(config.feature_flag_config.defaults.clone()),
+        maintenance: Maintenance::new(&config.maintenance_config),
+        user_rate_limiter: UserRateLimiter::new(&config.user_rate_limit_config),
+        session_events: broadcast::channel(SESSION_EVENTS_CAPACITY).0,
+        cleanup_last_run: Arc::new(AtomicI64::new(0)),
+        in_flight_requests: Arc::new(AtomicUsize::new(0)),
+        started_at: Utc::now(),
     });
 
-    initialize_database(app_state.clone())
+    if let Err(e) = initialize_database(app_state.clone()).await {
+        e.log_error();
+        error!("❌ Failed to initialize database schema, refusing to start.");
+        std::process::exit(1);
+    }
+
+    // 首次加载失败时先使用配置中的默认值，后台任务会继续重试
+    if let Err(e) = app_state.feature_flags.refresh(app_state.clone()).await {
+        e.log_error();
+    }
+    if let Err(e) = app_state.maintenance.refresh(app_state.clone()).await {
+        e.log_error();
+    }
+
+    seed_default_admin(app_state.clone())
         .await
-        .expect("Failed to initialize database");
+        .expect("Failed to seed default admin");
+
+    if let Command::CreateAdmin {
+        name,
+        email,
+        password,
+    } = command
+    {
+        match app_state
+            .user_service
+            .ensure_admin(app_state.clone(), name, email, password)
+            .await
+        {
+            Ok(Some(admin)) => info!("✅ Admin user {} has been created.", admin.email),
+            Ok(None) => info!("An admin user already exists, skipping."),
+            Err(e) => {
+                e.log_error();
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    spawn_token_cleanup(app_state.clone());
+    spawn_feature_flag_refresh(app_state.clone());
+
+    info!(
+        "{}",
+        format!("✅ The server is running on http://localhost:{port}")
+    );
+    info!("✅ You can press Ctrl+C to shut it down.");
 
     let app_router = all_routes(app_state.clone());
 
     let listener = tokio::net::TcpListener::bind(format!("127.0.0.1:{port}"))
         .await
         .unwrap();
-    serve(listener, app_router)
-        .with_graceful_shutdown(async {
-            match tokio::signal::ctrl_c().await {
-                Ok(()) => {
-                    println!();
-                    info!("✅ The server has been shut down gracefully by Ctrl+C.");
-                }
-                Err(e) => {
-                    println!();
-                    error!("❌ Error: {}", e);
-                }
+    let in_flight_requests = app_state.in_flight_requests.clone();
+    let drain_timeout = Duration::from_secs(config.server_config.shutdown_drain_timeout);
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+    // 保留对端地址，供登录记录 IP 和限流按 IP 区分客户端
+    let server = serve(
+        listener,
+        app_router.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(async {
+        match tokio::signal::ctrl_c().await {
+            Ok(()) => {
+                println!();
+                info!("✅ The server has been shut down gracefully by Ctrl+C.");
             }
-        })
-        .await
-        .unwrap();
+            Err(e) => {
+                println!();
+                error!("❌ Error: {}", e);
+            }
+        }
+        let _ = shutdown_tx.send(());
+    });
+
+    // 收到停机信号后最多等待 drain_timeout，避免卡住的长连接让进程一直无法退出
+    tokio::select! {
+        result = async { server.await } => result.unwrap(),
+        _ = async {
+            if shutdown_rx.await.is_ok() {
+                tokio::time::sleep(drain_timeout).await;
+            } else {
+                std::future::pending::<()>().await;
+            }
+        } => {
+            warn!(
+                in_flight = in_flight_requests.load(Ordering::Relaxed),
+                drain_timeout_secs = drain_timeout.as_secs(),
+                "Shutdown drain deadline reached, forcing exit"
+            );
+            std::process::exit(1);
+        }
+    }
 }
@@ -4,14 +4,23 @@ use std::sync::Arc;
 
 use axum::{
     extract::{Request, State},
-    http::{StatusCode, header::AUTHORIZATION},
+    http::{
+        HeaderMap, StatusCode,
+        header::{AUTHORIZATION, COOKIE, USER_AGENT},
+    },
     middleware::Next,
     response::Response,
 };
 
 use crate::{
-    models::{token_claims::TokenClaims, token_scope::TokenScope},
-    services::token::TokenService,
+    config::database::TenantIsolation,
+    extractors::tenant::TenantContext,
+    models::{
+        device::{DEVICE_FINGERPRINT_HEADER, DeviceInfo},
+        token_claims::TokenClaims,
+        token_scope::TokenScope,
+    },
+    services::token::{ACCESS_TOKEN_COOKIE, TokenService},
     state::AppState,
 };
 
@@ -20,24 +29,85 @@ pub async fn auth_middleware(
     mut request: Request,
     next: Next,
 ) -> Result<Response, StatusCode> {
-    let auth_header = request
-        .headers()
-        .get(AUTHORIZATION)
-        .and_then(|header| header.to_str().ok())
-        .ok_or(StatusCode::UNAUTHORIZED)?;
-    let token =
-        TokenService::extract_token_from_header(auth_header).ok_or(StatusCode::UNAUTHORIZED)?;
+    let token = request_token(request.headers()).ok_or(StatusCode::UNAUTHORIZED)?;
     let token_service = &app_state.token_service;
-    let claims = token_service
-        .verify_access_token(token)
-        .map_err(|_| StatusCode::UNAUTHORIZED)?;
-    if claims.is_expired() {
+    // 闲置超时依赖 last_active_at，只有启用时才为每个请求查询并刷新会话
+    let claims = if app_state.env.token_config.session_idle_timeout.is_some() {
+        token_service
+            .verify_access_token_with_session(app_state.clone(), token)
+            .await
+    } else {
+        token_service
+            .resolve_access_token(app_state.clone(), token)
+            .await
+    }
+    .map_err(|_| StatusCode::UNAUTHORIZED)?;
+    if claims.is_expired(app_state.env.token_config.clock_skew_leeway) {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+    // 启用绑定之前签发、没有绑定声明的令牌照常放行，直到自然过期
+    if app_state.env.token_config.device_binding
+        && let Some(binding) = claims.device_binding()
+        && binding != device_binding(request.headers())
+    {
+        tracing::warn!(user_id = %claims.sub, "Access token presented from a different device");
         return Err(StatusCode::UNAUTHORIZED);
     }
+    if !tenant_matches(&app_state, request.headers(), &claims) {
+        tracing::warn!(user_id = %claims.sub, "Access token presented to another tenant's database");
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+    record_user_on_span(&claims);
     request.extensions_mut().insert(claims);
     Ok(next.run(request).await)
 }
 
+/// 按数据库隔离租户时，请求所用的租户库由 Host 决定，令牌中的租户必须与之一致，
+/// 否则一个租户的令牌可以在另一个租户的库上执行操作
+fn tenant_matches(app_state: &AppState, headers: &HeaderMap, claims: &TokenClaims) -> bool {
+    app_state.env.db_config.tenant_isolation != TenantIsolation::Database
+        || claims.tenant_id() == TenantContext::from_headers(headers, app_state)
+}
+
+/// 把用户信息写入 TraceLayer 创建的请求 span，之后本请求内的所有日志都会带上 user_id
+fn record_user_on_span(claims: &TokenClaims) {
+    let span = tracing::Span::current();
+    span.record("user_id", claims.sub.as_str());
+    if let Some(role) = &claims.role {
+        span.record("role", role.to_str());
+    }
+}
+
+/// 根据请求头计算设备绑定值，见 `DeviceInfo::binding`
+pub(crate) fn device_binding(headers: &HeaderMap) -> String {
+    let header = |name| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+    };
+    DeviceInfo::binding(
+        header(USER_AGENT.as_str()),
+        header(DEVICE_FINGERPRINT_HEADER),
+    )
+}
+
+/// 优先使用 Authorization: Bearer，没有时退回 access_token Cookie
+pub(crate) fn request_token(headers: &HeaderMap) -> Option<&str> {
+    match headers
+        .get(AUTHORIZATION)
+        .and_then(|header| header.to_str().ok())
+    {
+        Some(auth_header) => TokenService::extract_token_from_header(auth_header),
+        None => headers
+            .get(COOKIE)
+            .and_then(|header| header.to_str().ok())
+            .and_then(|cookies| {
+                TokenService::extract_token_from_cookie(cookies, ACCESS_TOKEN_COOKIE)
+            }),
+    }
+}
+
 pub fn require_scopes(
     required_scopes: Vec<TokenScope>,
 ) -> impl Clone
@@ -88,20 +158,18 @@ pub async fn optional_auth_middleware(
     mut request: Request,
     next: Next,
 ) -> Response {
-    // 尝试获取 Authorization header
-    if let Some(auth_header) = request
-        .headers()
-        .get(AUTHORIZATION)
-        .and_then(|header| header.to_str().ok())
-    {
-        if let Some(token) = TokenService::extract_token_from_header(auth_header) {
-            let token_service = &app_state.token_service;
-
-            if let Ok(claims) = token_service.verify_access_token(token) {
-                if !claims.is_expired() {
-                    request.extensions_mut().insert(claims);
-                }
-            }
+    // 尝试获取 Authorization header 或 Cookie 中的令牌
+    if let Some(token) = request_token(request.headers()) {
+        let token_service = &app_state.token_service;
+
+        if let Ok(claims) = token_service
+            .resolve_access_token(app_state.clone(), token)
+            .await
+            && !claims.is_expired(app_state.env.token_config.clock_skew_leeway)
+            && tenant_matches(&app_state, request.headers(), &claims)
+        {
+            record_user_on_span(&claims);
+            request.extensions_mut().insert(claims);
         }
     }
 
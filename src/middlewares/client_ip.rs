@@ -0,0 +1,24 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::{extractors::client_ip::ClientIp, state::AppState};
+
+/// 解析真实客户端地址并放入请求扩展，会话记录、限流和管理员 IP 过滤都从这里读取；
+/// 必须位于全局限流层外侧
+pub async fn client_ip_middleware(
+    State(app_state): State<Arc<AppState>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    if let Some(ConnectInfo(peer)) = request.extensions().get::<ConnectInfo<SocketAddr>>() {
+        let ip = ClientIp::resolve(&app_state.env.network_config, peer.ip(), request.headers());
+        request.extensions_mut().insert(ClientIp(ip));
+    }
+    next.run(request).await
+}
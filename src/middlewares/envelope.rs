@@ -0,0 +1,41 @@
+use axum::{
+    body::{Body, to_bytes},
+    extract::Request,
+    http::{HeaderMap, StatusCode, header::CONTENT_LENGTH, header::CONTENT_TYPE},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::dtos::response::ApiResponse;
+
+/// 将 2xx 的 JSON 响应包装为 `{ data, meta }`，由 WRAP_SUCCESS_RESPONSES 开启；
+/// 错误响应、304 以及 SSE 等非 JSON 响应保持原样
+pub async fn response_envelope_middleware(
+    request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let response = next.run(request).await;
+    if !response.status().is_success() || !is_json(response.headers()) {
+        return Ok(response);
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = to_bytes(body, usize::MAX)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let Ok(data) = serde_json::from_slice::<serde_json::Value>(&bytes) else {
+        return Ok(Response::from_parts(parts, Body::from(bytes)));
+    };
+
+    let (_, body) = ApiResponse::new(data).into_response().into_parts();
+    parts.headers.remove(CONTENT_LENGTH);
+    Ok(Response::from_parts(parts, body))
+}
+
+fn is_json(headers: &HeaderMap) -> bool {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.starts_with("application/json"))
+        .unwrap_or(false)
+}
@@ -0,0 +1,48 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::Response,
+};
+
+use crate::{extractors::client_ip::ClientIp, state::AppState};
+
+/// 管理员路由的网络层防护：按 ADMIN_IP_ALLOWLIST / ADMIN_IP_DENYLIST 检查客户端地址，
+/// 挂在 auth_middleware 外侧，被拒绝的地址不会走到令牌校验
+pub async fn admin_ip_filter_middleware(
+    State(app_state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let network = &app_state.env.network_config;
+    if network.admin_allowlist.is_empty() && network.admin_denylist.is_empty() {
+        return Ok(next.run(request).await);
+    }
+    let Some(ClientIp(ip)) = request.extensions().get::<ClientIp>().copied() else {
+        return Err(StatusCode::FORBIDDEN);
+    };
+    if !network.is_admin_ip_allowed(ip) {
+        tracing::warn!(client_ip = %ip, path = %request.uri().path(), "Admin route blocked by IP filter");
+        return Err(StatusCode::FORBIDDEN);
+    }
+    Ok(next.run(request).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::network::NetworkConfig;
+
+    #[test]
+    fn denylist_takes_precedence_over_allowlist() {
+        let network = NetworkConfig {
+            trusted_proxies: Vec::new(),
+            admin_allowlist: vec!["10.8.0.0/16".parse().unwrap()],
+            admin_denylist: vec!["10.8.9.0/24".parse().unwrap()],
+        };
+        assert!(network.is_admin_ip_allowed("10.8.1.1".parse().unwrap()));
+        assert!(!network.is_admin_ip_allowed("10.8.9.1".parse().unwrap()));
+        assert!(!network.is_admin_ip_allowed("203.0.113.7".parse().unwrap()));
+    }
+}
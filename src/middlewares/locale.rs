@@ -0,0 +1,47 @@
+use axum::{
+    extract::Request,
+    http::{
+        HeaderValue,
+        header::{ACCEPT_LANGUAGE, CONTENT_LANGUAGE, CONTENT_LENGTH},
+    },
+    middleware::Next,
+    response::Response,
+};
+
+use crate::errors::{
+    i18n::{Locale, translate},
+    response::HttpError,
+};
+
+/// 解析 Accept-Language 并放入请求扩展，错误响应的 message 按错误码替换为对应语言，
+/// code 保持不变
+pub async fn locale_middleware(mut request: Request, next: Next) -> Response {
+    let locale = request
+        .headers()
+        .get(ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok())
+        .map(Locale::from_accept_language)
+        .unwrap_or_default();
+    request.extensions_mut().insert(locale);
+
+    let response = next.run(request).await;
+    let Some(error) = response.extensions().get::<HttpError>() else {
+        return response;
+    };
+    let Some(message) = translate(&error.code, locale) else {
+        return response;
+    };
+
+    let localized = HttpError {
+        message: message.to_string(),
+        ..error.clone()
+    }
+    .into_http_response();
+    let (mut parts, _) = response.into_parts();
+    let (_, body) = localized.into_parts();
+    parts.headers.remove(CONTENT_LENGTH);
+    parts
+        .headers
+        .insert(CONTENT_LANGUAGE, HeaderValue::from_static(locale.as_str()));
+    Response::from_parts(parts, body)
+}
@@ -0,0 +1,88 @@
+use axum::{
+    body::{Body, Bytes, to_bytes},
+    extract::Request,
+    http::{HeaderMap, StatusCode, header::CONTENT_TYPE},
+    middleware::Next,
+    response::Response,
+};
+use tracing::debug;
+
+const MAX_LOGGED_BODY_BYTES: usize = 1024 * 1024;
+
+const SENSITIVE_FIELDS: &[&str] = &[
+    "password",
+    "current_password",
+    "new_password",
+    "access_token",
+    "refresh_token",
+    "token",
+    "reset_token",
+    "recovery_code",
+    "recovery_codes",
+];
+
+/// 以 debug 级别记录 JSON 请求/响应体，敏感字段替换为 "[REDACTED]"
+/// 只缓冲 JSON 内容，SSE、WebSocket 等流式响应原样透传
+pub async fn body_logging_middleware(request: Request, next: Next) -> Result<Response, StatusCode> {
+    let (parts, body) = request.into_parts();
+    let request = if is_json(&parts.headers) {
+        let bytes = to_bytes(body, MAX_LOGGED_BODY_BYTES)
+            .await
+            .map_err(|_| StatusCode::PAYLOAD_TOO_LARGE)?;
+        debug!(
+            method = %parts.method,
+            path = %parts.uri.path(),
+            body = %redact_body(&bytes),
+            "Request body"
+        );
+        Request::from_parts(parts, Body::from(bytes))
+    } else {
+        Request::from_parts(parts, body)
+    };
+
+    let response = next.run(request).await;
+
+    let (parts, body) = response.into_parts();
+    if !is_json(&parts.headers) {
+        return Ok(Response::from_parts(parts, body));
+    }
+    let bytes = to_bytes(body, usize::MAX)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    debug!(status = %parts.status, body = %redact_body(&bytes), "Response body");
+    Ok(Response::from_parts(parts, Body::from(bytes)))
+}
+
+fn is_json(headers: &HeaderMap) -> bool {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.starts_with("application/json"))
+        .unwrap_or(false)
+}
+
+fn redact_body(bytes: &Bytes) -> String {
+    match serde_json::from_slice::<serde_json::Value>(bytes) {
+        Ok(mut value) => {
+            redact(&mut value);
+            value.to_string()
+        }
+        Err(_) => format!("<{} bytes of non-JSON body>", bytes.len()),
+    }
+}
+
+fn redact(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, field) in map.iter_mut() {
+                if SENSITIVE_FIELDS.contains(&key.as_str()) {
+                    *field = serde_json::Value::String("[REDACTED]".to_string());
+                } else {
+                    redact(field);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
@@ -0,0 +1,79 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Request, State},
+    http::{HeaderMap, HeaderValue, Method, StatusCode, header::RETRY_AFTER},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::{
+    errors::response::HttpError,
+    middlewares::auth::request_token,
+    models::{maintenance::MaintenanceMode, role::Role},
+    state::AppState,
+};
+
+// 登录和刷新必须保持可用，否则管理员在维护期间无法拿到令牌来关闭维护模式
+const EXEMPT_PATHS: &[&str] = &[
+    "/api/v1/health",
+    "/api/v1/ready",
+    "/api/v1/auth/login",
+    "/api/v1/auth/refresh",
+];
+
+/// 维护期间返回 503 和 Retry-After，管理员路由、健康检查以及携带管理员令牌的请求不受影响
+pub async fn maintenance_middleware(
+    State(app_state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let status = app_state.maintenance.current();
+    let blocked = match status.mode {
+        MaintenanceMode::Off => false,
+        MaintenanceMode::ReadOnly => !matches!(
+            *request.method(),
+            Method::GET | Method::HEAD | Method::OPTIONS
+        ),
+        MaintenanceMode::Offline => true,
+    };
+    if !blocked || is_exempt(&app_state, request.uri().path(), request.headers()).await {
+        return next.run(request).await;
+    }
+
+    let message = status
+        .message
+        .clone()
+        .unwrap_or_else(|| "The service is temporarily down for maintenance".to_string());
+    let mut response = HttpError::new(message, StatusCode::SERVICE_UNAVAILABLE, "MAINTENANCE_MODE")
+        .with_details(serde_json::json!({
+            "mode": status.mode,
+            "retry_after_seconds": status.retry_after_seconds,
+        }))
+        .into_http_response();
+    response
+        .headers_mut()
+        .insert(RETRY_AFTER, HeaderValue::from(status.retry_after_seconds));
+    response
+}
+
+// 只借用路径和头部：Request 不是 Sync，不能跨 await 持有其引用
+async fn is_exempt(app_state: &Arc<AppState>, path: &str, headers: &HeaderMap) -> bool {
+    if path.starts_with("/api/v1/admin/")
+        || path.starts_with("/api/v1/docs")
+        || EXEMPT_PATHS.contains(&path)
+    {
+        return true;
+    }
+    let Some(token) = request_token(headers) else {
+        return false;
+    };
+    app_state
+        .token_service
+        .resolve_access_token(app_state.clone(), token)
+        .await
+        .is_ok_and(|claims| {
+            matches!(claims.role, Some(Role::Admin))
+                && !claims.is_expired(app_state.env.token_config.clock_skew_leeway)
+        })
+}
@@ -1 +1,10 @@
 pub mod auth;
+pub mod client_ip;
+pub mod envelope;
+pub mod in_flight;
+pub mod ip_filter;
+pub mod locale;
+pub mod logging;
+pub mod maintenance;
+pub mod rate_limit;
+pub mod tenant;
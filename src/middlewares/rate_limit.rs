@@ -0,0 +1,31 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::{
+    errors::{api::ApiError, core::Result},
+    models::token_claims::TokenClaims,
+    state::AppState,
+};
+
+/// 按 claims.sub 限流，必须挂在 auth_middleware 内侧才能拿到 claims
+pub async fn user_rate_limit_middleware(
+    State(app_state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Result<Response> {
+    if !app_state.env.user_rate_limit_config.enabled {
+        return Ok(next.run(request).await);
+    }
+    if let Some(claims) = request.extensions().get::<TokenClaims>()
+        && !app_state.user_rate_limiter.try_acquire(&claims.sub)
+    {
+        tracing::warn!(user_id = %claims.sub, "Per-user rate limit exceeded");
+        return Err(ApiError::RateLimitExceeded.into());
+    }
+    Ok(next.run(request).await)
+}
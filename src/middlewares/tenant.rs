@@ -0,0 +1,32 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::{
+    config::database::TenantIsolation, database::tenant::CURRENT_TENANT_DB, errors::core::Result,
+    extractors::tenant::TenantContext, state::AppState,
+};
+
+/// 按数据库隔离租户时，把请求内的所有查询切换到子域名对应的租户库；
+/// 必须位于认证和维护模式中间件外侧，它们查询会话时也要落在租户库上
+pub async fn tenant_database_middleware(
+    State(app_state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Result<Response> {
+    if app_state.env.db_config.tenant_isolation != TenantIsolation::Database {
+        return Ok(next.run(request).await);
+    }
+    let Some(tenant_id) = TenantContext::from_headers(request.headers(), &app_state) else {
+        return Ok(next.run(request).await);
+    };
+    let db = app_state
+        .tenant_databases
+        .connection(app_state.clone(), &tenant_id)
+        .await?;
+    Ok(CURRENT_TENANT_DB.scope(db, next.run(request)).await)
+}
@@ -0,0 +1,103 @@
+use serde::{Deserialize, Deserializer, Serialize};
+use sha1::{Digest, Sha1};
+use std::net::IpAddr;
+use utoipa::ToSchema;
+use woothee::{parser::Parser, woothee::VALUE_UNKNOWN};
+
+/// 客户端自行生成并持久保存的设备指纹，启用令牌设备绑定时每次请求都要携带
+pub const DEVICE_FINGERPRINT_HEADER: &str = "x-device-fingerprint";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct DeviceInfo {
+    pub browser: Option<String>,
+    pub os: Option<String>,
+    pub device_type: Option<String>,
+    /// 原始 User-Agent，解析失败时用于展示
+    pub raw: String,
+}
+
+impl DeviceInfo {
+    pub fn from_user_agent(user_agent: &str) -> Self {
+        let known = |value: &str| (value != VALUE_UNKNOWN).then(|| value.to_string());
+        match Parser::new().parse(user_agent) {
+            Some(result) => Self {
+                browser: known(result.name),
+                os: known(result.os),
+                device_type: known(result.category),
+                raw: user_agent.to_string(),
+            },
+            None => Self {
+                browser: None,
+                os: None,
+                device_type: None,
+                raw: user_agent.to_string(),
+            },
+        }
+    }
+    /// 设备指纹：User-Agent 加上 IP 所在网段（IPv4 /24，IPv6 /48）的哈希，
+    /// 同一网络内 IP 变化不会被视为新设备
+    pub fn fingerprint(device_info: Option<&DeviceInfo>, ip: Option<IpAddr>) -> String {
+        let user_agent = device_info
+            .map(|info| info.raw.as_str())
+            .unwrap_or_default();
+        let subnet = match ip {
+            Some(IpAddr::V4(ip)) => {
+                let [a, b, c, _] = ip.octets();
+                format!("{a}.{b}.{c}.0/24")
+            }
+            Some(IpAddr::V6(ip)) => {
+                let segments = ip.segments();
+                format!("{:x}:{:x}:{:x}::/48", segments[0], segments[1], segments[2])
+            }
+            None => String::new(),
+        };
+        hex::encode(Sha1::digest(format!("{user_agent}|{subnet}")))
+    }
+    /// 令牌绑定值：User-Agent 加客户端提供的指纹的哈希；不包含 IP，切换网络不会使令牌失效
+    pub fn binding(user_agent: &str, client_fingerprint: &str) -> String {
+        hex::encode(Sha1::digest(format!("{user_agent}|{client_fingerprint}")))
+    }
+    /// 例如 "Chrome on Mac OSX"，无法识别时退回原始 User-Agent
+    pub fn display_name(&self) -> String {
+        match (&self.browser, &self.os) {
+            (Some(browser), Some(os)) => format!("{browser} on {os}"),
+            (Some(name), None) | (None, Some(name)) => name.clone(),
+            (None, None) => self.raw.clone(),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for DeviceInfo {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        // 兼容旧会话中以原始字符串保存的 device_info
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Stored {
+            Raw(String),
+            Structured {
+                browser: Option<String>,
+                os: Option<String>,
+                device_type: Option<String>,
+                raw: String,
+            },
+        }
+
+        Ok(match Stored::deserialize(deserializer)? {
+            Stored::Raw(raw) => DeviceInfo::from_user_agent(&raw),
+            Stored::Structured {
+                browser,
+                os,
+                device_type,
+                raw,
+            } => DeviceInfo {
+                browser,
+                os,
+                device_type,
+                raw,
+            },
+        })
+    }
+}
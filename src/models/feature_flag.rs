@@ -0,0 +1,14 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// 关闭后注册接口返回 404
+pub const REGISTRATION: &str = "registration";
+
+/// 运行时开关，数据库中的记录覆盖配置中的默认值，未配置的开关视为开启
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FeatureFlag {
+    pub name: String,
+    pub enabled: bool,
+    pub updated_at: Option<DateTime<Utc>>,
+}
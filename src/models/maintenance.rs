@@ -0,0 +1,35 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MaintenanceMode {
+    #[default]
+    Off,
+    /// 只允许 GET、HEAD、OPTIONS 请求
+    ReadOnly,
+    /// 除管理员、健康检查和登录外全部拒绝
+    Offline,
+}
+
+impl MaintenanceMode {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "off" => Some(MaintenanceMode::Off),
+            "read_only" => Some(MaintenanceMode::ReadOnly),
+            "offline" => Some(MaintenanceMode::Offline),
+            _ => None,
+        }
+    }
+}
+
+/// 维护状态，以单条记录 maintenance:current 保存
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MaintenanceStatus {
+    pub mode: MaintenanceMode,
+    pub message: Option<String>,
+    /// 写入 Retry-After 响应头的秒数
+    pub retry_after_seconds: u64,
+    pub updated_at: Option<DateTime<Utc>>,
+}
@@ -1,4 +1,11 @@
+pub mod device;
+pub mod feature_flag;
+pub mod maintenance;
+pub mod note;
+pub mod notification;
+pub mod oauth_connection;
 pub mod role;
+pub mod session_event;
 pub mod token;
 pub mod token_claims;
 pub mod token_scope;
@@ -0,0 +1,30 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// 示例资源：归属于单个用户的笔记，演示作用域中间件和所有权检查
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Note {
+    pub id: String,
+    pub user_id: String,
+    /// 创建者所属租户，管理员只能访问同一租户的笔记
+    #[serde(default)]
+    pub tenant_id: Option<String>,
+    pub title: String,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Note {
+    pub fn new(user_id: String, tenant_id: Option<String>, title: String, content: String) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            user_id,
+            tenant_id,
+            title,
+            content,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+}
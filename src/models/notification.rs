@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// 用户的邮件通知偏好，以对象形式存储在 users.notification_preferences
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct NotificationPreferences {
+    /// 关闭后仍会收到关键安全提醒（如新设备登录），只屏蔽一般性的安全通知
+    pub security_alerts: bool,
+    /// 资料变更、会话信任等账户动态
+    pub account_activity: bool,
+    /// 产品更新和推广邮件；本服务不发送此类邮件，偏好供外部营销系统读取
+    pub product_emails: bool,
+}
+
+impl Default for NotificationPreferences {
+    fn default() -> Self {
+        Self {
+            security_alerts: true,
+            account_activity: true,
+            product_emails: false,
+        }
+    }
+}
+
+/// 邮件类别，决定是否需要参考用户的通知偏好
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmailCategory {
+    /// 验证邮箱、重置密码等用户主动触发的邮件
+    Transactional,
+    /// 新设备登录等关键安全提醒
+    CriticalSecurity,
+    /// 密码修改等一般性安全通知
+    Security,
+    /// 会话信任变更等账户动态
+    AccountActivity,
+}
+
+impl NotificationPreferences {
+    pub fn allows(&self, category: EmailCategory) -> bool {
+        match category {
+            EmailCategory::Transactional | EmailCategory::CriticalSecurity => true,
+            EmailCategory::Security => self.security_alerts,
+            EmailCategory::AccountActivity => self.account_activity,
+        }
+    }
+}
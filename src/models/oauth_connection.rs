@@ -0,0 +1,15 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// 用户绑定的第三方登录身份，同一用户、提供方和提供方用户 id 的组合唯一
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OAuthConnection {
+    pub id: String,
+    pub user_id: String,
+    /// 提供方名称，统一小写，例如 github、google
+    pub provider: String,
+    /// 提供方返回的用户唯一标识
+    pub subject: String,
+    pub email: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
@@ -1,19 +1,48 @@
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// 数据库、JWT 声明和 API 中统一使用小写字符串，例如 "admin" / "moderator"
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Role {
     Admin,
+    Moderator,
+    Support,
     User,
+    /// 应用自定义的角色，权限范围与 User 相同，需要更多权限时通过自定义 scope 授予
+    Custom(String),
 }
 
 impl Role {
     pub fn to_str(&self) -> &str {
         match self {
-            Role::Admin => "Admin",
-            Role::User => "User",
+            Role::Admin => "admin",
+            Role::Moderator => "moderator",
+            Role::Support => "support",
+            Role::User => "user",
+            Role::Custom(role) => role,
         }
     }
+    /// 不区分大小写，兼容统一为小写之前写入数据库和签发到令牌中的 "Admin" / "User"；
+    /// 其他名称视为自定义角色，只允许字母、数字、`-` 和 `_`
+    pub fn parse(role: &str) -> Option<Role> {
+        let role = role.to_ascii_lowercase();
+        match role.as_str() {
+            "admin" => Some(Role::Admin),
+            "moderator" => Some(Role::Moderator),
+            "support" => Some(Role::Support),
+            "user" => Some(Role::User),
+            _ if is_valid_custom_role(&role) => Some(Role::Custom(role)),
+            _ => None,
+        }
+    }
+}
+
+fn is_valid_custom_role(role: &str) -> bool {
+    (1..=32).contains(&role.len())
+        && role.starts_with(|c: char| c.is_ascii_lowercase())
+        && role
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
 }
 
 impl fmt::Display for Role {
@@ -21,3 +50,86 @@ impl fmt::Display for Role {
         write!(f, "{}", self.to_str())
     }
 }
+
+impl Serialize for Role {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.to_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Role {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        // 已存储或已签发的值原样保留，不因命名规则收紧而无法读取
+        let role = String::deserialize(deserializer)?;
+        Ok(Role::parse(&role).unwrap_or(Role::Custom(role)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::user::User;
+
+    #[test]
+    fn serializes_as_lowercase() {
+        assert_eq!(serde_json::to_value(Role::Admin).unwrap(), "admin");
+        assert_eq!(serde_json::to_value(Role::User).unwrap(), "user");
+        assert_eq!(Role::Admin.to_string(), "admin");
+        assert_eq!(
+            serde_json::to_value(Role::Custom("billing".to_string())).unwrap(),
+            "billing"
+        );
+    }
+
+    #[test]
+    fn parse_rejects_malformed_custom_roles() {
+        assert_eq!(Role::parse("Support"), Some(Role::Support));
+        assert_eq!(
+            Role::parse("Billing"),
+            Some(Role::Custom("billing".to_string()))
+        );
+        assert_eq!(Role::parse("billing team"), None);
+        assert_eq!(Role::parse(""), None);
+    }
+
+    #[test]
+    fn deserializes_current_and_legacy_forms() {
+        for raw in ["\"admin\"", "\"Admin\"", "\"ADMIN\""] {
+            assert_eq!(serde_json::from_str::<Role>(raw).unwrap(), Role::Admin);
+        }
+        assert_eq!(
+            serde_json::from_str::<Role>("\"User\"").unwrap(),
+            Role::User
+        );
+        assert_eq!(
+            serde_json::from_str::<Role>("\"Moderator\"").unwrap(),
+            Role::Moderator
+        );
+        assert_eq!(
+            serde_json::from_str::<Role>("\"billing\"").unwrap(),
+            Role::Custom("billing".to_string())
+        );
+    }
+
+    // 用户记录通过 serde 写入和读出数据库，存储值必须与 to_str 一致
+    #[test]
+    fn admin_user_round_trips_through_storage() {
+        let mut user = User::new(
+            "user-1".to_string(),
+            "Admin".to_string(),
+            "admin@example.com".to_string(),
+            "hash".to_string(),
+        );
+        user.role = Role::Admin;
+        let stored = serde_json::to_value(&user).unwrap();
+        assert_eq!(stored["role"], Role::Admin.to_str());
+        let read_back: User = serde_json::from_value(stored).unwrap();
+        assert_eq!(read_back.role, Role::Admin);
+    }
+}
@@ -0,0 +1,25 @@
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SessionEvent {
+    /// 用户的所有会话被一次性撤销时 `session_id` 为 `None`
+    SessionRevoked {
+        user_id: String,
+        session_id: Option<String>,
+    },
+}
+
+impl SessionEvent {
+    pub fn session_revoked(user_id: String, session_id: Option<String>) -> Self {
+        Self::SessionRevoked {
+            user_id,
+            session_id,
+        }
+    }
+    pub fn user_id(&self) -> &str {
+        match self {
+            SessionEvent::SessionRevoked { user_id, .. } => user_id,
+        }
+    }
+}
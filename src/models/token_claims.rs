@@ -1,7 +1,64 @@
 use std::collections::HashMap;
 
 use crate::models::{role::Role, token_scope::TokenScope, token_type::TokenType};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+/// 签发方和受众，校验时要求与之一致
+pub const TOKEN_ISSUER: &str = "homeryland-api";
+pub const TOKEN_AUDIENCE: &str = "homeryland-client";
+
+/// 存放租户 id 的自定义声明键名
+pub const TENANT_ID_CLAIM: &str = "tenant_id";
+
+/// 存放设备绑定值的自定义声明键名，见 `DeviceInfo::binding`
+pub const DEVICE_BINDING_CLAIM: &str = "device_binding";
+
+/// 自定义声明允许的值类型，用于 EXTRA_CLAIMS_SCHEMA
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExtraClaimType {
+    String,
+    Number,
+    Bool,
+    Array,
+    Object,
+    Any,
+}
+
+impl ExtraClaimType {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "string" => Some(ExtraClaimType::String),
+            "number" => Some(ExtraClaimType::Number),
+            "bool" => Some(ExtraClaimType::Bool),
+            "array" => Some(ExtraClaimType::Array),
+            "object" => Some(ExtraClaimType::Object),
+            "any" => Some(ExtraClaimType::Any),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExtraClaimType::String => "string",
+            ExtraClaimType::Number => "number",
+            ExtraClaimType::Bool => "bool",
+            ExtraClaimType::Array => "array",
+            ExtraClaimType::Object => "object",
+            ExtraClaimType::Any => "any",
+        }
+    }
+
+    pub fn matches(&self, value: &serde_json::Value) -> bool {
+        match self {
+            ExtraClaimType::String => value.is_string(),
+            ExtraClaimType::Number => value.is_number(),
+            ExtraClaimType::Bool => value.is_boolean(),
+            ExtraClaimType::Array => value.is_array(),
+            ExtraClaimType::Object => value.is_object(),
+            ExtraClaimType::Any => true,
+        }
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TokenClaims {
@@ -15,6 +72,7 @@ pub struct TokenClaims {
     pub email: Option<String>,
     pub role: Option<Role>,
     pub scopes: Vec<TokenScope>,
+    #[serde(default)]
     pub extra: HashMap<String, serde_json::Value>,
 }
 
@@ -32,8 +90,8 @@ impl TokenClaims {
             token_type: TokenType::Access,
             iat,
             exp,
-            iss: Some("homeryland-api".to_string()),
-            aud: Some("homeryland-client".to_string()),
+            iss: Some(TOKEN_ISSUER.to_string()),
+            aud: Some(TOKEN_AUDIENCE.to_string()),
             jti: Some(uuid::Uuid::new_v4().to_string()),
             email: Some(email),
             role: Some(role),
@@ -47,8 +105,8 @@ impl TokenClaims {
             token_type: TokenType::Refresh,
             iat,
             exp,
-            iss: Some("homeryland-api".to_string()),
-            aud: Some("homeryland-client".to_string()),
+            iss: Some(TOKEN_ISSUER.to_string()),
+            aud: Some(TOKEN_AUDIENCE.to_string()),
             jti: Some(uuid::Uuid::new_v4().to_string()),
             email: None,
             role: None,
@@ -67,8 +125,8 @@ impl TokenClaims {
             token_type: TokenType::EmailVerification,
             iat,
             exp,
-            iss: Some("homeryland-api".to_string()),
-            aud: Some("homeryland-client".to_string()),
+            iss: Some(TOKEN_ISSUER.to_string()),
+            aud: Some(TOKEN_AUDIENCE.to_string()),
             jti: Some(uuid::Uuid::new_v4().to_string()),
             email: Some(email),
             role: None,
@@ -82,8 +140,8 @@ impl TokenClaims {
             token_type: TokenType::PasswordReset,
             iat,
             exp,
-            iss: Some("homeryland-api".to_string()),
-            aud: Some("homeryland-client".to_string()),
+            iss: Some(TOKEN_ISSUER.to_string()),
+            aud: Some(TOKEN_AUDIENCE.to_string()),
             jti: Some(uuid::Uuid::new_v4().to_string()),
             email: Some(email),
             role: None,
@@ -91,8 +149,21 @@ impl TokenClaims {
             extra: HashMap::new(),
         }
     }
-    pub fn is_expired(&self) -> bool {
-        chrono::Utc::now().timestamp() > self.exp
+    /// 读取自定义声明并反序列化为目标类型，不存在或类型不匹配时返回 None
+    pub fn get_extra<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        self.extra
+            .get(key)
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+    }
+    pub fn tenant_id(&self) -> Option<String> {
+        self.get_extra(TENANT_ID_CLAIM)
+    }
+    pub fn device_binding(&self) -> Option<String> {
+        self.get_extra(DEVICE_BINDING_CLAIM)
+    }
+    /// leeway 为容忍的时钟偏差秒数，应与签名校验时使用的值一致
+    pub fn is_expired(&self, leeway: u64) -> bool {
+        chrono::Utc::now().timestamp() > self.exp.saturating_add_unsigned(leeway)
     }
     pub fn has_scope(&self, scope: &TokenScope) -> bool {
         self.scopes.contains(scope)
@@ -116,7 +187,17 @@ impl TokenClaims {
                 TokenScope::AdminWrite,
                 TokenScope::AdminDelete,
             ],
-            Role::User => vec![
+            // 在普通用户的基础上增加删除权限，不能访问管理接口
+            Role::Moderator => vec![
+                TokenScope::Read,
+                TokenScope::Write,
+                TokenScope::Delete,
+                TokenScope::UserRead,
+                TokenScope::UserWrite,
+            ],
+            // 只读，用于协助排查用户问题
+            Role::Support => vec![TokenScope::Read, TokenScope::UserRead],
+            Role::User | Role::Custom(_) => vec![
                 TokenScope::Read,
                 TokenScope::Write,
                 TokenScope::UserRead,
@@ -18,6 +18,39 @@ pub enum TokenScope {
 }
 
 impl TokenScope {
+    /// 所有内置权限范围，不包含自定义范围
+    pub const KNOWN: [TokenScope; 12] = [
+        TokenScope::Read,
+        TokenScope::Write,
+        TokenScope::Delete,
+        TokenScope::UserRead,
+        TokenScope::UserWrite,
+        TokenScope::UserDelete,
+        TokenScope::AdminRead,
+        TokenScope::AdminWrite,
+        TokenScope::AdminDelete,
+        TokenScope::Refresh,
+        TokenScope::EmailVerify,
+        TokenScope::PasswordReset,
+    ];
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            TokenScope::Read => "Read the caller's own resources",
+            TokenScope::Write => "Create and update the caller's own resources",
+            TokenScope::Delete => "Delete the caller's own resources",
+            TokenScope::UserRead => "Read user profiles",
+            TokenScope::UserWrite => "Update user profiles",
+            TokenScope::UserDelete => "Delete users",
+            TokenScope::AdminRead => "Read admin data such as stats, users and sessions",
+            TokenScope::AdminWrite => "Change admin settings, roles and sessions",
+            TokenScope::AdminDelete => "Perform destructive admin operations",
+            TokenScope::Refresh => "Exchange a refresh token for new tokens",
+            TokenScope::EmailVerify => "Verify an email address",
+            TokenScope::PasswordReset => "Reset a password",
+            TokenScope::Custom(_) => "Custom scope",
+        }
+    }
     pub fn to_str(&self) -> String {
         match self {
             TokenScope::Read => "read".to_string(),
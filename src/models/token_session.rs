@@ -1,6 +1,11 @@
-use chrono::{DateTime, Utc};
+use std::net::IpAddr;
+
+use chrono::{DateTime, Duration, Utc};
+use ipnet::IpNet;
 use serde::{Deserialize, Serialize};
 
+use crate::models::{device::DeviceInfo, role::Role, token_claims::TokenClaims};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TokenSession {
     pub id: String,
@@ -10,9 +15,31 @@ pub struct TokenSession {
     pub created_at: DateTime<Utc>,
     pub last_active_at: DateTime<Utc>,
     pub is_active: bool,
-    pub device_info: Option<String>,
+    /// 刷新令牌的过期时间，之后会话无法再续期
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+    pub device_info: Option<DeviceInfo>,
     pub ip_address: Option<String>,
     pub location: Option<String>,
+    #[serde(default)]
+    pub trusted: bool,
+    #[serde(default)]
+    pub trusted_until: Option<DateTime<Utc>>,
+    /// 刷新时重新写入访问令牌的租户 id
+    #[serde(default)]
+    pub tenant_id: Option<String>,
+    /// 见 `DeviceInfo::fingerprint`，用于识别新设备登录
+    #[serde(default)]
+    pub device_fingerprint: Option<String>,
+    /// 启用设备绑定时写入访问令牌的绑定值，刷新时沿用
+    #[serde(default)]
+    pub device_binding: Option<String>,
+    /// opaque 访问令牌的 SHA-256，JWT 模式下为空
+    #[serde(default)]
+    pub access_token_hash: Option<String>,
+    /// opaque 访问令牌对应的声明，解析令牌时直接返回
+    #[serde(default)]
+    pub access_claims: Option<TokenClaims>,
 }
 
 impl TokenSession {
@@ -25,9 +52,186 @@ impl TokenSession {
             created_at: Utc::now(),
             last_active_at: Utc::now(),
             is_active: true,
+            expires_at: None,
             device_info: None,
             ip_address: None,
             location: None,
+            trusted: false,
+            trusted_until: None,
+            tenant_id: None,
+            device_fingerprint: None,
+            device_binding: None,
+            access_token_hash: None,
+            access_claims: None,
+        }
+    }
+
+    /// 清理任务会删除的会话：已撤销、刷新令牌已过期，或没有 expires_at 的旧记录超过保留期未活跃。
+    /// 与 `TokenRepository::cleanup_expired_sessions` 的删除条件保持一致
+    pub fn is_due_for_cleanup(&self, now: DateTime<Utc>, legacy_retention: Duration) -> bool {
+        match self.expires_at {
+            _ if !self.is_active => true,
+            Some(expires_at) => expires_at < now,
+            None => self.last_active_at < now - legacy_retention,
+        }
+    }
+
+    /// 受信任且未过期的会话可以跳过二次验证
+    pub fn is_trusted(&self) -> bool {
+        self.trusted
+            && self
+                .trusted_until
+                .map(|until| until > Utc::now())
+                .unwrap_or(false)
+    }
+}
+
+/// 刷新或轮换令牌时写回会话的字段
+#[derive(Debug, Clone)]
+pub struct SessionTokenUpdate {
+    pub access_token_jti: String,
+    pub refresh_token_jti: String,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub access_token_hash: Option<String>,
+    pub access_claims: Option<TokenClaims>,
+}
+
+/// 管理员批量撤销会话时的匹配条件
+#[derive(Debug, Clone)]
+pub enum SessionRevocationCriteria {
+    CreatedBefore(DateTime<Utc>),
+    IpAddress(String),
+    UserRole(Role),
+}
+
+/// 按来源 IP 查找会话：单个地址精确匹配，网段支持 IPv4 与 IPv6 的任意前缀长度
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionIpFilter {
+    Exact(String),
+    Subnet(IpNet),
+}
+
+impl SessionIpFilter {
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        if !value.contains('/') {
+            return value
+                .parse::<IpAddr>()
+                .ok()
+                .map(|ip| SessionIpFilter::Exact(ip.to_string()));
+        }
+        let network = value.parse::<IpNet>().ok()?.trunc();
+        if network.prefix_len() == network.max_prefix_len() {
+            return Some(SessionIpFilter::Exact(network.addr().to_string()));
         }
+        Some(SessionIpFilter::Subnet(network))
+    }
+
+    /// 会话记录的 IP 是否落在过滤条件内，无法解析的地址视为不匹配
+    pub fn matches(&self, ip_address: &str) -> bool {
+        let Ok(ip) = ip_address.parse::<IpAddr>() else {
+            return false;
+        };
+        match self {
+            SessionIpFilter::Exact(expected) => expected
+                .parse::<IpAddr>()
+                .is_ok_and(|expected| expected == ip),
+            SessionIpFilter::Subnet(network) => network.contains(&ip),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session_with(
+        is_active: bool,
+        expires_at: Option<DateTime<Utc>>,
+        last_active_at: DateTime<Utc>,
+    ) -> TokenSession {
+        let mut session = TokenSession::new(
+            "user-1".to_string(),
+            "access-jti".to_string(),
+            "refresh-jti".to_string(),
+        );
+        session.is_active = is_active;
+        session.expires_at = expires_at;
+        session.last_active_at = last_active_at;
+        session
+    }
+
+    #[test]
+    fn only_expired_or_revoked_sessions_are_cleaned_up() {
+        let now = Utc::now();
+        let retention = Duration::days(30);
+        // (是否活跃, 距过期的小时数, 最后活跃距今的天数, 是否应被清理)
+        let cases = [
+            (true, Some(24 * 7), 0, false),
+            (true, Some(1), 6, false),
+            (true, Some(-24), 8, true),
+            (false, Some(24 * 7), 0, true),
+            // 没有 expires_at 的旧记录按保留期判断
+            (true, None, 29, false),
+            (true, None, 31, true),
+        ];
+        for (is_active, expires_in_hours, idle_days, expected) in cases {
+            let session = session_with(
+                is_active,
+                expires_in_hours.map(|hours| now + Duration::hours(hours)),
+                now - Duration::days(idle_days),
+            );
+            assert_eq!(
+                session.is_due_for_cleanup(now, retention),
+                expected,
+                "{session:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn active_session_before_its_expiry_survives_cleanup() {
+        let now = Utc::now();
+        // 最后活跃早于保留期，但刷新令牌还没过期，仍然不能删除
+        let session = session_with(
+            true,
+            Some(now + Duration::days(1)),
+            now - Duration::days(40),
+        );
+        assert!(!session.is_due_for_cleanup(now, Duration::days(30)));
+    }
+
+    #[test]
+    fn single_address_is_exact() {
+        assert_eq!(
+            SessionIpFilter::parse("10.0.0.1"),
+            Some(SessionIpFilter::Exact("10.0.0.1".to_string()))
+        );
+        assert_eq!(
+            SessionIpFilter::parse("2001:db8::1/128"),
+            Some(SessionIpFilter::Exact("2001:db8::1".to_string()))
+        );
+    }
+
+    #[test]
+    fn ipv4_subnet_matches_any_prefix_length() {
+        let filter = SessionIpFilter::parse("192.168.4.0/22").unwrap();
+        assert!(filter.matches("192.168.7.255"));
+        assert!(!filter.matches("192.168.8.1"));
+        assert!(!filter.matches("2001:db8::1"));
+    }
+
+    #[test]
+    fn ipv6_subnet_matches() {
+        let filter = SessionIpFilter::parse("2001:db8:abcd::/48").unwrap();
+        assert!(filter.matches("2001:db8:abcd:12::1"));
+        assert!(!filter.matches("2001:db8:abce::1"));
+        assert!(!filter.matches("192.168.1.1"));
+    }
+
+    #[test]
+    fn invalid_input_is_rejected() {
+        assert_eq!(SessionIpFilter::parse("10.0.0.0/33"), None);
+        assert_eq!(SessionIpFilter::parse("not-an-ip"), None);
     }
 }
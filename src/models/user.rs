@@ -1,4 +1,4 @@
-use crate::models::role::Role;
+use crate::models::{notification::NotificationPreferences, role::Role};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
@@ -12,6 +12,29 @@ pub struct User {
     pub verified: bool,
     pub created_at: Option<DateTime<Utc>>,
     pub updated_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub last_login_at: Option<DateTime<Utc>>,
+    /// 待确认的新邮箱，确认前旧邮箱仍然有效
+    #[serde(default)]
+    pub pending_email: Option<String>,
+    /// 之前使用过的密码哈希，最新的在前，不包含当前密码
+    #[serde(default)]
+    pub password_history: Vec<String>,
+    #[serde(default)]
+    pub failed_login_attempts: u32,
+    #[serde(default)]
+    pub locked_until: Option<DateTime<Utc>>,
+    /// 所属租户，None 表示未启用多租户时的全局用户
+    #[serde(default)]
+    pub tenant_id: Option<String>,
+    #[serde(default)]
+    pub notification_preferences: NotificationPreferences,
+    /// 账户恢复码的哈希，每个恢复码只能使用一次
+    #[serde(default)]
+    pub recovery_codes: Vec<String>,
+    /// 申请删除账户的时间，宽限期内可以重新激活，过期后由后台任务彻底删除
+    #[serde(default)]
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,6 +47,15 @@ pub struct UserPublicInfo {
     pub created_at: Option<DateTime<Utc>>,
 }
 
+/// 管理员用户列表的过滤条件，search 同时匹配名称和邮箱
+#[derive(Debug, Clone, Default)]
+pub struct UserListFilter {
+    pub search: Option<String>,
+    pub role: Option<Role>,
+    pub verified: Option<bool>,
+    pub tenant_id: Option<String>,
+}
+
 impl User {
     pub fn new(id: String, name: String, email: String, password: String) -> Self {
         Self {
@@ -35,8 +67,22 @@ impl User {
             verified: false,
             created_at: Some(Utc::now()),
             updated_at: Some(Utc::now()),
+            last_login_at: None,
+            pending_email: None,
+            password_history: Vec::new(),
+            failed_login_attempts: 0,
+            locked_until: None,
+            tenant_id: None,
+            notification_preferences: NotificationPreferences::default(),
+            recovery_codes: Vec::new(),
+            deleted_at: None,
         }
     }
+    pub fn is_locked(&self) -> bool {
+        self.locked_until
+            .map(|until| until > Utc::now())
+            .unwrap_or(false)
+    }
     pub fn is_admin(&self) -> bool {
         matches!(self.role, Role::Admin)
     }
@@ -0,0 +1,44 @@
+use crate::handlers::admin::{
+    admin_resend_verification, admin_revoke_user_sessions, bulk_revoke_sessions, cleanup_expired_sessions, get_system_stats,
+    get_maintenance, get_session_by_id, get_user_by_id, list_feature_flags, list_scopes, list_users, search_sessions,
+    unlock_user, update_feature_flag, update_maintenance, update_user_role,
+};
+use crate::middlewares::auth::{admin_middleware, auth_middleware, require_scopes};
+use crate::middlewares::ip_filter::admin_ip_filter_middleware;
+use crate::middlewares::rate_limit::user_rate_limit_middleware;
+use crate::models::token_scope::TokenScope;
+use crate::state::AppState;
+use axum::Router;
+use axum::middleware::{from_fn, from_fn_with_state};
+use axum::routing::{get, post, put};
+use std::sync::Arc;
+
+pub fn admin_routes(app_state: Arc<AppState>) -> Router<Arc<AppState>> {
+    let router = Router::new()
+        .route("/stats", get(get_system_stats))
+        .route("/users", get(list_users))
+        .route("/users/{id}", get(get_user_by_id))
+        .route("/users/{id}/unlock", post(unlock_user))
+        .route("/users/{id}/resend-verification", post(admin_resend_verification))
+        .route("/users/revoke-sessions", post(admin_revoke_user_sessions))
+        .route("/users/role", put(update_user_role))
+        .route("/sessions", get(search_sessions))
+        .route("/sessions/{id}", get(get_session_by_id))
+        .route("/sessions/cleanup", post(cleanup_expired_sessions))
+        .route("/sessions/revoke", post(bulk_revoke_sessions))
+        .route(
+            "/scopes",
+            get(list_scopes).route_layer(from_fn(require_scopes(vec![TokenScope::AdminRead]))),
+        )
+        .route("/feature-flags", get(list_feature_flags))
+        .route("/feature-flags/{name}", put(update_feature_flag))
+        .route("/maintenance", get(get_maintenance).put(update_maintenance));
+    // 开发用的批量造数接口不会编译进 release 构建
+    #[cfg(debug_assertions)]
+    let router = router.route("/dev/seed-users", post(crate::handlers::dev::seed_users));
+    router
+        .route_layer(from_fn(admin_middleware))
+        .route_layer(from_fn_with_state(app_state.clone(), user_rate_limit_middleware))
+        .route_layer(from_fn_with_state(app_state.clone(), auth_middleware))
+        .route_layer(from_fn_with_state(app_state, admin_ip_filter_middleware))
+}
@@ -0,0 +1,199 @@
+use crate::config::password::PasswordPolicy;
+use crate::dtos::auth::{
+    ChangeEmailRequest, ChangePasswordRequest, ConfirmEmailChangeRequest, ForgotPasswordRequest,
+    IntrospectRequest, IntrospectResponse, LoginRequest, LoginResponse, LogoutRequest,
+    LogoutResponse, ReactivateAccountRequest, RecoverAccountRequest, RecoverAccountResponse,
+    RecoveryCodesResponse, RefreshTokenRequest, RefreshTokenResponse,
+    RegenerateRecoveryCodesRequest, RegisterRequest, ResetPasswordRequest,
+    RevokeRefreshTokenRequest, TokenInfoResponse, UserInfo,
+};
+use crate::dtos::note::{CreateNoteRequest, NoteResponse, UpdateNoteRequest};
+use crate::dtos::pagination::{PaginatedResponse, PaginationMeta};
+use crate::dtos::response::ResponseMeta;
+use crate::dtos::user::{
+    ConnectionInfo, DeleteAccountRequest, DeleteAccountResponse, ExportedProfile, ExportedSession,
+    ProfileResponse, ReplaceProfileRequest, SessionInfo, TrustSessionRequest, UpdateProfileRequest,
+    UserDataExport,
+};
+use crate::errors::response::{ErrorDetail, ErrorResponse};
+use crate::handlers::admin::{
+    AdminSessionDetail, AdminSessionInfo, AdminUserInfo, BulkRevokeSessionsRequest, ScopeInfo,
+    SessionOwnerInfo, SystemStats, UpdateFeatureFlagRequest, UpdateMaintenanceRequest,
+};
+use crate::handlers::health::{
+    BuildInfo, DatabaseCheck, ReadinessChecks, ReadinessResponse, TokenCleanupCheck,
+};
+use crate::handlers::{admin, auth, health, note, user, ws};
+use crate::models::feature_flag::FeatureFlag;
+use crate::models::maintenance::{MaintenanceMode, MaintenanceStatus};
+use crate::models::notification::NotificationPreferences;
+use axum::Router;
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+use utoipa_swagger_ui::SwaggerUi;
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Axum SurrealDB Template API"),
+    paths(
+        auth::register,
+        auth::login,
+        auth::refresh_token,
+        auth::logout,
+        auth::revoke_refresh_token,
+        auth::change_password,
+        auth::change_email,
+        auth::confirm_email_change,
+        auth::introspect,
+        auth::token_info,
+        auth::password_policy,
+        auth::forgot_password,
+        auth::reset_password,
+        auth::recover_account,
+        auth::reactivate_account,
+        auth::regenerate_recovery_codes,
+        auth::verify_email,
+        auth::resend_verification_email,
+        auth::get_user_sessions,
+        auth::revoke_session,
+        auth::revoke_all_sessions,
+        auth::trust_session,
+        auth::rotate_current_session,
+        user::get_profile,
+        user::update_profile,
+        user::replace_profile,
+        user::get_notification_preferences,
+        user::update_notification_preferences,
+        user::export_user_data,
+        user::delete_account,
+        user::list_connections,
+        user::unlink_connection,
+        note::list_notes,
+        note::create_note,
+        note::get_note,
+        note::update_note,
+        note::delete_note,
+        ws::ws_handler,
+        admin::get_system_stats,
+        admin::stream_system_stats,
+        admin::list_users,
+        admin::get_user_by_id,
+        admin::unlock_user,
+        admin::admin_resend_verification,
+        admin::admin_revoke_user_sessions,
+        admin::update_user_role,
+        admin::search_sessions,
+        admin::get_session_by_id,
+        admin::cleanup_expired_sessions,
+        admin::bulk_revoke_sessions,
+        admin::list_scopes,
+        admin::list_feature_flags,
+        admin::update_feature_flag,
+        admin::get_maintenance,
+        admin::update_maintenance,
+        health::health,
+        health::ready,
+        health::info,
+    ),
+    components(schemas(
+        RegisterRequest,
+        LoginRequest,
+        LoginResponse,
+        RefreshTokenRequest,
+        RefreshTokenResponse,
+        LogoutRequest,
+        LogoutResponse,
+        RevokeRefreshTokenRequest,
+        ChangePasswordRequest,
+        ChangeEmailRequest,
+        ConfirmEmailChangeRequest,
+        IntrospectRequest,
+        IntrospectResponse,
+        TokenInfoResponse,
+        PasswordPolicy,
+        ForgotPasswordRequest,
+        ResetPasswordRequest,
+        RegenerateRecoveryCodesRequest,
+        RecoveryCodesResponse,
+        RecoverAccountRequest,
+        ReactivateAccountRequest,
+        RecoverAccountResponse,
+        UserInfo,
+        TrustSessionRequest,
+        ProfileResponse,
+        UserDataExport,
+        DeleteAccountRequest,
+        DeleteAccountResponse,
+        UpdateProfileRequest,
+        ReplaceProfileRequest,
+        ConnectionInfo,
+        ExportedProfile,
+        ExportedSession,
+        NotificationPreferences,
+        CreateNoteRequest,
+        UpdateNoteRequest,
+        NoteResponse,
+        PaginatedResponse<NoteResponse>,
+        SystemStats,
+        AdminUserInfo,
+        PaginationMeta,
+        PaginatedResponse<AdminUserInfo>,
+        AdminSessionInfo,
+        PaginatedResponse<AdminSessionInfo>,
+        AdminSessionDetail,
+        SessionOwnerInfo,
+        ScopeInfo,
+        SessionInfo,
+        PaginatedResponse<SessionInfo>,
+        BulkRevokeSessionsRequest,
+        FeatureFlag,
+        UpdateFeatureFlagRequest,
+        MaintenanceMode,
+        MaintenanceStatus,
+        UpdateMaintenanceRequest,
+        ReadinessResponse,
+        ReadinessChecks,
+        DatabaseCheck,
+        TokenCleanupCheck,
+        BuildInfo,
+        ErrorResponse,
+        ErrorDetail,
+        ResponseMeta,
+    )),
+    modifiers(&BearerSecurity),
+    tags(
+        (name = "auth", description = "Registration, login and credential management"),
+        (name = "users", description = "Current user profile"),
+        (name = "sessions", description = "Session listing, revocation and notifications"),
+        (name = "notes", description = "Example resource guarded by read/write/delete scopes"),
+        (name = "admin", description = "Administrative endpoints, admin scopes required"),
+        (name = "health", description = "Liveness and readiness probes")
+    )
+)]
+pub struct ApiDoc;
+
+struct BearerSecurity;
+
+impl Modify for BearerSecurity {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer_auth",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .build(),
+            ),
+        );
+    }
+}
+
+/// Swagger UI 返回 HTML，因此不经过 JSON accept 校验
+pub fn docs_routes() -> Router {
+    #[allow(unused_mut)]
+    let mut openapi = ApiDoc::openapi();
+    #[cfg(debug_assertions)]
+    openapi.merge(crate::handlers::dev::DevApiDoc::openapi());
+    Router::new().merge(SwaggerUi::new("/api/v1/docs").url("/api/v1/docs/openapi.json", openapi))
+}
@@ -1,13 +1,26 @@
+use crate::routes::admin::admin_routes;
+use crate::routes::docs::docs_routes;
+use crate::routes::notes::note_routes;
 use crate::routes::protected::protected_routes;
 use crate::routes::public::public_routes;
+use crate::routes::stream::stream_routes;
+use crate::extractors::client_ip::ClientIpKeyExtractor;
+use crate::handlers::fallback::{method_not_allowed, not_found};
+use crate::middlewares::client_ip::client_ip_middleware;
+use crate::middlewares::envelope::response_envelope_middleware;
+use crate::middlewares::in_flight::in_flight_middleware;
+use crate::middlewares::locale::locale_middleware;
+use crate::middlewares::logging::body_logging_middleware;
+use crate::middlewares::maintenance::maintenance_middleware;
+use crate::middlewares::tenant::tenant_database_middleware;
 use crate::state::AppState;
-use axum::http::header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE};
 use axum::http::{HeaderValue, Method};
-use axum::{Extension, Router};
+use axum::{Extension, Router, middleware};
 use tower_governor::governor::GovernorConfigBuilder;
 use tower_governor::GovernorLayer;
 use std::sync::Arc;
 use std::time::Duration;
+use tower_http::compression::CompressionLayer;
 use tower_http::cors::CorsLayer;
 use tower_http::timeout::TimeoutLayer;
 use tower_http::trace;
@@ -15,38 +28,86 @@ use tower_http::trace::TraceLayer;
 use tower_http::validate_request::ValidateRequestHeaderLayer;
 use tracing::Level;
 
+pub mod admin;
+pub mod docs;
+pub mod notes;
 pub mod protected;
 pub mod public;
+pub mod stream;
 
 pub fn all_routes(app_state: Arc<AppState>) -> Router {
     let frontend_url = app_state.env.frontend_config.frontend_url.clone();
+    let cors_allow_headers = app_state.env.frontend_config.cors_allow_headers.clone();
 
     let governor_conf = GovernorConfigBuilder::default()
+        .key_extractor(ClientIpKeyExtractor)
         .per_second(2)
         .burst_size(10)
         .finish()
         .unwrap();
 
-    let api_routes = Router::new()
+    // 公开路由（认证表单、WebSocket、健康检查）不强制 Accept: application/json
+    let mut api_routes = Router::new()
+        .merge(protected_routes(app_state.clone()))
+        .merge(note_routes(app_state.clone()))
+        .nest("/admin", admin_routes(app_state.clone()))
+        .layer(ValidateRequestHeaderLayer::accept("application/json"))
         .merge(public_routes())
-        .merge(protected_routes());
+        .merge(stream_routes(app_state.clone()));
+
+    // 默认关闭以兼容现有客户端，文档路由不受影响
+    if app_state.env.server_config.wrap_responses {
+        api_routes = api_routes.layer(middleware::from_fn(response_envelope_middleware));
+    }
+
+    let mut router = Router::new().nest("/api/v1", api_routes)
+        .with_state(app_state.clone())
+        .merge(docs_routes())
+        .fallback(not_found)
+        .method_not_allowed_fallback(method_not_allowed)
+        .layer(middleware::from_fn_with_state(app_state.clone(), maintenance_middleware))
+        .layer(middleware::from_fn_with_state(app_state.clone(), tenant_database_middleware))
+        .layer(middleware::from_fn(locale_middleware));
+
+    if app_state.env.server_config.log_bodies {
+        router = router.layer(middleware::from_fn(body_logging_middleware));
+    }
 
-    Router::new().nest("/api/v1", api_routes)
+    // 压缩层位于 TraceLayer 内侧，日志记录的是压缩后的响应
+    if app_state.env.server_config.enable_compression {
+        router = router.layer(CompressionLayer::new());
+    }
+
+    router
         .layer(CorsLayer::new()
             .allow_origin(frontend_url.parse::<HeaderValue>().unwrap())
-            .allow_headers([AUTHORIZATION, ACCEPT, CONTENT_TYPE])
-            .allow_methods([Method::GET, Method::POST, Method::PUT]))
+            .allow_headers(cors_allow_headers)
+            // axum 的 get 路由会自动以去掉响应体的方式响应 HEAD，这里只需在 CORS 中放行
+            .allow_methods([Method::GET, Method::HEAD, Method::POST, Method::PUT, Method::PATCH, Method::DELETE])
+            // Cookie 模式下跨域请求需要携带凭据
+            .allow_credentials(true))
         .layer(TraceLayer::new_for_http()
-            .make_span_with(trace::DefaultMakeSpan::new()
-                .level(Level::INFO))
+            // user_id 和 role 先留空，由认证中间件在验证令牌后填入；
+            // 只记录路径，查询参数可能带有令牌（如 WebSocket 的 ?token=）
+            .make_span_with(|request: &axum::extract::Request| {
+                tracing::info_span!(
+                    "request",
+                    method = %request.method(),
+                    path = %request.uri().path(),
+                    version = ?request.version(),
+                    user_id = tracing::field::Empty,
+                    role = tracing::field::Empty,
+                )
+            })
             .on_request(trace::DefaultOnRequest::new()
                 .level(Level::INFO))
             .on_response(trace::DefaultOnResponse::new()
                 .level(Level::INFO)))
         .layer(TimeoutLayer::new(Duration::from_secs(30)))
-        .layer(ValidateRequestHeaderLayer::accept("application/json"))
         .layer(GovernorLayer{
             config: Arc::new(governor_conf)
         })
+        .layer(middleware::from_fn_with_state(app_state.clone(), client_ip_middleware))
+        .layer(middleware::from_fn_with_state(app_state.clone(), in_flight_middleware))
         .layer(Extension(app_state))
 }
\ No newline at end of file
@@ -0,0 +1,32 @@
+use crate::handlers::note::{create_note, delete_note, get_note, list_notes, update_note};
+use crate::middlewares::auth::{
+    auth_middleware, require_delete_scope, require_read_scope, require_write_scope,
+};
+use crate::middlewares::rate_limit::user_rate_limit_middleware;
+use crate::state::AppState;
+use axum::Router;
+use axum::middleware::{from_fn, from_fn_with_state};
+use axum::routing::{delete, get, patch, post};
+use std::sync::Arc;
+
+/// 按 HTTP 方法挂载不同的作用域中间件：GET 需要 read，POST/PATCH 需要 write，DELETE 需要 delete
+pub fn note_routes(app_state: Arc<AppState>) -> Router<Arc<AppState>> {
+    let read_routes = Router::new()
+        .route("/notes", get(list_notes))
+        .route("/notes/{id}", get(get_note))
+        .route_layer(from_fn(require_read_scope));
+    let write_routes = Router::new()
+        .route("/notes", post(create_note))
+        .route("/notes/{id}", patch(update_note))
+        .route_layer(from_fn(require_write_scope));
+    let delete_routes = Router::new()
+        .route("/notes/{id}", delete(delete_note))
+        .route_layer(from_fn(require_delete_scope));
+
+    Router::new()
+        .merge(read_routes)
+        .merge(write_routes)
+        .merge(delete_routes)
+        .route_layer(from_fn_with_state(app_state.clone(), user_rate_limit_middleware))
+        .route_layer(from_fn_with_state(app_state, auth_middleware))
+}
@@ -1,5 +1,68 @@
+use crate::extractors::client_ip::ClientIpKeyExtractor;
+use crate::handlers::auth::{
+    change_email, change_password, get_user_sessions, introspect, logout,
+    regenerate_recovery_codes, resend_verification_email, revoke_all_sessions,
+    revoke_refresh_token, revoke_session, rotate_current_session, token_info, trust_session,
+};
+use crate::handlers::user::{
+    delete_account, export_user_data, get_notification_preferences, get_profile, list_connections,
+    replace_profile, unlink_connection, update_notification_preferences, update_profile,
+};
+use crate::middlewares::auth::auth_middleware;
+use crate::middlewares::rate_limit::user_rate_limit_middleware;
+use crate::state::AppState;
 use axum::Router;
+use axum::middleware::from_fn_with_state;
+use axum::routing::{delete, get, patch, post};
+use std::sync::Arc;
+use tower_governor::GovernorLayer;
+use tower_governor::governor::GovernorConfigBuilder;
+
+pub fn protected_routes(app_state: Arc<AppState>) -> Router<Arc<AppState>> {
+    // 数据导出开销较大，单独限流：每个 IP 每分钟补充一次，最多连续两次
+    let export_governor_conf = GovernorConfigBuilder::default()
+        .key_extractor(ClientIpKeyExtractor)
+        .per_second(60)
+        .burst_size(2)
+        .finish()
+        .unwrap();
 
-pub fn protected_routes() -> Router {
     Router::new()
-}
\ No newline at end of file
+        .route("/auth/logout", post(logout))
+        .route("/auth/revoke-refresh", post(revoke_refresh_token))
+        .route("/auth/change-password", post(change_password))
+        .route("/auth/change-email", post(change_email))
+        .route("/auth/introspect", post(introspect))
+        .route("/auth/token-info", get(token_info))
+        .route("/auth/resend-verification", post(resend_verification_email))
+        .route("/auth/recovery-codes", post(regenerate_recovery_codes))
+        .route(
+            "/users/me",
+            get(get_profile)
+                .patch(update_profile)
+                .put(replace_profile)
+                .delete(delete_account),
+        )
+        .route(
+            "/users/me/notifications",
+            get(get_notification_preferences).put(update_notification_preferences),
+        )
+        .route("/users/me/connections", get(list_connections))
+        .route(
+            "/users/me/connections/{provider}",
+            delete(unlink_connection),
+        )
+        .route(
+            "/users/me/export",
+            get(export_user_data).layer(GovernorLayer {
+                config: Arc::new(export_governor_conf),
+            }),
+        )
+        .route("/sessions", get(get_user_sessions))
+        .route("/sessions/revoke", post(revoke_session))
+        .route("/sessions/revoke-all", post(revoke_all_sessions))
+        .route("/sessions/current/rotate", post(rotate_current_session))
+        .route("/sessions/{id}/trust", patch(trust_session))
+        .route_layer(from_fn_with_state(app_state.clone(), user_rate_limit_middleware))
+        .route_layer(from_fn_with_state(app_state, auth_middleware))
+}
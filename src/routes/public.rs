@@ -1,5 +1,28 @@
+use crate::handlers::auth::{
+    confirm_email_change, forgot_password, login, password_policy, reactivate_account,
+    recover_account, refresh_token, register, reset_password, verify_email,
+};
+use crate::handlers::health::{health, info, ready};
+use crate::handlers::ws::ws_handler;
+use crate::state::AppState;
 use axum::Router;
+use axum::routing::{get, post};
+use std::sync::Arc;
 
-pub fn public_routes() -> Router {
+pub fn public_routes() -> Router<Arc<AppState>> {
     Router::new()
-}
\ No newline at end of file
+        .route("/auth/register", post(register))
+        .route("/auth/login", post(login))
+        .route("/auth/refresh", post(refresh_token))
+        .route("/auth/password-policy", get(password_policy))
+        .route("/auth/forgot-password", post(forgot_password))
+        .route("/auth/reset-password", post(reset_password))
+        .route("/auth/recover", post(recover_account))
+        .route("/auth/reactivate", post(reactivate_account))
+        .route("/auth/verify-email", post(verify_email))
+        .route("/auth/confirm-email-change", post(confirm_email_change))
+        .route("/ws", get(ws_handler))
+        .route("/health", get(health))
+        .route("/info", get(info))
+        .route("/ready", get(ready))
+}
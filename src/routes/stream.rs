@@ -0,0 +1,23 @@
+use crate::handlers::admin::stream_system_stats;
+use crate::middlewares::auth::{admin_middleware, auth_middleware};
+use crate::middlewares::ip_filter::admin_ip_filter_middleware;
+use crate::middlewares::rate_limit::user_rate_limit_middleware;
+use crate::state::AppState;
+use axum::Router;
+use axum::middleware::{from_fn, from_fn_with_state};
+use axum::routing::get;
+use std::sync::Arc;
+
+/// SSE 客户端发送 `Accept: text/event-stream`，因此这些路由不经过 JSON accept 校验；
+/// 其余中间件与 `admin_routes` 保持一致，包括管理接口的 IP 过滤
+pub fn stream_routes(app_state: Arc<AppState>) -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/admin/stats/stream", get(stream_system_stats))
+        .route_layer(from_fn(admin_middleware))
+        .route_layer(from_fn_with_state(
+            app_state.clone(),
+            user_rate_limit_middleware,
+        ))
+        .route_layer(from_fn_with_state(app_state.clone(), auth_middleware))
+        .route_layer(from_fn_with_state(app_state, admin_ip_filter_middleware))
+}
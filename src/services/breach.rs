@@ -0,0 +1,58 @@
+use std::time::Duration;
+
+use sha1::{Digest, Sha1};
+use tracing::warn;
+
+const PWNED_PASSWORDS_RANGE_URL: &str = "https://api.pwnedpasswords.com/range";
+
+#[derive(Debug, Clone)]
+pub struct BreachService {
+    client: reqwest::Client,
+}
+
+impl BreachService {
+    pub fn new(timeout_ms: u64) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_millis(timeout_ms))
+            .build()
+            .expect("Failed to build HTTP client for breached password checks");
+        Self { client }
+    }
+
+    /// 通过 Pwned Passwords k-anonymity 接口检查密码是否出现在已知泄露库中，
+    /// 只上传 SHA-1 的前 5 位；服务不可用时放行并记录警告
+    pub async fn is_compromised(&self, password: &str) -> bool {
+        let hash = hex::encode_upper(Sha1::digest(password.as_bytes()));
+        let (prefix, suffix) = hash.split_at(5);
+
+        let body = match self.fetch_range(prefix).await {
+            Ok(body) => body,
+            Err(e) => {
+                warn!(error = %e, "Breached password check unavailable, allowing password");
+                return false;
+            }
+        };
+
+        // 每行格式为 SUFFIX:COUNT，开启 padding 后会混入 COUNT 为 0 的假数据
+        body.lines().any(|line| {
+            line.trim()
+                .split_once(':')
+                .map(|(candidate, count)| {
+                    candidate.eq_ignore_ascii_case(suffix)
+                        && count.trim().parse::<u64>().unwrap_or(0) > 0
+                })
+                .unwrap_or(false)
+        })
+    }
+
+    async fn fetch_range(&self, prefix: &str) -> reqwest::Result<String> {
+        self.client
+            .get(format!("{}/{}", PWNED_PASSWORDS_RANGE_URL, prefix))
+            .header("Add-Padding", "true")
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await
+    }
+}
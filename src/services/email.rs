@@ -0,0 +1,34 @@
+use crate::{
+    errors::core::Result,
+    models::{notification::EmailCategory, user::User},
+};
+
+/// 邮件发送服务；尚未接入 SMTP 或第三方服务，目前只记录收件人和主题
+#[derive(Debug, Clone)]
+pub struct EmailService;
+
+impl EmailService {
+    pub fn new() -> Self {
+        Self
+    }
+    /// 正文中常带有重置、验证等令牌，不能写入日志
+    pub async fn send(&self, to: &str, subject: &str, _body: &str) -> Result<()> {
+        tracing::info!(to, subject, "Sending email");
+        Ok(())
+    }
+    /// 按用户的通知偏好决定是否发送，返回是否实际发送
+    pub async fn send_to_user(
+        &self,
+        user: &User,
+        category: EmailCategory,
+        subject: &str,
+        body: &str,
+    ) -> Result<bool> {
+        if !user.notification_preferences.allows(category) {
+            tracing::debug!(user_id = %user.id, ?category, "Email suppressed by notification preferences");
+            return Ok(false);
+        }
+        self.send(&user.email, subject, body).await?;
+        Ok(true)
+    }
+}
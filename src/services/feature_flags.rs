@@ -0,0 +1,90 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use crate::{
+    database::feature_flag::FeatureFlagRepository,
+    errors::{api::ApiError, core::Result},
+    models::feature_flag::FeatureFlag,
+    state::AppState,
+};
+
+/// 功能开关的内存缓存，请求路径上只读缓存，由后台任务定期从数据库刷新
+#[derive(Debug, Clone)]
+pub struct FeatureFlags {
+    defaults: HashMap<String, bool>,
+    overrides: Arc<RwLock<HashMap<String, FeatureFlag>>>,
+    flag_repo: FeatureFlagRepository,
+}
+
+impl FeatureFlags {
+    pub fn new(defaults: HashMap<String, bool>) -> Self {
+        Self {
+            defaults,
+            overrides: Arc::new(RwLock::new(HashMap::new())),
+            flag_repo: FeatureFlagRepository::new(),
+        }
+    }
+
+    pub fn is_enabled(&self, name: &str) -> bool {
+        let overrides = self.overrides.read().unwrap_or_else(|e| e.into_inner());
+        overrides
+            .get(name)
+            .map(|flag| flag.enabled)
+            .or_else(|| self.defaults.get(name).copied())
+            .unwrap_or(true)
+    }
+
+    /// 开关关闭时按资源不存在处理，不暴露该功能曾经存在
+    pub fn ensure_enabled(&self, name: &str) -> Result<()> {
+        if self.is_enabled(name) {
+            Ok(())
+        } else {
+            Err(ApiError::NotFound.into())
+        }
+    }
+
+    /// 配置默认值与数据库记录合并后的全部开关
+    pub fn list(&self) -> Vec<FeatureFlag> {
+        let overrides = self.overrides.read().unwrap_or_else(|e| e.into_inner());
+        let mut flags: Vec<FeatureFlag> = self
+            .defaults
+            .iter()
+            .filter(|(name, _)| !overrides.contains_key(*name))
+            .map(|(name, enabled)| FeatureFlag {
+                name: name.clone(),
+                enabled: *enabled,
+                updated_at: None,
+            })
+            .chain(overrides.values().cloned())
+            .collect();
+        flags.sort_by(|a, b| a.name.cmp(&b.name));
+        flags
+    }
+
+    pub async fn refresh(&self, app_state: Arc<AppState>) -> Result<()> {
+        let flags = self.flag_repo.list(app_state).await?;
+        let mut overrides = self.overrides.write().unwrap_or_else(|e| e.into_inner());
+        *overrides = flags
+            .into_iter()
+            .map(|flag| (flag.name.clone(), flag))
+            .collect();
+        Ok(())
+    }
+
+    /// 写入数据库并立即更新本实例的缓存，其他实例在下次刷新时生效
+    pub async fn set(
+        &self,
+        app_state: Arc<AppState>,
+        name: String,
+        enabled: bool,
+    ) -> Result<FeatureFlag> {
+        let flag = self.flag_repo.upsert(app_state, name, enabled).await?;
+        self.overrides
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(flag.name.clone(), flag.clone());
+        Ok(flag)
+    }
+}
@@ -0,0 +1,60 @@
+use std::sync::{Arc, RwLock};
+
+use crate::{
+    config::maintenance::MaintenanceConfig,
+    database::maintenance::MaintenanceRepository,
+    errors::core::Result,
+    models::maintenance::{MaintenanceMode, MaintenanceStatus},
+    state::AppState,
+};
+
+/// 维护状态的内存缓存，与功能开关一起由后台任务定期刷新
+#[derive(Debug, Clone)]
+pub struct Maintenance {
+    status: Arc<RwLock<MaintenanceStatus>>,
+    maintenance_repo: MaintenanceRepository,
+}
+
+impl Maintenance {
+    pub fn new(config: &MaintenanceConfig) -> Self {
+        Self {
+            status: Arc::new(RwLock::new(MaintenanceStatus {
+                mode: config.default_mode,
+                message: None,
+                retry_after_seconds: config.retry_after_seconds,
+                updated_at: None,
+            })),
+            maintenance_repo: MaintenanceRepository::new(),
+        }
+    }
+
+    pub fn current(&self) -> MaintenanceStatus {
+        self.status
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// 数据库中没有记录时保留配置中的默认值
+    pub async fn refresh(&self, app_state: Arc<AppState>) -> Result<()> {
+        if let Some(status) = self.maintenance_repo.find(app_state).await? {
+            *self.status.write().unwrap_or_else(|e| e.into_inner()) = status;
+        }
+        Ok(())
+    }
+
+    pub async fn set(
+        &self,
+        app_state: Arc<AppState>,
+        mode: MaintenanceMode,
+        message: Option<String>,
+        retry_after_seconds: u64,
+    ) -> Result<MaintenanceStatus> {
+        let status = self
+            .maintenance_repo
+            .upsert(app_state, mode, message, retry_after_seconds)
+            .await?;
+        *self.status.write().unwrap_or_else(|e| e.into_inner()) = status.clone();
+        Ok(status)
+    }
+}
@@ -1,2 +1,8 @@
+pub mod breach;
+pub mod email;
+pub mod feature_flags;
+pub mod maintenance;
+pub mod note;
+pub mod rate_limit;
 pub mod user;
 pub mod token;
\ No newline at end of file
@@ -0,0 +1,95 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+
+use crate::{
+    database::{note::NoteRepository, repository::Repository},
+    dtos::pagination::Pagination,
+    errors::{core::Result, db::DatabaseError},
+    models::note::Note,
+    state::AppState,
+};
+
+#[derive(Debug, Clone)]
+pub struct NoteService {
+    note_repo: NoteRepository,
+}
+
+impl NoteService {
+    pub fn new() -> Self {
+        Self {
+            note_repo: NoteRepository::new(),
+        }
+    }
+    pub async fn create_note(
+        &self,
+        app_state: Arc<AppState>,
+        user_id: String,
+        tenant_id: Option<String>,
+        title: String,
+        content: String,
+    ) -> Result<Note> {
+        let note = Note::new(user_id, tenant_id, title, content);
+        self.note_repo.create(app_state, note).await
+    }
+    /// 不做所有权检查，由调用方在读取后校验
+    pub async fn get_note(&self, app_state: Arc<AppState>, note_id: String) -> Result<Note> {
+        self.note_repo
+            .find_by_id(app_state, note_id)
+            .await?
+            .ok_or_else(|| DatabaseError::NotFound("Note not found".to_string()).into())
+    }
+    pub async fn update_note(
+        &self,
+        app_state: Arc<AppState>,
+        mut note: Note,
+        title: Option<String>,
+        content: Option<String>,
+    ) -> Result<Note> {
+        if let Some(title) = title {
+            note.title = title;
+        }
+        if let Some(content) = content {
+            note.content = content;
+        }
+        note.updated_at = Utc::now();
+        self.note_repo.update(app_state, note).await
+    }
+    pub async fn delete_note(&self, app_state: Arc<AppState>, note_id: String) -> Result<()> {
+        self.note_repo.delete(app_state, note_id).await
+    }
+    /// 管理员查看本租户的全部笔记，普通用户只能看到自己的笔记
+    pub async fn list_notes(
+        &self,
+        app_state: Arc<AppState>,
+        user_id: String,
+        tenant_id: Option<String>,
+        is_admin: bool,
+        pagination: &Pagination,
+    ) -> Result<(Vec<Note>, u64)> {
+        if is_admin {
+            let notes = self
+                .note_repo
+                .find_by_tenant(
+                    app_state.clone(),
+                    tenant_id.clone(),
+                    pagination.limit,
+                    pagination.start(),
+                )
+                .await?;
+            let total = self.note_repo.count_by_tenant(app_state, tenant_id).await?;
+            return Ok((notes, total));
+        }
+        let notes = self
+            .note_repo
+            .find_by_user(
+                app_state.clone(),
+                user_id.clone(),
+                pagination.limit,
+                pagination.start(),
+            )
+            .await?;
+        let total = self.note_repo.count_by_user(app_state, user_id).await?;
+        Ok((notes, total))
+    }
+}
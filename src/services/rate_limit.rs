@@ -0,0 +1,103 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use crate::config::rate_limit::UserRateLimitConfig;
+
+// 超过这个数量时顺带清理已经补满的桶，避免长期运行后 map 无限增长
+const PRUNE_THRESHOLD: usize = 10_000;
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+/// 按用户 ID 计数的内存令牌桶，多实例部署时每个实例各自计数
+#[derive(Debug, Clone)]
+pub struct UserRateLimiter {
+    buckets: Arc<Mutex<HashMap<String, Bucket>>>,
+    capacity: f64,
+    refill_per_second: f64,
+}
+
+impl UserRateLimiter {
+    pub fn new(config: &UserRateLimitConfig) -> Self {
+        Self {
+            buckets: Arc::new(Mutex::new(HashMap::new())),
+            capacity: f64::from(config.burst_size.max(1)),
+            refill_per_second: f64::from(config.requests_per_minute) / 60.0,
+        }
+    }
+
+    /// 消耗一个令牌，桶已空时返回 false
+    pub fn try_acquire(&self, user_id: &str) -> bool {
+        self.try_acquire_at(user_id, Instant::now())
+    }
+
+    fn try_acquire_at(&self, user_id: &str, now: Instant) -> bool {
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        if buckets.len() >= PRUNE_THRESHOLD {
+            buckets.retain(|_, bucket| self.refilled(bucket, now) < self.capacity);
+        }
+        let bucket = buckets.entry(user_id.to_string()).or_insert(Bucket {
+            tokens: self.capacity,
+            updated_at: now,
+        });
+        bucket.tokens = self.refilled(bucket, now);
+        bucket.updated_at = now;
+        if bucket.tokens < 1.0 {
+            return false;
+        }
+        bucket.tokens -= 1.0;
+        true
+    }
+
+    fn refilled(&self, bucket: &Bucket, now: Instant) -> f64 {
+        let elapsed = now.saturating_duration_since(bucket.updated_at).as_secs_f64();
+        (bucket.tokens + elapsed * self.refill_per_second).min(self.capacity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn limiter(requests_per_minute: u32, burst_size: u32) -> UserRateLimiter {
+        UserRateLimiter::new(&UserRateLimitConfig {
+            enabled: true,
+            requests_per_minute,
+            burst_size,
+        })
+    }
+
+    #[test]
+    fn rejects_after_burst_is_exhausted() {
+        let limiter = limiter(60, 3);
+        let now = Instant::now();
+        assert!((0..3).all(|_| limiter.try_acquire_at("user-1", now)));
+        assert!(!limiter.try_acquire_at("user-1", now));
+    }
+
+    #[test]
+    fn buckets_are_tracked_per_user() {
+        let limiter = limiter(60, 1);
+        let now = Instant::now();
+        assert!(limiter.try_acquire_at("user-1", now));
+        assert!(!limiter.try_acquire_at("user-1", now));
+        assert!(limiter.try_acquire_at("user-2", now));
+    }
+
+    #[test]
+    fn tokens_refill_over_time() {
+        let limiter = limiter(60, 1);
+        let now = Instant::now();
+        assert!(limiter.try_acquire_at("user-1", now));
+        assert!(!limiter.try_acquire_at("user-1", now + Duration::from_millis(500)));
+        assert!(limiter.try_acquire_at("user-1", now + Duration::from_secs(2)));
+    }
+}
@@ -1,16 +1,54 @@
-use std::sync::Arc;
+use std::{collections::HashMap, net::IpAddr, sync::Arc};
 
 use crate::{
-    config::token::TokenConfig,
-    database::token::TokenRepository,
-    errors::core::Result,
+    config::token::{AccessTokenFormat, SessionLimitStrategy, TokenConfig},
+    database::{token::TokenRepository, user::UserRepository},
+    dtos::pagination::Pagination,
+    errors::{auth::AuthError, core::Result, db::DatabaseError, jwt::JwtError},
     models::{
-        role::Role, token_claims::TokenClaims, token_scope::TokenScope, token_session::TokenSession,
+        device::DeviceInfo,
+        role::Role,
+        session_event::SessionEvent,
+        token_claims::{
+            DEVICE_BINDING_CLAIM, TENANT_ID_CLAIM, TOKEN_AUDIENCE, TOKEN_ISSUER, TokenClaims,
+        },
+        token_scope::TokenScope,
+        token_session::{
+            SessionIpFilter, SessionRevocationCriteria, SessionTokenUpdate, TokenSession,
+        },
+        token_type::TokenType,
+        user::User,
     },
     state::AppState,
 };
-use chrono::{Duration, Utc};
-use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation, decode, encode};
+use axum::http::{HeaderMap, HeaderValue, header::SET_COOKIE};
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation, decode, decode_header, encode};
+use sha2::{Digest, Sha256};
+
+pub const ACCESS_TOKEN_COOKIE: &str = "access_token";
+pub const REFRESH_TOKEN_COOKIE: &str = "refresh_token";
+const REFRESH_TOKEN_COOKIE_PATH: &str = "/api/v1/auth";
+/// opaque 访问令牌的前缀，用于和 JWT 区分，切换格式后旧令牌仍可使用直到过期
+pub const OPAQUE_TOKEN_PREFIX: &str = "opq_";
+
+// opaque 模式下哈希和声明需要随会话保存
+struct IssuedAccessToken {
+    token: String,
+    claims: TokenClaims,
+    token_hash: Option<String>,
+}
+
+// 新登录对用户已有会话的处理方式
+#[derive(Debug, PartialEq, Eq)]
+enum LoginSessionMode {
+    // SINGLE_ACTIVE_SESSION：撤销其他所有会话
+    RevokeOthers,
+    // ONE_SESSION_PER_DEVICE：同一设备复用已有会话
+    ReuseDevice,
+    // 保留已有会话，只受会话上限约束
+    KeepOthers,
+}
 
 #[derive(Debug, Clone)]
 pub struct TokenService {
@@ -32,11 +70,24 @@ impl TokenService {
         email: &str,
         role: &Role,
         custom_scopes: Option<Vec<TokenScope>>,
+        extra_claims: Option<HashMap<String, serde_json::Value>>,
     ) -> Result<String> {
+        let claims = self.access_claims(user_id, email, role, custom_scopes, extra_claims)?;
+        self.encode_access_token(&claims)
+    }
+
+    fn access_claims(
+        &self,
+        user_id: &str,
+        email: &str,
+        role: &Role,
+        custom_scopes: Option<Vec<TokenScope>>,
+        extra_claims: Option<HashMap<String, serde_json::Value>>,
+    ) -> Result<TokenClaims> {
         let now = Utc::now();
         let expires_at = now + Duration::seconds(self.config.access_token_expires_in);
         let scopes = custom_scopes.unwrap_or_else(|| TokenClaims::default_scopes_for_role(role));
-        let claims = TokenClaims::new_access_token(
+        let mut claims = TokenClaims::new_access_token(
             user_id.to_string(),
             email.to_string(),
             role.clone(),
@@ -44,9 +95,78 @@ impl TokenService {
             expires_at.timestamp(),
             scopes,
         );
-        let header = Header::new(jsonwebtoken::Algorithm::HS256);
+        // 自定义声明（如租户、功能开关）合并进 extra，不覆盖标准字段
+        if let Some(extra_claims) = extra_claims {
+            self.validate_extra_claims(&extra_claims)?;
+            claims.extra.extend(extra_claims);
+        }
+        Ok(claims)
+    }
+
+    fn encode_access_token(&self, claims: &TokenClaims) -> Result<String> {
+        let mut header = Header::new(jsonwebtoken::Algorithm::HS256);
+        header.kid = Some(self.config.jwt_access_kid.clone());
         let encoding_key = EncodingKey::from_secret(self.config.jwt_access_secret.as_bytes());
-        encode(&header, &claims, &encoding_key).map_err(Into::into)
+        encode(&header, claims, &encoding_key).map_err(Into::into)
+    }
+
+    fn issue_access_token(&self, claims: TokenClaims) -> Result<IssuedAccessToken> {
+        match self.config.access_token_format {
+            AccessTokenFormat::Jwt => Ok(IssuedAccessToken {
+                token: self.encode_access_token(&claims)?,
+                claims,
+                token_hash: None,
+            }),
+            AccessTokenFormat::Opaque => {
+                let token = format!(
+                    "{}{}{}",
+                    OPAQUE_TOKEN_PREFIX,
+                    uuid::Uuid::new_v4().simple(),
+                    uuid::Uuid::new_v4().simple()
+                );
+                Ok(IssuedAccessToken {
+                    token_hash: Some(Self::hash_opaque_token(&token)),
+                    token,
+                    claims,
+                })
+            }
+        }
+    }
+
+    fn hash_opaque_token(token: &str) -> String {
+        hex::encode(Sha256::digest(token.as_bytes()))
+    }
+
+    // 配置了 EXTRA_CLAIMS_SCHEMA 时拒绝未声明的键和类型不符的值
+    fn validate_extra_claims(
+        &self,
+        extra_claims: &HashMap<String, serde_json::Value>,
+    ) -> Result<()> {
+        let Some(schema) = &self.config.extra_claims_schema else {
+            return Ok(());
+        };
+        for (key, value) in extra_claims {
+            if (key == TENANT_ID_CLAIM || key == DEVICE_BINDING_CLAIM) && value.is_string() {
+                continue;
+            }
+            match schema.get(key) {
+                None => {
+                    return Err(JwtError::invalid_extra_claim(format!(
+                        "'{key}' is not an allowed claim"
+                    ))
+                    .into());
+                }
+                Some(claim_type) if !claim_type.matches(value) => {
+                    return Err(JwtError::invalid_extra_claim(format!(
+                        "'{key}' should be of type {}",
+                        claim_type.as_str()
+                    ))
+                    .into());
+                }
+                Some(_) => {}
+            }
+        }
+        Ok(())
     }
 
     pub fn generate_refresh_token(&self, user_id: &str) -> Result<String> {
@@ -57,14 +177,15 @@ impl TokenService {
             now.timestamp(),
             expires_at.timestamp(),
         );
-        let header = Header::new(jsonwebtoken::Algorithm::HS256);
+        let mut header = Header::new(jsonwebtoken::Algorithm::HS256);
+        header.kid = Some(self.config.jwt_refresh_kid.clone());
         let encoding_key = EncodingKey::from_secret(self.config.jwt_refresh_secret.as_bytes());
         encode(&header, &claims, &encoding_key).map_err(Into::into)
     }
 
     pub fn generate_email_verification_token(&self, user_id: &str, email: &str) -> Result<String> {
         let now = Utc::now();
-        let expires_at = now + Duration::hours(24);
+        let expires_at = now + Duration::seconds(self.config.email_verification_expires_in);
         let claims = TokenClaims::new_email_verification_token(
             user_id.to_string(),
             email.to_string(),
@@ -79,7 +200,7 @@ impl TokenService {
 
     pub fn generate_password_reset_token(&self, user_id: &str, email: &str) -> Result<String> {
         let now = Utc::now();
-        let expires_at = now + Duration::hours(1);
+        let expires_at = now + Duration::seconds(self.config.password_reset_expires_in);
         let claims = TokenClaims::new_password_reset_token(
             user_id.to_string(),
             email.to_string(),
@@ -94,27 +215,158 @@ impl TokenService {
     pub async fn create_session(
         &self,
         app_state: Arc<AppState>,
-        user_id: &str,
-        email: &str,
-        role: &Role,
-        device_info: Option<String>,
+        user: &User,
+        device_info: Option<DeviceInfo>,
+        ip_address: Option<IpAddr>,
         custom_scopes: Option<Vec<TokenScope>>,
+        device_binding: Option<String>,
     ) -> Result<(String, String, TokenSession)> {
-        let access_token = self.generate_access_token(user_id, email, role, custom_scopes)?;
-        let refresh_token = self.generate_refresh_token(user_id)?;
+        let user_id = user.id.as_str();
+        let device_fingerprint = DeviceInfo::fingerprint(device_info.as_ref(), ip_address);
+        let access_claims = self.access_claims(
+            user_id,
+            &user.email,
+            &user.role,
+            custom_scopes,
+            Self::session_extra_claims(user.tenant_id.clone(), device_binding.clone()),
+        )?;
+
+        // 在任何地方登录都会让其他地方下线，此时会话上限和同设备复用都不再适用
+        let mode = self.login_session_mode();
+        if mode == LoginSessionMode::RevokeOthers {
+            self.revoke_all_user_sessions(app_state.clone(), user_id.to_string())
+                .await?;
+        } else if mode == LoginSessionMode::ReuseDevice
+            && let Some(existing) = self
+                .token_repo
+                .find_active_session_by_device(
+                    app_state.clone(),
+                    user_id.to_string(),
+                    device_fingerprint.clone(),
+                    ip_address.map(|ip| ip.to_string()),
+                )
+                .await?
+        {
+            self.token_repo
+                .update_session_device(
+                    app_state.clone(),
+                    existing.id.clone(),
+                    device_binding,
+                    device_info,
+                )
+                .await?;
+            return self
+                .reissue_session_tokens(app_state, existing.id, access_claims)
+                .await;
+        }
+
+        self.enforce_session_limit(app_state.clone(), user_id)
+            .await?;
 
-        let access_claims = self.verify_access_token(&access_token)?;
+        let access = self.issue_access_token(access_claims)?;
+        let refresh_token = self.generate_refresh_token(user_id)?;
         let refresh_claims = self.verify_refresh_token(&refresh_token)?;
 
-        let access_jti = access_claims.jti.unwrap_or_default();
+        let access_jti = access.claims.jti.clone().unwrap_or_default();
         let refresh_jti = refresh_claims.jti.unwrap_or_default();
 
         let mut session = TokenSession::new(user_id.to_string(), access_jti, refresh_jti);
+        session.expires_at = DateTime::from_timestamp(refresh_claims.exp, 0);
+        session.access_claims = access.token_hash.is_some().then(|| access.claims.clone());
+        session.access_token_hash = access.token_hash;
+        session.device_fingerprint = Some(device_fingerprint);
+        session.device_binding = device_binding;
         session.device_info = device_info;
+        session.ip_address = ip_address.map(|ip| ip.to_string());
+        session.tenant_id = user.tenant_id.clone();
 
         let created_session = self.token_repo.create_session(app_state, session).await?;
 
-        Ok((access_token, refresh_token, created_session))
+        Ok((access.token, refresh_token, created_session))
+    }
+
+    fn login_session_mode(&self) -> LoginSessionMode {
+        if self.config.single_active_session {
+            LoginSessionMode::RevokeOthers
+        } else if self.config.one_session_per_device {
+            LoginSessionMode::ReuseDevice
+        } else {
+            LoginSessionMode::KeepOthers
+        }
+    }
+
+    /// 用户已有会话但从未使用过该设备指纹时视为新设备；首次登录不算
+    pub async fn is_new_device(
+        &self,
+        app_state: Arc<AppState>,
+        user_id: &str,
+        device_fingerprint: &str,
+    ) -> Result<bool> {
+        let (total, matching) = self
+            .token_repo
+            .count_sessions_by_fingerprint(
+                app_state,
+                user_id.to_string(),
+                device_fingerprint.to_string(),
+            )
+            .await?;
+        Ok(total > 0 && matching == 0)
+    }
+
+    // 会话级的自定义声明：租户 id 和设备绑定值，刷新时从会话记录中恢复
+    fn session_extra_claims(
+        tenant_id: Option<String>,
+        device_binding: Option<String>,
+    ) -> Option<HashMap<String, serde_json::Value>> {
+        let extra: HashMap<String, serde_json::Value> = [
+            (TENANT_ID_CLAIM, tenant_id),
+            (DEVICE_BINDING_CLAIM, device_binding),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| Some((key.to_string(), serde_json::Value::String(value?))))
+        .collect();
+        (!extra.is_empty()).then_some(extra)
+    }
+
+    // 为新会话腾出位置：按配置撤销最久未活跃的会话或直接拒绝
+    async fn enforce_session_limit(&self, app_state: Arc<AppState>, user_id: &str) -> Result<()> {
+        if self.config.max_sessions_per_user == 0 {
+            return Ok(());
+        }
+        let active = self
+            .token_repo
+            .count_active_sessions_by_user(app_state.clone(), user_id.to_string())
+            .await?;
+        let excess = self.sessions_over_limit(active)?;
+        if excess == 0 {
+            return Ok(());
+        }
+
+        let evicted = self
+            .token_repo
+            .find_least_recently_active_sessions(app_state.clone(), user_id.to_string(), excess)
+            .await?;
+        for session in evicted {
+            tracing::info!(
+                user_id,
+                session_id = %session.id,
+                "Evicting session over the per-user limit"
+            );
+            self.revoke_session(app_state.clone(), session.id).await?;
+        }
+        Ok(())
+    }
+
+    // 已有 active 个活跃会话时新登录需要撤销的会话数，Reject 策略下达到上限直接拒绝
+    fn sessions_over_limit(&self, active: u64) -> Result<u64> {
+        let max_sessions = self.config.max_sessions_per_user as u64;
+        if max_sessions == 0 || active < max_sessions {
+            return Ok(0);
+        }
+        if self.config.session_limit_strategy == SessionLimitStrategy::Reject {
+            return Err(AuthError::SessionLimitReached.into());
+        }
+        Ok(active - max_sessions + 1)
     }
 
     pub async fn refresh_session(
@@ -131,23 +383,113 @@ impl TokenService {
             .await?
             .ok_or_else(|| crate::errors::auth::AuthError::InvalidToken)?;
 
-        if !session.is_active {
-            return Err(crate::errors::auth::AuthError::InvalidToken.into());
+        // 清理任务随时可能删除的会话不再允许刷新
+        if session.is_due_for_cleanup(Utc::now(), self.config.session_retention()) {
+            return Err(AuthError::InvalidToken.into());
+        }
+        if self.is_idle(&session) {
+            self.revoke_idle_session(app_state, session).await?;
+            return Err(AuthError::InvalidToken.into());
         }
 
-        let new_access_token = self.generate_access_token(
-            &session.user_id,
-            "",
-            &crate::models::role::Role::User,
+        // 角色和邮箱以用户当前记录为准，刷新后立即反映角色变更
+        let user = UserRepository::new()
+            .find_by_id(app_state.clone(), session.user_id.clone())
+            .await?
+            .ok_or(AuthError::InvalidToken)?;
+        let access_claims = self.refreshed_access_claims(&session, &user)?;
+        let (access_token, refresh_token, _) = self
+            .reissue_session_tokens(app_state, session.id, access_claims)
+            .await?;
+        Ok((access_token, refresh_token))
+    }
+
+    // 刷新时的访问令牌声明：按用户当前角色重新计算权限范围，租户和设备绑定从会话记录中恢复
+    fn refreshed_access_claims(&self, session: &TokenSession, user: &User) -> Result<TokenClaims> {
+        self.access_claims(
+            &user.id,
+            &user.email,
+            &user.role,
             None,
-        )?;
-        let new_refresh_token = self.generate_refresh_token(&session.user_id)?;
+            Self::session_extra_claims(session.tenant_id.clone(), session.device_binding.clone()),
+        )
+    }
 
-        self.token_repo
-            .update_last_active(app_state, session.id)
+    /// 为当前会话换发新的令牌对，角色、权限范围和自定义声明保持不变。旧的刷新令牌和 opaque 访问令牌随即失效；
+    /// JWT 访问令牌是无状态的，旧令牌在过期前仍能通过签名校验
+    pub async fn rotate_current_session(
+        &self,
+        app_state: Arc<AppState>,
+        claims: &TokenClaims,
+    ) -> Result<(String, String)> {
+        let jti = claims.jti.clone().ok_or(AuthError::InvalidToken)?;
+        let session = self
+            .token_repo
+            .find_by_access_token_jti(app_state.clone(), jti)
+            .await?
+            .filter(|session| session.is_active)
+            .ok_or(AuthError::InvalidToken)?;
+        if session.user_id != claims.sub {
+            return Err(AuthError::PermissionDenied.into());
+        }
+
+        let role = claims.role.clone().unwrap_or(Role::User);
+        let access_claims = self.access_claims(
+            &claims.sub,
+            claims.email.as_deref().unwrap_or_default(),
+            &role,
+            Some(claims.scopes.clone()),
+            Some(claims.extra.clone()),
+        )?;
+        let (access_token, refresh_token, _) = self
+            .reissue_session_tokens(app_state, session.id, access_claims)
             .await?;
+        Ok((access_token, refresh_token))
+    }
+
+    // 签发新的令牌对并写回会话，旧的访问令牌和刷新令牌不再能匹配到该会话
+    async fn reissue_session_tokens(
+        &self,
+        app_state: Arc<AppState>,
+        session_id: String,
+        access_claims: TokenClaims,
+    ) -> Result<(String, String, TokenSession)> {
+        let access = self.issue_access_token(access_claims)?;
+        let refresh_token = self.generate_refresh_token(&access.claims.sub)?;
+        let refresh_claims = self.verify_refresh_token(&refresh_token)?;
 
-        Ok((new_access_token, new_refresh_token))
+        let update = SessionTokenUpdate {
+            access_token_jti: access.claims.jti.clone().unwrap_or_default(),
+            refresh_token_jti: refresh_claims.jti.unwrap_or_default(),
+            expires_at: DateTime::from_timestamp(refresh_claims.exp, 0),
+            access_claims: access.token_hash.is_some().then(|| access.claims.clone()),
+            access_token_hash: access.token_hash,
+        };
+        let session = self
+            .token_repo
+            .rotate_session_tokens(app_state, session_id, update)
+            .await?
+            .ok_or_else(|| DatabaseError::NotFound("Session not found".to_string()))?;
+
+        Ok((access.token, refresh_token, session))
+    }
+
+    /// 按令牌格式解析访问令牌：opaque 令牌查询会话表并要求会话仍然活跃，其余按 JWT 校验签名。
+    /// 两种格式始终都能解析，切换 ACCESS_TOKEN_FORMAT 不会使已签发的令牌失效
+    pub async fn resolve_access_token(
+        &self,
+        app_state: Arc<AppState>,
+        token: &str,
+    ) -> Result<TokenClaims> {
+        if !token.starts_with(OPAQUE_TOKEN_PREFIX) {
+            return self.verify_access_token(token);
+        }
+        self.token_repo
+            .find_by_access_token_hash(app_state, Self::hash_opaque_token(token))
+            .await?
+            .filter(|session| session.is_active)
+            .and_then(|session| session.access_claims)
+            .ok_or_else(|| AuthError::InvalidToken.into())
     }
 
     pub async fn verify_access_token_with_session(
@@ -155,7 +497,7 @@ impl TokenService {
         app_state: Arc<AppState>,
         token: &str,
     ) -> Result<TokenClaims> {
-        let claims = self.verify_access_token(token)?;
+        let claims = self.resolve_access_token(app_state.clone(), token).await?;
 
         if let Some(jti) = &claims.jti {
             if let Some(session) = self
@@ -166,6 +508,10 @@ impl TokenService {
                 if !session.is_active {
                     return Err(crate::errors::auth::AuthError::InvalidToken.into());
                 }
+                if self.is_idle(&session) {
+                    self.revoke_idle_session(app_state, session).await?;
+                    return Err(AuthError::InvalidToken.into());
+                }
 
                 self.token_repo
                     .update_last_active(app_state, session.id)
@@ -178,8 +524,138 @@ impl TokenService {
         Ok(claims)
     }
 
+    /// 超过 SESSION_IDLE_TIMEOUT 没有活动的会话，未配置时永不闲置
+    fn is_idle(&self, session: &TokenSession) -> bool {
+        self.config
+            .session_idle_timeout
+            .is_some_and(|timeout| Utc::now() - session.last_active_at > Duration::seconds(timeout))
+    }
+
+    async fn revoke_idle_session(
+        &self,
+        app_state: Arc<AppState>,
+        session: TokenSession,
+    ) -> Result<()> {
+        tracing::info!(
+            user_id = %session.user_id,
+            session_id = %session.id,
+            last_active_at = %session.last_active_at,
+            "Revoking idle session"
+        );
+        self.revoke_session(app_state, session.id).await
+    }
+
+    /// 校验签名、过期时间和会话状态，任何一项失败都返回 None，不区分原因
+    pub async fn introspect(
+        &self,
+        app_state: Arc<AppState>,
+        token: &str,
+        prefer_refresh: bool,
+    ) -> Option<TokenClaims> {
+        if token.starts_with(OPAQUE_TOKEN_PREFIX) {
+            return self
+                .resolve_access_token(app_state, token)
+                .await
+                .ok()
+                .filter(|claims| !claims.is_expired(self.config.clock_skew_leeway));
+        }
+        let verified = if prefer_refresh {
+            self.verify_refresh_token(token)
+                .or_else(|_| self.verify_access_token(token))
+        } else {
+            self.verify_access_token(token)
+                .or_else(|_| self.verify_refresh_token(token))
+        };
+        let claims = verified
+            .ok()
+            .filter(|claims| !claims.is_expired(self.config.clock_skew_leeway))?;
+        let jti = claims.jti.clone()?;
+
+        let session = match claims.token_type {
+            TokenType::Access => {
+                self.token_repo
+                    .find_by_access_token_jti(app_state, jti)
+                    .await
+            }
+            TokenType::Refresh => {
+                self.token_repo
+                    .find_by_refresh_token_jti(app_state, jti)
+                    .await
+            }
+            _ => return None,
+        };
+        session
+            .ok()
+            .flatten()
+            .filter(|session| session.is_active)
+            .map(|_| claims)
+    }
+
     pub async fn revoke_session(&self, app_state: Arc<AppState>, session_id: String) -> Result<()> {
-        self.token_repo.revoke_session(app_state, session_id).await
+        if let Some(session) = self
+            .token_repo
+            .revoke_session(app_state.clone(), session_id)
+            .await?
+        {
+            // 没有订阅者时发送会失败，可以忽略
+            let _ = app_state.session_events.send(SessionEvent::session_revoked(
+                session.user_id,
+                Some(session.id),
+            ));
+        }
+        Ok(())
+    }
+
+    /// 标记或取消标记受信任会话，只能修改自己的活跃会话
+    pub async fn set_session_trust(
+        &self,
+        app_state: Arc<AppState>,
+        user_id: &str,
+        session_id: String,
+        trusted: bool,
+    ) -> Result<TokenSession> {
+        let session = self
+            .token_repo
+            .find_by_id(app_state.clone(), session_id.clone())
+            .await?
+            .filter(|session| session.is_active)
+            .ok_or_else(|| DatabaseError::NotFound("Session not found".to_string()))?;
+        if session.user_id != user_id {
+            return Err(AuthError::PermissionDenied.into());
+        }
+
+        let trusted_until =
+            trusted.then(|| Utc::now() + Duration::days(self.config.trusted_session_days));
+        self.token_repo
+            .set_trusted(app_state, session_id, trusted, trusted_until)
+            .await?
+            .ok_or_else(|| DatabaseError::NotFound("Session not found".to_string()).into())
+    }
+
+    /// 只让指定的刷新令牌失效，会话和其访问令牌在剩余有效期内仍然可用
+    pub async fn revoke_refresh_token(
+        &self,
+        app_state: Arc<AppState>,
+        user_id: &str,
+        refresh_token: &str,
+    ) -> Result<()> {
+        let refresh_claims = self.verify_refresh_token(refresh_token)?;
+        let refresh_jti = refresh_claims.jti.ok_or(AuthError::InvalidToken)?;
+        let session = self
+            .token_repo
+            .find_by_refresh_token_jti(app_state.clone(), refresh_jti)
+            .await?
+            .filter(|session| session.is_active)
+            .ok_or(AuthError::InvalidToken)?;
+        if session.user_id != user_id {
+            return Err(AuthError::PermissionDenied.into());
+        }
+
+        self.token_repo
+            .invalidate_refresh_token(app_state, session.id)
+            .await?
+            .ok_or_else(|| DatabaseError::NotFound("Session not found".to_string()))?;
+        Ok(())
     }
 
     pub async fn revoke_all_user_sessions(
@@ -188,10 +664,74 @@ impl TokenService {
         user_id: String,
     ) -> Result<()> {
         self.token_repo
-            .revoke_all_user_sessions(app_state, user_id)
+            .revoke_all_user_sessions(app_state.clone(), user_id.clone())
+            .await?;
+        let _ = app_state
+            .session_events
+            .send(SessionEvent::session_revoked(user_id, None));
+        Ok(())
+    }
+
+    /// 按 id 查找会话，包括已撤销的会话
+    pub async fn find_session_by_id(
+        &self,
+        app_state: Arc<AppState>,
+        session_id: String,
+    ) -> Result<Option<TokenSession>> {
+        self.token_repo.find_by_id(app_state, session_id).await
+    }
+
+    pub async fn find_sessions_by_ip(
+        &self,
+        app_state: Arc<AppState>,
+        tenant_id: Option<String>,
+        filter: SessionIpFilter,
+        pagination: &Pagination,
+    ) -> Result<(Vec<TokenSession>, u64)> {
+        self.token_repo
+            .find_sessions_by_ip(
+                app_state,
+                tenant_id,
+                filter,
+                pagination.limit,
+                pagination.start(),
+            )
             .await
     }
 
+    /// 只撤销 tenant_id 对应租户内的会话
+    pub async fn revoke_sessions_matching(
+        &self,
+        app_state: Arc<AppState>,
+        tenant_id: Option<String>,
+        criteria: SessionRevocationCriteria,
+    ) -> Result<usize> {
+        let revoked = match criteria {
+            SessionRevocationCriteria::CreatedBefore(cutoff_time) => {
+                self.token_repo
+                    .revoke_sessions_created_before(app_state.clone(), tenant_id, cutoff_time)
+                    .await?
+            }
+            SessionRevocationCriteria::IpAddress(ip_address) => {
+                self.token_repo
+                    .revoke_sessions_by_ip(app_state.clone(), tenant_id, ip_address)
+                    .await?
+            }
+            SessionRevocationCriteria::UserRole(role) => {
+                self.token_repo
+                    .revoke_sessions_by_user_role(app_state.clone(), tenant_id, role)
+                    .await?
+            }
+        };
+        for session in &revoked {
+            let _ = app_state.session_events.send(SessionEvent::session_revoked(
+                session.user_id.clone(),
+                Some(session.id.clone()),
+            ));
+        }
+        Ok(revoked.len())
+    }
+
     pub async fn get_user_active_sessions(
         &self,
         app_state: Arc<AppState>,
@@ -202,24 +742,116 @@ impl TokenService {
             .await
     }
 
+    /// 返回当前页的活跃会话和所有页的活跃会话总数
+    pub async fn get_user_active_sessions_page(
+        &self,
+        app_state: Arc<AppState>,
+        user_id: String,
+        pagination: &Pagination,
+    ) -> Result<(Vec<TokenSession>, u64)> {
+        let sessions = self
+            .token_repo
+            .find_active_sessions_by_user_page(
+                app_state.clone(),
+                user_id.clone(),
+                pagination.limit,
+                pagination.start(),
+            )
+            .await?;
+        let total = self
+            .token_repo
+            .count_active_sessions_by_user(app_state, user_id)
+            .await?;
+        Ok((sessions, total))
+    }
+
+    pub async fn get_all_user_sessions(
+        &self,
+        app_state: Arc<AppState>,
+        user_id: String,
+    ) -> Result<Vec<TokenSession>> {
+        self.token_repo.find_all_by_user(app_state, user_id).await
+    }
+
     pub async fn cleanup_expired_sessions(&self, app_state: Arc<AppState>) -> Result<usize> {
-        self.token_repo.cleanup_expired_sessions(app_state).await
+        self.token_repo
+            .cleanup_expired_sessions(app_state, self.config.session_retention())
+            .await
+    }
+
+    /// 包括已撤销但尚未被清理的会话，用于观察表的增长
+    pub async fn count_all_sessions(&self, app_state: Arc<AppState>) -> Result<u64> {
+        self.token_repo.count_all_sessions(app_state).await
+    }
+
+    /// 同样包括已撤销的会话，但只统计指定租户
+    pub async fn count_tenant_sessions(
+        &self,
+        app_state: Arc<AppState>,
+        tenant_id: Option<String>,
+    ) -> Result<u64> {
+        self.token_repo
+            .count_sessions_in_tenant(app_state, tenant_id)
+            .await
+    }
+
+    pub async fn count_active_sessions(
+        &self,
+        app_state: Arc<AppState>,
+        tenant_id: Option<String>,
+    ) -> Result<u64> {
+        self.token_repo
+            .count_active_sessions(app_state, tenant_id)
+            .await
+    }
+
+    pub async fn count_user_active_sessions(
+        &self,
+        app_state: Arc<AppState>,
+        user_id: String,
+    ) -> Result<u64> {
+        self.token_repo
+            .count_active_sessions_by_user(app_state, user_id)
+            .await
     }
 
     pub fn verify_access_token(&self, token: &str) -> Result<TokenClaims> {
-        self.verify_token(token, &self.config.jwt_access_secret)
+        let secret = match decode_header(token)?.kid {
+            Some(kid) => self
+                .config
+                .access_secret_for(&kid)
+                .ok_or(JwtError::InvalidToken)?,
+            // 引入 kid 之前签发的令牌
+            None => &self.config.jwt_access_secret,
+        };
+        self.verify_token(token, secret, TokenType::Access)
     }
 
     pub fn verify_refresh_token(&self, token: &str) -> Result<TokenClaims> {
-        self.verify_token(token, &self.config.jwt_refresh_secret)
+        let secret = match decode_header(token)?.kid {
+            Some(kid) => self
+                .config
+                .refresh_secret_for(&kid)
+                .ok_or(JwtError::InvalidToken)?,
+            None => &self.config.jwt_refresh_secret,
+        };
+        self.verify_token(token, secret, TokenType::Refresh)
     }
 
     pub fn verify_email_verification_token(&self, token: &str) -> Result<TokenClaims> {
-        self.verify_token(token, &self.config.email_verification_secret)
+        self.verify_token(
+            token,
+            &self.config.email_verification_secret,
+            TokenType::EmailVerification,
+        )
     }
 
     pub fn verify_password_reset_token(&self, token: &str) -> Result<TokenClaims> {
-        self.verify_token(token, &self.config.password_reset_secret)
+        self.verify_token(
+            token,
+            &self.config.password_reset_secret,
+            TokenType::PasswordReset,
+        )
     }
 
     pub fn extract_token_from_header(auth_header: &str) -> Option<&str> {
@@ -230,6 +862,66 @@ impl TokenService {
         }
     }
 
+    /// 从 Cookie 请求头中取出指定名称的值
+    pub fn extract_token_from_cookie<'a>(cookie_header: &'a str, name: &str) -> Option<&'a str> {
+        cookie_header
+            .split(';')
+            .filter_map(|pair| pair.trim().split_once('='))
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value)
+            .filter(|value| !value.is_empty())
+    }
+
+    /// 访问令牌和刷新令牌对应的 Set-Cookie 头；刷新令牌只发往认证路由
+    pub fn token_cookies(&self, access_token: &str, refresh_token: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for cookie in [
+            self.cookie(
+                ACCESS_TOKEN_COOKIE,
+                access_token,
+                "/",
+                self.config.access_token_expires_in,
+            ),
+            self.cookie(
+                REFRESH_TOKEN_COOKIE,
+                refresh_token,
+                REFRESH_TOKEN_COOKIE_PATH,
+                self.config.refresh_token_expires_in,
+            ),
+        ] {
+            if let Ok(value) = HeaderValue::from_str(&cookie) {
+                headers.append(SET_COOKIE, value);
+            }
+        }
+        headers
+    }
+
+    /// 退出登录时清除令牌 Cookie
+    pub fn clear_token_cookies(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for cookie in [
+            self.cookie(ACCESS_TOKEN_COOKIE, "", "/", 0),
+            self.cookie(REFRESH_TOKEN_COOKIE, "", REFRESH_TOKEN_COOKIE_PATH, 0),
+        ] {
+            if let Ok(value) = HeaderValue::from_str(&cookie) {
+                headers.append(SET_COOKIE, value);
+            }
+        }
+        headers
+    }
+
+    fn cookie(&self, name: &str, value: &str, path: &str, max_age: i64) -> String {
+        let mut cookie = format!(
+            "{}={}; Path={}; Max-Age={}; HttpOnly; SameSite={}",
+            name, value, path, max_age, self.config.cookie_same_site
+        );
+        // SameSite=None 必须配合 Secure，否则浏览器会拒绝
+        if self.config.cookie_secure || self.config.cookie_same_site == "None" {
+            cookie.push_str("; Secure");
+        }
+        cookie
+    }
+
     pub fn generate_token_pair(
         &self,
         user_id: &str,
@@ -237,15 +929,275 @@ impl TokenService {
         role: &Role,
         custom_scopes: Option<Vec<TokenScope>>,
     ) -> Result<(String, String)> {
-        let access_token = self.generate_access_token(user_id, email, role, custom_scopes)?;
+        let access_token = self.generate_access_token(user_id, email, role, custom_scopes, None)?;
         let refresh_token = self.generate_refresh_token(user_id)?;
         Ok((access_token, refresh_token))
     }
 
-    fn verify_token(&self, token: &str, secret: &str) -> Result<TokenClaims> {
+    // 签名校验之外再核对令牌类型，防止密钥配置重叠时不同用途的令牌互相冒用
+    fn verify_token(&self, token: &str, secret: &str, expected: TokenType) -> Result<TokenClaims> {
         let decoding_key = DecodingKey::from_secret(secret.as_bytes());
-        let validation = Validation::new(jsonwebtoken::Algorithm::HS256);
+        let mut validation = Validation::new(jsonwebtoken::Algorithm::HS256);
+        validation.set_issuer(&[TOKEN_ISSUER]);
+        validation.set_audience(&[TOKEN_AUDIENCE]);
+        validation.leeway = self.config.clock_skew_leeway;
         let token_data = decode::<TokenClaims>(token, &decoding_key, &validation)?;
+        if token_data.claims.token_type != expected {
+            return Err(JwtError::InvalidPayload.into());
+        }
         Ok(token_data.claims)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{errors::core::Error, models::token_claims::ExtraClaimType};
+
+    const LEEWAY: u64 = 30;
+
+    fn token_service() -> TokenService {
+        TokenService::new(TokenConfig {
+            jwt_access_secret: "access-secret".to_string(),
+            jwt_refresh_secret: "refresh-secret".to_string(),
+            jwt_access_kid: "access-v1".to_string(),
+            jwt_refresh_kid: "refresh-v1".to_string(),
+            jwt_previous_access_keys: Vec::new(),
+            jwt_previous_refresh_keys: Vec::new(),
+            email_verification_secret: "email-secret".to_string(),
+            password_reset_secret: "reset-secret".to_string(),
+            access_token_expires_in: 900,
+            refresh_token_expires_in: 604800,
+            email_verification_expires_in: 86400,
+            password_reset_expires_in: 3600,
+            token_cleanup_interval: 3600,
+            trusted_session_days: 30,
+            session_retention_days: None,
+            session_idle_timeout: None,
+            max_sessions_per_user: 0,
+            session_limit_strategy: SessionLimitStrategy::EvictOldest,
+            clock_skew_leeway: LEEWAY,
+            session_table_alert_threshold: 100000,
+            cookie_delivery: false,
+            cookie_secure: true,
+            cookie_same_site: "Strict".to_string(),
+            extra_claims_schema: None,
+            access_token_format: AccessTokenFormat::Jwt,
+            one_session_per_device: false,
+            single_active_session: false,
+            device_binding: false,
+        })
+    }
+
+    // 签发一个 exp 为 now + offset 秒的访问令牌
+    fn access_token_expiring_in(service: &TokenService, offset: i64) -> String {
+        let now = Utc::now().timestamp();
+        let claims = TokenClaims::new_access_token(
+            "user-1".to_string(),
+            "user@example.com".to_string(),
+            Role::User,
+            now - 900,
+            now + offset,
+            TokenClaims::default_scopes_for_role(&Role::User),
+        );
+        let mut header = Header::new(jsonwebtoken::Algorithm::HS256);
+        header.kid = Some(service.config.jwt_access_kid.clone());
+        let encoding_key = EncodingKey::from_secret(service.config.jwt_access_secret.as_bytes());
+        encode(&header, &claims, &encoding_key).unwrap()
+    }
+
+    #[test]
+    fn token_expired_within_leeway_is_accepted() {
+        let service = token_service();
+        let token = access_token_expiring_in(&service, -10);
+        let claims = service.verify_access_token(&token).unwrap();
+        assert!(!claims.is_expired(LEEWAY));
+    }
+
+    #[test]
+    fn token_expired_beyond_leeway_is_rejected() {
+        let service = token_service();
+        let token = access_token_expiring_in(&service, -(LEEWAY as i64) - 60);
+        assert!(service.verify_access_token(&token).is_err());
+    }
+
+    #[test]
+    fn admin_role_claim_round_trips() {
+        let service = token_service();
+        let token = service
+            .generate_access_token("user-1", "admin@example.com", &Role::Admin, None, None)
+            .unwrap();
+        let claims = service.verify_access_token(&token).unwrap();
+        assert_eq!(claims.role, Some(Role::Admin));
+    }
+
+    #[test]
+    fn refresh_token_is_rejected_as_access_token_when_secrets_overlap() {
+        let mut service = token_service();
+        service.config.jwt_refresh_secret = service.config.jwt_access_secret.clone();
+        service.config.jwt_refresh_kid = service.config.jwt_access_kid.clone();
+        let refresh_token = service.generate_refresh_token("user-1").unwrap();
+        assert!(service.verify_refresh_token(&refresh_token).is_ok());
+        assert!(service.verify_access_token(&refresh_token).is_err());
+    }
+
+    #[test]
+    fn access_token_is_rejected_as_reset_token_when_secrets_overlap() {
+        let mut service = token_service();
+        service.config.password_reset_secret = service.config.jwt_access_secret.clone();
+        let reset_token = service
+            .generate_password_reset_token("user-1", "user@example.com")
+            .unwrap();
+        assert!(service.verify_password_reset_token(&reset_token).is_ok());
+        let access_token = service
+            .generate_access_token("user-1", "user@example.com", &Role::User, None, None)
+            .unwrap();
+        assert!(service.verify_password_reset_token(&access_token).is_err());
+    }
+
+    #[test]
+    fn second_login_revokes_previous_session_in_single_session_mode() {
+        let mut service = token_service();
+        assert_eq!(service.login_session_mode(), LoginSessionMode::KeepOthers);
+        service.config.one_session_per_device = true;
+        assert_eq!(service.login_session_mode(), LoginSessionMode::ReuseDevice);
+        // 单会话模式优先于同设备复用，之前的会话一律下线
+        service.config.single_active_session = true;
+        assert_eq!(service.login_session_mode(), LoginSessionMode::RevokeOthers);
+    }
+
+    fn service_with_session_limit(max: u32, strategy: SessionLimitStrategy) -> TokenService {
+        let mut service = token_service();
+        service.config.max_sessions_per_user = max;
+        service.config.session_limit_strategy = strategy;
+        service
+    }
+
+    #[test]
+    fn sixth_login_evicts_one_session_under_evict_oldest() {
+        let service = service_with_session_limit(5, SessionLimitStrategy::EvictOldest);
+        assert_eq!(service.sessions_over_limit(4).unwrap(), 0);
+        assert_eq!(service.sessions_over_limit(5).unwrap(), 1);
+        // 上限调低后多出的会话一次性撤销
+        assert_eq!(service.sessions_over_limit(7).unwrap(), 3);
+    }
+
+    #[test]
+    fn sixth_login_is_rejected_under_reject() {
+        let service = service_with_session_limit(5, SessionLimitStrategy::Reject);
+        assert_eq!(service.sessions_over_limit(4).unwrap(), 0);
+        assert!(matches!(
+            service.sessions_over_limit(5),
+            Err(Error::Auth(AuthError::SessionLimitReached))
+        ));
+    }
+
+    #[test]
+    fn zero_session_limit_means_unlimited() {
+        let service = service_with_session_limit(0, SessionLimitStrategy::Reject);
+        assert_eq!(service.sessions_over_limit(100).unwrap(), 0);
+    }
+
+    fn service_with_schema(schema: &[(&str, ExtraClaimType)]) -> TokenService {
+        let mut service = token_service();
+        service.config.extra_claims_schema = Some(
+            schema
+                .iter()
+                .map(|(key, claim_type)| (key.to_string(), *claim_type))
+                .collect(),
+        );
+        service
+    }
+
+    fn generate_with_extra(service: &TokenService, extra: serde_json::Value) -> Result<String> {
+        let extra = serde_json::from_value(extra).unwrap();
+        service.generate_access_token("user-1", "user@example.com", &Role::User, None, Some(extra))
+    }
+
+    #[test]
+    fn custom_claims_round_trip_through_encode_and_verify() {
+        let service = token_service();
+        let extra = serde_json::json!({ "plan": "pro", "seats": 5, TENANT_ID_CLAIM: "acme" });
+        let token = generate_with_extra(&service, extra).unwrap();
+        let claims = service.verify_access_token(&token).unwrap();
+        assert_eq!(claims.get_extra::<String>("plan").as_deref(), Some("pro"));
+        assert_eq!(claims.get_extra::<u32>("seats"), Some(5));
+        assert_eq!(claims.tenant_id().as_deref(), Some("acme"));
+        assert_eq!(claims.get_extra::<String>("missing"), None);
+    }
+
+    #[test]
+    fn extra_claims_are_unconstrained_without_schema() {
+        let service = token_service();
+        assert!(generate_with_extra(&service, serde_json::json!({ "anything": [1, 2] })).is_ok());
+    }
+
+    #[test]
+    fn extra_claims_matching_schema_are_accepted() {
+        let service = service_with_schema(&[("plan", ExtraClaimType::String)]);
+        let extra = serde_json::json!({ "plan": "pro", TENANT_ID_CLAIM: "acme" });
+        assert!(generate_with_extra(&service, extra).is_ok());
+    }
+
+    #[test]
+    fn unexpected_or_mistyped_extra_claims_are_rejected() {
+        let service = service_with_schema(&[("plan", ExtraClaimType::String)]);
+        assert!(generate_with_extra(&service, serde_json::json!({ "debug": true })).is_err());
+        assert!(generate_with_extra(&service, serde_json::json!({ "plan": 3 })).is_err());
+    }
+
+    #[test]
+    fn device_binding_claim_round_trips_under_a_schema() {
+        let service = service_with_schema(&[("plan", ExtraClaimType::String)]);
+        let extra = TokenService::session_extra_claims(None, Some("binding".to_string()));
+        let token = service
+            .generate_access_token("user-1", "user@example.com", &Role::User, None, extra)
+            .unwrap();
+        let claims = service.verify_access_token(&token).unwrap();
+        assert_eq!(claims.device_binding().as_deref(), Some("binding"));
+        assert_eq!(claims.tenant_id(), None);
+    }
+
+    #[test]
+    fn opaque_access_tokens_are_stored_as_hashes() {
+        let mut service = token_service();
+        service.config.access_token_format = AccessTokenFormat::Opaque;
+        let claims = service
+            .access_claims("user-1", "user@example.com", &Role::User, None, None)
+            .unwrap();
+        let access = service.issue_access_token(claims).unwrap();
+        assert!(access.token.starts_with(OPAQUE_TOKEN_PREFIX));
+        assert_eq!(
+            access.token_hash,
+            Some(TokenService::hash_opaque_token(&access.token))
+        );
+        assert!(service.verify_access_token(&access.token).is_err());
+    }
+
+    #[test]
+    fn refreshed_admin_token_keeps_admin_scopes() {
+        let service = token_service();
+        let mut user = User::new(
+            "admin-1".to_string(),
+            "Admin".to_string(),
+            "admin@example.com".to_string(),
+            "hash".to_string(),
+        );
+        user.role = Role::Admin;
+        let mut session = TokenSession::new(
+            user.id.clone(),
+            "access-jti".to_string(),
+            "refresh-jti".to_string(),
+        );
+        session.tenant_id = Some("acme".to_string());
+        let claims = service.refreshed_access_claims(&session, &user).unwrap();
+        assert_eq!(claims.role, Some(Role::Admin));
+        assert_eq!(claims.email.as_deref(), Some("admin@example.com"));
+        assert_eq!(
+            claims.scopes,
+            TokenClaims::default_scopes_for_role(&Role::Admin)
+        );
+        assert!(claims.has_scope(&TokenScope::AdminRead));
+        assert_eq!(claims.tenant_id().as_deref(), Some("acme"));
+    }
+}
@@ -1,24 +1,51 @@
-use chrono::Utc;
+use chrono::{DateTime, Duration, Utc};
 use regex::Regex;
+use std::borrow::Cow;
+use std::sync::LazyLock;
 use uuid::Uuid;
+use validator::{ValidationError, ValidationErrors};
 
 use crate::{
-    database::user::UserRepository,
-    errors::{auth::AuthError, core::Result},
-    models::{role::Role, user::User},
+    database::{oauth_connection::OAuthConnectionRepository, user::UserRepository},
+    dtos::pagination::Pagination,
+    errors::{auth::AuthError, core::Result, db::DatabaseError},
+    models::{
+        notification::NotificationPreferences,
+        oauth_connection::OAuthConnection,
+        role::Role,
+        user::{User, UserListFilter},
+    },
     state::AppState,
 };
 use std::sync::Arc;
 
+const RECOVERY_CODE_COUNT: usize = 10;
+// 恢复码是高熵随机串，不需要密码那样高的哈希成本；降低成本以便逐个比对
+const RECOVERY_CODE_HASH_COST: u32 = 6;
+
+static EMAIL_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}$").expect("valid email regex")
+});
+
+fn into_result(errors: ValidationErrors) -> Result<()> {
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.into())
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct UserService {
     user_repo: UserRepository,
+    connection_repo: OAuthConnectionRepository,
 }
 
 impl UserService {
     pub fn new() -> Self {
         Self {
             user_repo: UserRepository::new(),
+            connection_repo: OAuthConnectionRepository::new(),
         }
     }
     fn hash_password(&self, password: &str) -> Result<String> {
@@ -29,38 +56,82 @@ impl UserService {
         use bcrypt::verify;
         verify(password, hash).map_err(|_| AuthError::InvalidHashFormat.into())
     }
-    fn validate_user_input(&self, name: &str, email: &str, password: &str) -> Result<()> {
-        self.validate_name(name)?;
-        self.validate_email(email)?;
-        self.validate_password(password)?;
-        Ok(())
+    /// 姓名、邮箱和密码规则的错误一次性收集返回，与 DTO 层的校验保持一致；
+    /// 泄露检查需要外部请求，只在其余规则全部通过后执行
+    async fn validate_user_input(
+        &self,
+        app_state: &AppState,
+        name: &str,
+        email: &str,
+        password: &str,
+    ) -> Result<()> {
+        let mut errors = ValidationErrors::new();
+        self.collect_name_errors(name, &mut errors);
+        self.collect_email_errors(email, &mut errors);
+        self.collect_password_errors(app_state, "password", password, &mut errors);
+        if !errors.is_empty() {
+            return Err(errors.into());
+        }
+        self.check_breached(app_state, password).await
+    }
+    // 密码规则统一由配置中的 PasswordPolicy 校验，DTO 只检查非空
+    async fn validate_password(
+        &self,
+        app_state: &AppState,
+        field: &'static str,
+        password: &str,
+    ) -> Result<()> {
+        let mut errors = ValidationErrors::new();
+        self.collect_password_errors(app_state, field, password, &mut errors);
+        into_result(errors)?;
+        self.check_breached(app_state, password).await
     }
-    fn validate_name(&self, name: &str) -> Result<()> {
+    fn collect_name_errors(&self, name: &str, errors: &mut ValidationErrors) {
         if name.trim().is_empty() {
-            return Err(AuthError::InvalidCredentials.into());
+            errors.add(
+                "name",
+                ValidationError::new("required")
+                    .with_message(Cow::Borrowed("Name cannot be empty")),
+            );
+        } else if name.len() > 100 {
+            let mut error = ValidationError::new("length")
+                .with_message(Cow::Borrowed("Name must not be more than 100 characters"));
+            error.add_param(Cow::Borrowed("max"), &100);
+            errors.add("name", error);
         }
-        if name.len() > 100 {
-            return Err(AuthError::InvalidCredentials.into());
-        }
-        Ok(())
     }
-    fn validate_email(&self, email: &str) -> Result<()> {
-        let email_regex = Regex::new(r"^[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}$")
-            .map_err(|_| AuthError::InvalidCredentials)?;
-        if !email_regex.is_match(email) {
-            return Err(AuthError::InvalidCredentials.into());
+    fn collect_email_errors(&self, email: &str, errors: &mut ValidationErrors) {
+        if !EMAIL_REGEX.is_match(email) {
+            errors.add(
+                "email",
+                ValidationError::new("email").with_message(Cow::Borrowed("Invalid email format")),
+            );
         }
-        Ok(())
     }
-    fn validate_password(&self, password: &str) -> Result<()> {
+    fn collect_password_errors(
+        &self,
+        app_state: &AppState,
+        field: &'static str,
+        password: &str,
+        errors: &mut ValidationErrors,
+    ) {
         if password.is_empty() {
-            return Err(AuthError::EmptyPassword.into());
-        }
-        if password.len() < 8 {
-            return Err(AuthError::InvalidCredentials.into());
+            errors.add(
+                field,
+                ValidationError::new("required")
+                    .with_message(Cow::Borrowed("Password cannot be empty")),
+            );
+            return;
         }
-        if password.len() > 128 {
-            return Err(AuthError::password_too_long(128).into());
+        app_state
+            .env
+            .password_policy
+            .collect_errors(field, password, errors);
+    }
+    async fn check_breached(&self, app_state: &AppState, password: &str) -> Result<()> {
+        let policy = &app_state.env.password_policy;
+        if policy.check_breached && app_state.breach_service.is_compromised(password).await {
+            return Err(AuthError::CompromisedPassword.into());
         }
         Ok(())
     }
@@ -70,50 +141,218 @@ impl UserService {
         name: String,
         email: String,
         password: String,
+        tenant_id: Option<String>,
     ) -> Result<User> {
-        self.validate_user_input(&name, &email, &password)?;
+        let mut user = User::new(Uuid::new_v4().to_string(), name, email, password);
+        user.tenant_id = tenant_id;
+        self.insert_user(app_state, user).await
+    }
+    pub async fn create_admin(
+        &self,
+        app_state: Arc<AppState>,
+        name: String,
+        email: String,
+        password: String,
+    ) -> Result<User> {
+        let mut user = User::new(Uuid::new_v4().to_string(), name, email, password);
+        user.role = Role::Admin;
+        user.verified = true;
+        self.insert_user(app_state, user).await
+    }
+    /// 批量生成测试用户，所有用户共用一个随机密码，只哈希一次以免 bcrypt 拖慢批量写入
+    #[cfg(debug_assertions)]
+    pub async fn seed_users(
+        &self,
+        app_state: Arc<AppState>,
+        count: u32,
+        tenant_id: Option<String>,
+    ) -> Result<(Vec<String>, String)> {
+        let password = Uuid::new_v4().simple().to_string();
+        let password_hash = self.hash_password(&password)?;
+        let users: Vec<User> = (0..count)
+            .map(|_| {
+                let id = Uuid::new_v4().to_string();
+                let suffix = id[..8].to_string();
+                let mut user = User::new(
+                    id,
+                    format!("Seed User {suffix}"),
+                    format!("seed-{suffix}@example.com"),
+                    password_hash.clone(),
+                );
+                user.verified = true;
+                user.tenant_id = tenant_id.clone();
+                user
+            })
+            .collect();
+        let ids = users.iter().map(|user| user.id.clone()).collect();
+        self.user_repo.create_many(app_state, users).await?;
+        Ok((ids, password))
+    }
+    /// 仅在系统中还没有任何管理员时创建，已存在时返回 `None`
+    pub async fn ensure_admin(
+        &self,
+        app_state: Arc<AppState>,
+        name: String,
+        email: String,
+        password: String,
+    ) -> Result<Option<User>> {
         if self
             .user_repo
-            .email_exists(app_state.clone(), email.clone())
+            .count_by_role(app_state.clone(), Role::Admin)
+            .await?
+            > 0
+        {
+            return Ok(None);
+        }
+        self.create_admin(app_state, name, email, password)
+            .await
+            .map(Some)
+    }
+    // 传入的 user.password 为明文，校验通过后替换为哈希再写入
+    async fn insert_user(&self, app_state: Arc<AppState>, mut user: User) -> Result<User> {
+        self.validate_user_input(&app_state, &user.name, &user.email, &user.password)
+            .await?;
+        if self
+            .user_repo
+            .email_exists(
+                app_state.clone(),
+                user.email.clone(),
+                user.tenant_id.clone(),
+            )
             .await?
         {
             return Err(AuthError::EmailAlreadyExists.into());
         }
-        let password_hash = self.hash_password(&password)?;
-        let user = User {
-            id: Uuid::new_v4().to_string(),
-            name,
-            email,
-            password: password_hash,
-            role: Role::User,
-            verified: false,
-            created_at: Some(Utc::now()),
-            updated_at: Some(Utc::now()),
-        };
-        self.user_repo.create(app_state, user).await
+        user.password = self.hash_password(&user.password)?;
+        // 上面的查重只为尽早返回，并发注册由仓储层的事务兜底
+        self.user_repo
+            .create(app_state, user)
+            .await?
+            .ok_or_else(|| AuthError::EmailAlreadyExists.into())
     }
     pub async fn authenticate_user(
         &self,
         app_state: Arc<AppState>,
         email: String,
         password: String,
+        tenant_id: Option<String>,
     ) -> Result<User> {
         let user = self
             .user_repo
-            .find_by_email(app_state, email)
+            .find_by_email(app_state.clone(), email, tenant_id)
             .await?
             .ok_or(AuthError::InvalidCredentials)?;
+        if user.is_locked() {
+            return Err(AuthError::AccountLocked.into());
+        }
+        if !self.verify_password(&password, &user.password)? {
+            self.record_failed_login(app_state, user).await?;
+            return Err(AuthError::InvalidCredentials.into());
+        }
+        // 密码校验之后再判断，避免通过这个错误探测某个邮箱是否申请了删除
+        if user.deleted_at.is_some() {
+            return Err(AuthError::AccountPendingDeletion.into());
+        }
+        self.user_repo
+            .update_last_login(app_state, user.id.clone())
+            .await
+    }
+    // 连续失败达到上限后锁定账户，计数从零重新开始
+    async fn record_failed_login(&self, app_state: Arc<AppState>, user: User) -> Result<User> {
+        let lockout = &app_state.env.lockout_config;
+        let attempts = user.failed_login_attempts + 1;
+        let (attempts, locked_until) = if attempts >= lockout.max_failed_attempts {
+            (
+                0,
+                Some(Utc::now() + Duration::minutes(lockout.lockout_minutes)),
+            )
+        } else {
+            (attempts, None)
+        };
+        self.user_repo
+            .update_failed_logins(app_state, user.id, attempts, locked_until)
+            .await
+    }
+    /// 生成一组新的恢复码并替换旧的，明文只在这里返回一次
+    pub async fn regenerate_recovery_codes(
+        &self,
+        app_state: Arc<AppState>,
+        user_id: String,
+        password: String,
+    ) -> Result<Vec<String>> {
+        let user = self
+            .user_repo
+            .find_by_id(app_state.clone(), user_id)
+            .await?
+            .ok_or(AuthError::UserNoLongerExists)?;
         if !self.verify_password(&password, &user.password)? {
             return Err(AuthError::InvalidCredentials.into());
         }
-        Ok(user)
+        let codes: Vec<String> = (0..RECOVERY_CODE_COUNT)
+            .map(|_| generate_recovery_code())
+            .collect();
+        let hashes = codes
+            .iter()
+            .map(|code| {
+                bcrypt::hash(normalize_recovery_code(code), RECOVERY_CODE_HASH_COST)
+                    .map_err(|_| AuthError::HashingError.into())
+            })
+            .collect::<Result<Vec<String>>>()?;
+        self.user_repo
+            .update_recovery_codes(app_state, user.id, hashes)
+            .await?;
+        Ok(codes)
+    }
+    /// 用恢复码代替邮箱找回账户，恢复码使用后立即作废，返回用户和剩余恢复码数量
+    pub async fn recover_account(
+        &self,
+        app_state: Arc<AppState>,
+        email: String,
+        recovery_code: String,
+        tenant_id: Option<String>,
+    ) -> Result<(User, usize)> {
+        let user = self
+            .user_repo
+            .find_by_email(app_state.clone(), email, tenant_id)
+            .await?
+            .ok_or(AuthError::InvalidCredentials)?;
+        if user.is_locked() {
+            return Err(AuthError::AccountLocked.into());
+        }
+        let recovery_code = normalize_recovery_code(&recovery_code);
+        let mut matched = None;
+        for hash in &user.recovery_codes {
+            if self.verify_password(&recovery_code, hash)? {
+                matched = Some(hash.clone());
+                break;
+            }
+        }
+        // 错误的恢复码与错误的密码共用失败计数和锁定策略
+        let Some(hash) = matched else {
+            self.record_failed_login(app_state, user).await?;
+            return Err(AuthError::InvalidCredentials.into());
+        };
+        // 与登录一致：恢复码校验之后再判断，且不消耗恢复码，需先撤销删除申请
+        if user.deleted_at.is_some() {
+            return Err(AuthError::AccountPendingDeletion.into());
+        }
+        let user = self
+            .user_repo
+            .consume_recovery_code(app_state, user.id, hash)
+            .await?
+            .ok_or(AuthError::InvalidCredentials)?;
+        let remaining = user.recovery_codes.len();
+        Ok((user, remaining))
     }
     pub async fn find_by_email(
         &self,
         app_state: Arc<AppState>,
         email: String,
+        tenant_id: Option<String>,
     ) -> Result<Option<User>> {
-        self.user_repo.find_by_email(app_state, email).await
+        self.user_repo
+            .find_by_email(app_state, email, tenant_id)
+            .await
     }
     pub async fn find_by_id(
         &self,
@@ -122,7 +361,34 @@ impl UserService {
     ) -> Result<Option<User>> {
         self.user_repo.find_by_id(app_state, user_id).await
     }
-    pub async fn verify_email(&self, app_state: Arc<AppState>, user_id: String) -> Result<User> {
+    pub async fn unlock_user(&self, app_state: Arc<AppState>, user_id: String) -> Result<User> {
+        self.user_repo
+            .update_failed_logins(app_state, user_id, 0, None)
+            .await
+    }
+    pub async fn update_role(
+        &self,
+        app_state: Arc<AppState>,
+        user_id: String,
+        role: Role,
+    ) -> Result<User> {
+        self.user_repo.update_role(app_state, user_id, role).await
+    }
+    /// 令牌中的邮箱必须是账户当前的邮箱；修改邮箱的确认令牌指向新邮箱，不能用来验证旧邮箱
+    pub async fn verify_email(
+        &self,
+        app_state: Arc<AppState>,
+        user_id: String,
+        email: Option<String>,
+    ) -> Result<User> {
+        let user = self
+            .user_repo
+            .find_by_id(app_state.clone(), user_id.clone())
+            .await?
+            .ok_or(AuthError::UserNoLongerExists)?;
+        if email.as_deref() != Some(user.email.as_str()) {
+            return Err(AuthError::InvalidToken.into());
+        }
         self.user_repo
             .update_verification_status(app_state, user_id, true)
             .await
@@ -142,11 +408,9 @@ impl UserService {
         if !self.verify_password(&current_password, &user.password)? {
             return Err(AuthError::InvalidCredentials.into());
         }
-        self.validate_password(&new_password)?;
-        let new_password_hash = self.hash_password(&new_password)?;
-        self.user_repo
-            .update_password(app_state, user_id, new_password_hash)
-            .await
+        self.validate_password(&app_state, "new_password", &new_password)
+            .await?;
+        self.replace_password(app_state, user, &new_password).await
     }
     pub async fn reset_password(
         &self,
@@ -154,10 +418,40 @@ impl UserService {
         user_id: String,
         new_password: String,
     ) -> Result<User> {
-        self.validate_password(&new_password)?;
-        let new_password_hash = self.hash_password(&new_password)?;
+        self.validate_password(&app_state, "new_password", &new_password)
+            .await?;
+        let user = self
+            .user_repo
+            .find_by_id(app_state.clone(), user_id)
+            .await?
+            .ok_or(AuthError::UserNoLongerExists)?;
+        self.replace_password(app_state, user, &new_password).await
+    }
+    // 当前密码加上历史记录共 history_depth 个密码不能重复使用
+    async fn replace_password(
+        &self,
+        app_state: Arc<AppState>,
+        user: User,
+        new_password: &str,
+    ) -> Result<User> {
+        let depth = app_state.env.password_policy.history_depth;
+        let mut password_history = Vec::new();
+        if depth > 0 {
+            for hash in
+                std::iter::once(&user.password).chain(user.password_history.iter().take(depth - 1))
+            {
+                if self.verify_password(new_password, hash)? {
+                    return Err(AuthError::PasswordReused.into());
+                }
+            }
+            password_history = std::iter::once(user.password)
+                .chain(user.password_history)
+                .take(depth - 1)
+                .collect();
+        }
+        let new_password_hash = self.hash_password(new_password)?;
         self.user_repo
-            .update_password(app_state, user_id, new_password_hash)
+            .update_password(app_state, user.id, new_password_hash, password_history)
             .await
     }
     pub async fn update_profile(
@@ -167,6 +461,10 @@ impl UserService {
         name: Option<String>,
         email: Option<String>,
     ) -> Result<User> {
+        let mut errors = ValidationErrors::new();
+        if let Some(ref new_name) = name {
+            self.collect_name_errors(new_name, &mut errors);
+        }
         if let Some(ref new_email) = email {
             let current_user = self
                 .user_repo
@@ -174,24 +472,288 @@ impl UserService {
                 .await?
                 .ok_or(AuthError::UserNoLongerExists)?;
             if new_email != &current_user.email {
-                if self
-                    .user_repo
-                    .email_exists(app_state.clone(), new_email.clone())
-                    .await?
+                self.collect_email_errors(new_email, &mut errors);
+                if errors.is_empty()
+                    && self
+                        .user_repo
+                        .email_exists(
+                            app_state.clone(),
+                            new_email.clone(),
+                            current_user.tenant_id.clone(),
+                        )
+                        .await?
                 {
                     return Err(AuthError::EmailAlreadyExists.into());
                 }
-                self.validate_email(new_email)?;
             }
         }
-        if let Some(ref new_name) = name {
-            self.validate_name(new_name)?;
-        }
+        into_result(errors)?;
         self.user_repo
             .update_profile(app_state, user_id, name, email)
             .await
     }
-    pub async fn delete_user(&self, app_state: Arc<AppState>, user_id: String) -> Result<()> {
-        self.user_repo.delete(app_state, user_id).await
+    /// 新邮箱只记录为 pending_email，需通过验证链接确认后才生效
+    pub async fn request_email_change(
+        &self,
+        app_state: Arc<AppState>,
+        user_id: String,
+        new_email: String,
+    ) -> Result<User> {
+        self.update_profile(app_state, user_id, None, Some(new_email))
+            .await
+    }
+    pub async fn confirm_email_change(
+        &self,
+        app_state: Arc<AppState>,
+        user_id: String,
+        email: String,
+    ) -> Result<User> {
+        let user = self
+            .user_repo
+            .find_by_id(app_state.clone(), user_id.clone())
+            .await?
+            .ok_or(AuthError::UserNoLongerExists)?;
+        if user.pending_email.as_deref() != Some(email.as_str()) {
+            return Err(AuthError::InvalidToken.into());
+        }
+        // 等待确认期间邮箱可能已被其他账户注册
+        if self
+            .user_repo
+            .email_exists(app_state.clone(), email.clone(), user.tenant_id.clone())
+            .await?
+        {
+            return Err(AuthError::EmailAlreadyExists.into());
+        }
+        self.user_repo
+            .confirm_email_change(app_state, user_id, email)
+            .await?
+            .ok_or_else(|| AuthError::InvalidToken.into())
+    }
+    pub async fn update_notification_preferences(
+        &self,
+        app_state: Arc<AppState>,
+        user_id: String,
+        preferences: NotificationPreferences,
+    ) -> Result<User> {
+        self.user_repo
+            .update_notification_preferences(app_state, user_id, preferences)
+            .await
+    }
+    /// 申请删除账户：只做标记，宽限期结束后才真正删除，返回计划删除的时间
+    pub async fn schedule_deletion(
+        &self,
+        app_state: Arc<AppState>,
+        user_id: String,
+        password: String,
+    ) -> Result<DateTime<Utc>> {
+        let user = self
+            .user_repo
+            .find_by_id(app_state.clone(), user_id)
+            .await?
+            .ok_or(AuthError::UserNoLongerExists)?;
+        if !self.verify_password(&password, &user.password)? {
+            return Err(AuthError::InvalidCredentials.into());
+        }
+        let deleted_at = user.deleted_at.unwrap_or_else(Utc::now);
+        self.user_repo
+            .set_deleted_at(app_state.clone(), user.id, Some(deleted_at))
+            .await?;
+        Ok(deleted_at + grace_period(&app_state))
+    }
+    /// 宽限期内凭邮箱和密码撤销删除申请
+    pub async fn reactivate_account(
+        &self,
+        app_state: Arc<AppState>,
+        email: String,
+        password: String,
+        tenant_id: Option<String>,
+    ) -> Result<User> {
+        let user = self
+            .user_repo
+            .find_by_email(app_state.clone(), email, tenant_id)
+            .await?
+            .ok_or(AuthError::InvalidCredentials)?;
+        if user.is_locked() {
+            return Err(AuthError::AccountLocked.into());
+        }
+        if !self.verify_password(&password, &user.password)? {
+            self.record_failed_login(app_state, user).await?;
+            return Err(AuthError::InvalidCredentials.into());
+        }
+        // 宽限期已过、只是还没被后台任务清理的账户不能再恢复
+        match user.deleted_at {
+            Some(deleted_at) if deleted_at + grace_period(&app_state) > Utc::now() => {}
+            _ => return Err(AuthError::InvalidCredentials.into()),
+        }
+        self.user_repo
+            .set_deleted_at(app_state, user.id, None)
+            .await
+    }
+    /// 彻底删除宽限期已过的账户，返回删除的数量
+    pub async fn purge_deleted_users(&self, app_state: Arc<AppState>) -> Result<usize> {
+        let cutoff = Utc::now() - grace_period(&app_state);
+        let user_ids = self
+            .user_repo
+            .find_ids_deleted_before(app_state.clone(), cutoff)
+            .await?;
+        let purged = user_ids.len();
+        for user_id in user_ids {
+            self.user_repo.delete(app_state.clone(), user_id).await?;
+        }
+        Ok(purged)
+    }
+    pub async fn list_connections(
+        &self,
+        app_state: Arc<AppState>,
+        user_id: String,
+    ) -> Result<Vec<OAuthConnection>> {
+        self.connection_repo.find_by_user(app_state, user_id).await
+    }
+    /// 只通过第三方登录注册的账户没有密码，不允许解绑最后一个提供方，否则将无法再登录
+    pub async fn unlink_connection(
+        &self,
+        app_state: Arc<AppState>,
+        user_id: String,
+        provider: String,
+    ) -> Result<()> {
+        let provider = provider.to_lowercase();
+        let user = self
+            .user_repo
+            .find_by_id(app_state.clone(), user_id.clone())
+            .await?
+            .ok_or(AuthError::UserNoLongerExists)?;
+        let connections = self
+            .connection_repo
+            .find_by_user(app_state.clone(), user_id.clone())
+            .await?;
+        if !connections.iter().any(|c| c.provider == provider) {
+            return Err(DatabaseError::NotFound("Connection not found".to_string()).into());
+        }
+        let has_other_provider = connections.iter().any(|c| c.provider != provider);
+        if user.password.is_empty() && !has_other_provider {
+            return Err(AuthError::LastLoginMethod.into());
+        }
+        self.connection_repo
+            .delete_by_provider(app_state, user_id, provider)
+            .await?;
+        Ok(())
+    }
+    pub async fn list_users(
+        &self,
+        app_state: Arc<AppState>,
+        filter: UserListFilter,
+        pagination: Pagination,
+    ) -> Result<(Vec<User>, u64)> {
+        self.user_repo
+            .list(
+                app_state,
+                filter,
+                pagination.start(),
+                pagination.limit,
+                pagination.cursor,
+            )
+            .await
+    }
+    pub async fn count_users(
+        &self,
+        app_state: Arc<AppState>,
+        tenant_id: Option<String>,
+    ) -> Result<u64> {
+        self.user_repo.count_all(app_state, tenant_id).await
+    }
+    pub async fn count_verified_users(
+        &self,
+        app_state: Arc<AppState>,
+        tenant_id: Option<String>,
+    ) -> Result<u64> {
+        self.user_repo.count_verified(app_state, tenant_id).await
+    }
+    pub async fn count_users_by_role(
+        &self,
+        app_state: Arc<AppState>,
+        tenant_id: Option<String>,
+        role: Role,
+    ) -> Result<u64> {
+        self.user_repo
+            .count_by_role_in_tenant(app_state, tenant_id, role)
+            .await
+    }
+    pub async fn count_users_created_since(
+        &self,
+        app_state: Arc<AppState>,
+        tenant_id: Option<String>,
+        since: DateTime<Utc>,
+    ) -> Result<u64> {
+        self.user_repo
+            .count_created_since(app_state, tenant_id, since)
+            .await
+    }
+}
+
+fn grace_period(app_state: &AppState) -> Duration {
+    Duration::days(app_state.env.account_deletion_config.grace_period_days)
+}
+
+// 形如 XXXX-XXXX-XXXX 的 12 位十六进制随机串
+fn generate_recovery_code() -> String {
+    let random = Uuid::new_v4().simple().to_string().to_uppercase();
+    format!("{}-{}-{}", &random[..4], &random[4..8], &random[8..12])
+}
+
+// 忽略用户输入中的分隔符、空白和大小写
+fn normalize_recovery_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::password::PasswordPolicy;
+
+    fn policy() -> PasswordPolicy {
+        PasswordPolicy {
+            min_length: 8,
+            max_length: 64,
+            require_uppercase: false,
+            require_lowercase: false,
+            require_digit: false,
+            require_symbol: false,
+            check_breached: false,
+            history_depth: 0,
+            breach_check_timeout_ms: 2000,
+        }
+    }
+
+    fn password_errors(password: &str) -> ValidationErrors {
+        let mut errors = ValidationErrors::new();
+        policy().collect_errors("password", password, &mut errors);
+        errors
+    }
+
+    #[test]
+    fn forty_char_passphrase_is_accepted_end_to_end() {
+        let passphrase = "correct horse battery staple on the moon";
+        assert_eq!(passphrase.chars().count(), 40);
+        assert!(password_errors(passphrase).is_empty());
+
+        let service = UserService::new();
+        let hash = service.hash_password(passphrase).unwrap();
+        assert!(service.verify_password(passphrase, &hash).unwrap());
+        assert!(!service.verify_password(&passphrase[..39], &hash).unwrap());
+    }
+
+    #[test]
+    fn multibyte_password_over_bcrypt_limit_is_rejected() {
+        // 30 个汉字只有 30 个字符，但 UTF-8 编码后是 90 字节
+        let password = "密".repeat(30);
+        let errors = password_errors(&password);
+        let codes: Vec<_> = errors.field_errors()["password"]
+            .iter()
+            .map(|error| error.code.as_ref())
+            .collect();
+        assert_eq!(codes, ["bytes"]);
     }
 }
@@ -1,13 +1,46 @@
 use crate::{
     config::Config,
-    services::{token::TokenService, user::UserService},
+    database::tenant::{CURRENT_TENANT_DB, TenantDatabases},
+    models::session_event::SessionEvent,
+    services::{
+        breach::BreachService, feature_flags::FeatureFlags, maintenance::Maintenance,
+        rate_limit::UserRateLimiter, token::TokenService, user::UserService,
+    },
+};
+use chrono::{DateTime, Utc};
+use std::sync::{
+    Arc,
+    atomic::{AtomicI64, AtomicUsize},
 };
 use surrealdb::{Surreal, engine::remote::ws::Client};
+use tokio::sync::broadcast;
 
 #[derive(Debug, Clone)]
 pub struct AppState {
     pub env: Config,
-    pub db: Surreal<Client>,
+    /// 根数据库连接；查询应通过 `db()` 获取连接，以便在按数据库隔离租户时切换到租户库
+    pub root_db: Surreal<Client>,
+    pub tenant_databases: TenantDatabases,
     pub token_service: TokenService,
     pub user_service: UserService,
+    pub breach_service: BreachService,
+    pub feature_flags: FeatureFlags,
+    pub maintenance: Maintenance,
+    pub user_rate_limiter: UserRateLimiter,
+    pub session_events: broadcast::Sender<SessionEvent>,
+    /// 后台会话清理任务最近一次成功运行的 Unix 时间戳，0 表示尚未运行
+    pub cleanup_last_run: Arc<AtomicI64>,
+    /// 正在处理的 HTTP 请求数
+    pub in_flight_requests: Arc<AtomicUsize>,
+    /// 进程启动时间，用于计算运行时长
+    pub started_at: DateTime<Utc>,
+}
+
+impl AppState {
+    /// 当前请求使用的数据库连接：处于租户作用域内时返回租户库，否则返回根数据库
+    pub fn db(&self) -> Surreal<Client> {
+        CURRENT_TENANT_DB
+            .try_with(Surreal::clone)
+            .unwrap_or_else(|_| self.root_db.clone())
+    }
 }
@@ -0,0 +1,124 @@
+use crate::config::database::TenantIsolation;
+use crate::database::tenant::CURRENT_TENANT_DB;
+use crate::services::user::UserService;
+use crate::state::AppState;
+use chrono::Utc;
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+use tracing::{Instrument, error, info, info_span, warn};
+use uuid::Uuid;
+
+pub fn spawn_token_cleanup(app_state: Arc<AppState>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let period =
+            Duration::from_secs(app_state.env.token_config.token_cleanup_interval.max(1) as u64);
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            // 每次运行使用独立的 span 和 run_id，便于和请求日志区分
+            let run_id = Uuid::new_v4();
+            for tenant_id in cleanup_targets(&app_state) {
+                let tenant_id = tenant_id.as_deref();
+                let run = async {
+                    run_cleanup(app_state.clone())
+                        .instrument(info_span!("token_cleanup", %run_id, tenant_id))
+                        .await;
+                    purge_deleted_accounts(app_state.clone())
+                        .instrument(info_span!("account_purge", %run_id, tenant_id))
+                        .await;
+                };
+                let Some(tenant_id) = tenant_id else {
+                    run.await;
+                    continue;
+                };
+                match app_state
+                    .tenant_databases
+                    .connection(app_state.clone(), tenant_id)
+                    .await
+                {
+                    Ok(db) => CURRENT_TENANT_DB.scope(db, run).await,
+                    Err(err) => error!(
+                        tenant_id,
+                        error = %err,
+                        error_code = %err.error_code(),
+                        "Tenant database unavailable, cleanup skipped"
+                    ),
+                }
+            }
+        }
+    })
+}
+
+// 根数据库总是需要清理；按数据库隔离租户时，每个已登记租户的库也各自清理一遍
+fn cleanup_targets(app_state: &AppState) -> Vec<Option<String>> {
+    let mut targets = vec![None];
+    if app_state.env.db_config.tenant_isolation == TenantIsolation::Database {
+        targets.extend(app_state.env.db_config.tenants.iter().cloned().map(Some));
+    }
+    targets
+}
+
+async fn run_cleanup(app_state: Arc<AppState>) {
+    let started = Instant::now();
+    match app_state
+        .token_service
+        .cleanup_expired_sessions(app_state.clone())
+        .await
+    {
+        Ok(cleaned_count) => {
+            app_state
+                .cleanup_last_run
+                .store(Utc::now().timestamp(), Ordering::Relaxed);
+            info!(
+                cleaned_count,
+                duration_ms = started.elapsed().as_millis() as u64,
+                "Expired token sessions cleaned up"
+            );
+            warn_if_session_table_oversized(app_state).await;
+        }
+        Err(err) => {
+            error!(
+                error = %err,
+                error_code = %err.error_code(),
+                duration_ms = started.elapsed().as_millis() as u64,
+                "Token session cleanup failed"
+            );
+        }
+    }
+}
+
+// 申请删除且宽限期已过的账户随会话清理一起彻底删除
+async fn purge_deleted_accounts(app_state: Arc<AppState>) {
+    match UserService::new().purge_deleted_users(app_state).await {
+        Ok(0) => {}
+        Ok(purged_count) => info!(
+            purged_count,
+            "Accounts past the deletion grace period purged"
+        ),
+        Err(err) => {
+            error!(
+                error = %err,
+                error_code = %err.error_code(),
+                "Account purge failed"
+            );
+        }
+    }
+}
+
+// 清理后仍然超过阈值，说明会话增长速度超过了清理速度
+async fn warn_if_session_table_oversized(app_state: Arc<AppState>) {
+    let threshold = app_state.env.token_config.session_table_alert_threshold;
+    if let Ok(total_sessions) = app_state
+        .token_service
+        .count_all_sessions(app_state.clone())
+        .await
+        && total_sessions > threshold
+    {
+        warn!(
+            total_sessions,
+            threshold, "token_sessions table exceeds the alert threshold"
+        );
+    }
+}
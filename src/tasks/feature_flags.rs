@@ -0,0 +1,31 @@
+use crate::state::AppState;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::warn;
+
+pub fn spawn_feature_flag_refresh(app_state: Arc<AppState>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let period = Duration::from_secs(app_state.env.feature_flag_config.refresh_interval.max(1));
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            // 功能开关和维护状态只存放在根数据库，无需按租户刷新；刷新失败时继续使用上一次的缓存
+            if let Err(err) = app_state.feature_flags.refresh(app_state.clone()).await {
+                warn!(
+                    error = %err,
+                    error_code = %err.error_code(),
+                    "Feature flag refresh failed"
+                );
+            }
+            // 维护状态同样需要在多实例之间同步，复用同一个刷新周期
+            if let Err(err) = app_state.maintenance.refresh(app_state.clone()).await {
+                warn!(
+                    error = %err,
+                    error_code = %err.error_code(),
+                    "Maintenance status refresh failed"
+                );
+            }
+        }
+    })
+}